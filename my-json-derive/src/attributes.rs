@@ -0,0 +1,524 @@
+use proc_macro2::Span;
+use syn::{
+    ext::IdentExt, spanned::Spanned, Attribute, DeriveInput, ExprPath, Fields, FieldsNamed, Ident,
+    LitStr, Type,
+};
+
+use crate::rename_rule::RenameRule;
+
+/// `#[json(...)]` on the struct or enum itself.
+#[derive(Default)]
+pub struct ContainerAttributes {
+    pub rename_all: Option<RenameRule>,
+    pub tag: Option<String>,
+    pub content: Option<String>,
+    pub untagged: bool,
+}
+
+impl ContainerAttributes {
+    pub fn parse(attrs: &[Attribute]) -> syn::Result<Self> {
+        let mut result = Self::default();
+
+        for attr in json_attributes(attrs) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("rename_all") {
+                    let value: LitStr = meta.value()?.parse()?;
+                    result.rename_all = Some(parse_rename_rule(&value)?);
+                    return Ok(());
+                }
+
+                if meta.path.is_ident("tag") {
+                    let value: LitStr = meta.value()?.parse()?;
+                    result.tag = Some(value.value());
+                    return Ok(());
+                }
+
+                if meta.path.is_ident("content") {
+                    let value: LitStr = meta.value()?.parse()?;
+                    result.content = Some(value.value());
+                    return Ok(());
+                }
+
+                if meta.path.is_ident("untagged") {
+                    result.untagged = true;
+                    return Ok(());
+                }
+
+                Err(meta.error("unknown my-json container attribute"))
+            })?;
+        }
+
+        Ok(result)
+    }
+
+    /// How an enum with these attributes is put on the wire.
+    pub fn enum_representation(&self, span: Span) -> syn::Result<EnumRepresentation> {
+        match (&self.tag, &self.content, self.untagged) {
+            (None, None, false) => Ok(EnumRepresentation::External),
+            (Some(tag), None, false) => Ok(EnumRepresentation::Internal { tag: tag.clone() }),
+            (Some(tag), Some(content), false) => Ok(EnumRepresentation::Adjacent {
+                tag: tag.clone(),
+                content: content.clone(),
+            }),
+            (None, None, true) => Ok(EnumRepresentation::Untagged),
+            (None, Some(_), _) => Err(syn::Error::new(span, "`content` requires `tag`")),
+            _ => Err(syn::Error::new(
+                span,
+                "`untagged` can not be combined with `tag` or `content`",
+            )),
+        }
+    }
+}
+
+/// The wire form of an enum.
+pub enum EnumRepresentation {
+    /// `"Unit"`, `{"Variant":content}` - the default.
+    External,
+    /// `{"tag":"Variant", ...fields}` - `#[json(tag = "...")]`.
+    Internal { tag: String },
+    /// `{"tag":"Variant","content":content}` - `#[json(tag = "...", content = "...")]`.
+    Adjacent { tag: String, content: String },
+    /// Just the content, `null` for a unit variant - `#[json(untagged)]`. Reading tries the
+    /// variants in declaration order.
+    Untagged,
+}
+
+/// `#[json(...)]` on an enum variant.
+#[derive(Default)]
+pub struct VariantAttributes {
+    pub rename: Option<String>,
+    pub rename_all: Option<RenameRule>,
+}
+
+impl VariantAttributes {
+    pub fn parse(attrs: &[Attribute]) -> syn::Result<Self> {
+        let mut result = Self::default();
+
+        for attr in json_attributes(attrs) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("rename") {
+                    let value: LitStr = meta.value()?.parse()?;
+                    result.rename = Some(value.value());
+                    return Ok(());
+                }
+
+                if meta.path.is_ident("rename_all") {
+                    let value: LitStr = meta.value()?.parse()?;
+                    result.rename_all = Some(parse_rename_rule(&value)?);
+                    return Ok(());
+                }
+
+                Err(meta.error("unknown my-json variant attribute"))
+            })?;
+        }
+
+        Ok(result)
+    }
+}
+
+/// What a field falls back to when its key is absent.
+pub enum FieldDefault {
+    /// `#[json(default)]` - `Default::default()`.
+    Trait,
+    /// `#[json(default = "path")]` - a `fn() -> T`.
+    Path(ExprPath),
+}
+
+/// `#[json(...)]` on a field.
+#[derive(Default)]
+pub struct FieldAttributes {
+    pub rename: Option<String>,
+    pub skip: bool,
+    pub default: Option<FieldDefault>,
+    pub flatten: bool,
+    pub skip_serializing_if: Option<ExprPath>,
+    pub lenient: bool,
+}
+
+impl FieldAttributes {
+    pub fn parse(attrs: &[Attribute]) -> syn::Result<Self> {
+        let mut result = Self::default();
+
+        for attr in json_attributes(attrs) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("rename") {
+                    let value: LitStr = meta.value()?.parse()?;
+                    result.rename = Some(value.value());
+                    return Ok(());
+                }
+
+                if meta.path.is_ident("skip") {
+                    result.skip = true;
+                    return Ok(());
+                }
+
+                if meta.path.is_ident("default") {
+                    if meta.input.peek(syn::Token![=]) {
+                        let value: LitStr = meta.value()?.parse()?;
+                        result.default = Some(FieldDefault::Path(value.parse()?));
+                    } else {
+                        result.default = Some(FieldDefault::Trait);
+                    }
+                    return Ok(());
+                }
+
+                if meta.path.is_ident("flatten") {
+                    result.flatten = true;
+                    return Ok(());
+                }
+
+                if meta.path.is_ident("skip_serializing_if") {
+                    let value: LitStr = meta.value()?.parse()?;
+                    result.skip_serializing_if = Some(value.parse()?);
+                    return Ok(());
+                }
+
+                if meta.path.is_ident("lenient") {
+                    result.lenient = true;
+                    return Ok(());
+                }
+
+                Err(meta.error("unknown my-json field attribute"))
+            })?;
+        }
+
+        Ok(result)
+    }
+}
+
+/// A named field together with the key it has on the wire.
+pub struct JsonField<'s> {
+    pub ident: &'s Ident,
+    pub ty: &'s Type,
+    pub json_name: String,
+    pub attrs: FieldAttributes,
+}
+
+impl JsonField<'_> {
+    /// Whether the field owns a key of its own - `skip` and `flatten` fields do not.
+    pub fn is_keyed(&self) -> bool {
+        !self.attrs.skip && !self.attrs.flatten
+    }
+}
+
+/// The fields of a struct with named fields - the only struct shape the derives support, since
+/// only that shape has an obvious JSON object form.
+pub fn get_struct_fields(input: &DeriveInput) -> syn::Result<Vec<JsonField<'_>>> {
+    let container = ContainerAttributes::parse(&input.attrs)?;
+
+    if container.tag.is_some() || container.content.is_some() || container.untagged {
+        return Err(syn::Error::new(
+            input.ident.span(),
+            "`tag`, `content` and `untagged` apply only to enums",
+        ));
+    }
+
+    match &input.data {
+        syn::Data::Struct(syn::DataStruct {
+            fields: Fields::Named(fields),
+            ..
+        }) => get_json_fields(fields, container.rename_all),
+        _ => Err(syn::Error::new(
+            input.ident.span(),
+            "my-json derives support only structs with named fields and enums",
+        )),
+    }
+}
+
+pub fn get_json_fields(
+    fields: &FieldsNamed,
+    rename_all: Option<RenameRule>,
+) -> syn::Result<Vec<JsonField<'_>>> {
+    let mut result: Vec<JsonField> = Vec::new();
+
+    for field in &fields.named {
+        let ident = field.ident.as_ref().unwrap();
+        let attrs = FieldAttributes::parse(&field.attrs)?;
+
+        if attrs.flatten && (attrs.rename.is_some() || attrs.skip_serializing_if.is_some()) {
+            return Err(syn::Error::new(
+                field.span(),
+                "`flatten` can not be combined with `rename` or `skip_serializing_if`",
+            ));
+        }
+
+        if attrs.skip && (attrs.flatten || attrs.rename.is_some()) {
+            return Err(syn::Error::new(
+                field.span(),
+                "`skip` can not be combined with `flatten` or `rename`",
+            ));
+        }
+
+        if attrs.lenient && (attrs.skip || attrs.flatten) {
+            return Err(syn::Error::new(
+                field.span(),
+                "`lenient` can not be combined with `skip` or `flatten`",
+            ));
+        }
+
+        let field_name = ident.unraw().to_string();
+
+        let json_name = match (&attrs.rename, rename_all) {
+            (Some(rename), _) => rename.clone(),
+            (None, Some(rule)) => rule.apply(&field_name),
+            (None, None) => field_name,
+        };
+
+        let is_keyed = !attrs.skip && !attrs.flatten;
+
+        if is_keyed
+            && result
+                .iter()
+                .any(|other| other.is_keyed() && other.json_name == json_name)
+        {
+            return Err(syn::Error::new(
+                field.span(),
+                format!("JSON key '{}' is used by more than one field", json_name),
+            ));
+        }
+
+        result.push(JsonField {
+            ident,
+            ty: &field.ty,
+            json_name,
+            attrs,
+        });
+    }
+
+    Ok(result)
+}
+
+/// The shape of a variant's content.
+pub enum VariantShape<'s> {
+    Unit,
+    /// `Variant(T)` - the content is `T` itself.
+    Newtype(&'s Type),
+    /// `Variant(A, B, ...)` - the content is an array.
+    Tuple(Vec<&'s Type>),
+    /// `Variant { ... }` - the content is an object.
+    Struct(Vec<JsonField<'s>>),
+}
+
+pub struct JsonVariant<'s> {
+    pub ident: &'s Ident,
+    pub json_name: String,
+    pub shape: VariantShape<'s>,
+}
+
+/// The variants of an enum together with their names on the wire.
+pub fn get_enum_variants(
+    input: &DeriveInput,
+) -> syn::Result<(Vec<JsonVariant<'_>>, EnumRepresentation)> {
+    let container = ContainerAttributes::parse(&input.attrs)?;
+
+    let syn::Data::Enum(data) = &input.data else {
+        unreachable!("get_enum_variants is called only for enums");
+    };
+
+    let representation = container.enum_representation(input.ident.span())?;
+
+    let mut result: Vec<JsonVariant> = Vec::new();
+
+    for variant in &data.variants {
+        let attrs = VariantAttributes::parse(&variant.attrs)?;
+
+        let json_name = match (&attrs.rename, container.rename_all) {
+            (Some(rename), _) => rename.clone(),
+            (None, Some(rule)) => rule.apply_to_variant(&variant.ident.unraw().to_string()),
+            (None, None) => variant.ident.unraw().to_string(),
+        };
+
+        if result.iter().any(|other| other.json_name == json_name) {
+            return Err(syn::Error::new(
+                variant.span(),
+                format!(
+                    "variant name '{}' is used by more than one variant",
+                    json_name
+                ),
+            ));
+        }
+
+        let shape = match &variant.fields {
+            Fields::Unit => VariantShape::Unit,
+            Fields::Unnamed(fields) => {
+                if let Some(field) = fields
+                    .unnamed
+                    .iter()
+                    .find(|field| json_attributes(&field.attrs).next().is_some())
+                {
+                    return Err(syn::Error::new(
+                        field.span(),
+                        "my-json attributes are not supported on tuple variant fields",
+                    ));
+                }
+
+                let types: Vec<&Type> = fields.unnamed.iter().map(|field| &field.ty).collect();
+
+                if types.len() == 1 {
+                    VariantShape::Newtype(types[0])
+                } else {
+                    VariantShape::Tuple(types)
+                }
+            }
+            Fields::Named(fields) => {
+                VariantShape::Struct(get_json_fields(fields, attrs.rename_all)?)
+            }
+        };
+
+        if let (EnumRepresentation::Internal { tag }, VariantShape::Tuple(_)) =
+            (&representation, &shape)
+        {
+            return Err(syn::Error::new(
+                variant.span(),
+                format!(
+                    "a tuple variant has no object form to carry the tag '{}'; use `content` as well",
+                    tag
+                ),
+            ));
+        }
+
+        if let (EnumRepresentation::Internal { tag }, VariantShape::Newtype(ty)) =
+            (&representation, &shape)
+        {
+            if !can_be_object(ty) {
+                return Err(syn::Error::new(
+                    variant.span(),
+                    format!(
+                        "the content of this variant is not an object, so it can not carry the tag '{}'; use `content` as well",
+                        tag
+                    ),
+                ));
+            }
+        }
+
+        if let (EnumRepresentation::Internal { tag }, VariantShape::Struct(fields)) =
+            (&representation, &shape)
+        {
+            if fields
+                .iter()
+                .any(|field| field.is_keyed() && &field.json_name == tag)
+            {
+                return Err(syn::Error::new(
+                    variant.span(),
+                    format!("a field of this variant uses the tag key '{}'", tag),
+                ));
+            }
+        }
+
+        result.push(JsonVariant {
+            ident: &variant.ident,
+            json_name,
+            shape,
+        });
+    }
+
+    Ok((result, representation))
+}
+
+// The types the library writes as something other than an object.
+const NOT_OBJECTS: &[&str] = &[
+    "u8",
+    "i8",
+    "u16",
+    "i16",
+    "u32",
+    "i32",
+    "u64",
+    "i64",
+    "u128",
+    "i128",
+    "usize",
+    "isize",
+    "f32",
+    "f64",
+    "bool",
+    "char",
+    "str",
+    "String",
+    "Cow",
+    "Decimal",
+    "Option",
+    "Vec",
+    "VecDeque",
+    "HashSet",
+    "BTreeSet",
+    "StrOrString",
+    "JsonNumber",
+    "JsonFloat",
+    "JsonSafeInteger",
+    "DateTimeAsMicroseconds",
+    "JsonDateTime",
+];
+
+// Whether `ty` can write an object, as far as its spelling tells. A type not known here is taken
+// to be a struct.
+fn can_be_object(ty: &Type) -> bool {
+    match ty {
+        Type::Reference(reference) => can_be_object(&reference.elem),
+        Type::Paren(paren) => can_be_object(&paren.elem),
+        Type::Group(group) => can_be_object(&group.elem),
+        Type::Tuple(_) | Type::Array(_) | Type::Slice(_) => false,
+        Type::Path(path) if path.qself.is_none() => {
+            let Some(segment) = path.path.segments.last() else {
+                return true;
+            };
+
+            let name = segment.ident.to_string();
+
+            // Written as the value they hold.
+            if matches!(name.as_str(), "Box" | "Rc" | "Arc" | "Lenient") {
+                if let syn::PathArguments::AngleBracketed(arguments) = &segment.arguments {
+                    if let Some(syn::GenericArgument::Type(inner)) = arguments.args.first() {
+                        return can_be_object(inner);
+                    }
+                }
+            }
+
+            !NOT_OBJECTS.contains(&name.as_str())
+        }
+        _ => true,
+    }
+}
+
+fn json_attributes(attrs: &[Attribute]) -> impl Iterator<Item = &Attribute> {
+    attrs.iter().filter(|attr| attr.path().is_ident("json"))
+}
+
+fn parse_rename_rule(value: &LitStr) -> syn::Result<RenameRule> {
+    RenameRule::from_str(&value.value()).ok_or_else(|| {
+        let supported: Vec<&str> = RenameRule::ALL.iter().map(|(name, _)| *name).collect();
+        syn::Error::new(
+            value.span(),
+            format!(
+                "unknown rename_all rule '{}'. Supported: {}",
+                value.value(),
+                supported.join(", ")
+            ),
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use syn::DeriveInput;
+
+    use super::get_enum_variants;
+
+    fn read_variants(attrs: &str, content: &str) -> syn::Result<()> {
+        let input: DeriveInput =
+            syn::parse_str(&format!("#[json({})] enum E {{ V({}) }}", attrs, content)).unwrap();
+        get_enum_variants(&input).map(|_| ())
+    }
+
+    #[test]
+    fn test_internal_tag_needs_an_object_content() {
+        for content in ["u32", "String", "&'a str", "Box<Vec<Item>>", "Option<Item>"] {
+            let err = read_variants("tag = \"type\"", content).unwrap_err();
+            assert!(err.to_string().contains("use `content` as well"), "{}", err);
+        }
+
+        assert!(read_variants("tag = \"type\"", "Order").is_ok());
+        assert!(read_variants("tag = \"type\"", "Box<Order>").is_ok());
+        assert!(read_variants("tag = \"type\", content = \"c\"", "u32").is_ok());
+    }
+}
@@ -0,0 +1,375 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{DeriveInput, Lifetime};
+
+use crate::attributes::{get_enum_variants, EnumRepresentation, JsonVariant, VariantShape};
+use crate::reader::{impl_reader, read_named_fields, reader_generics};
+
+pub fn generate(input: &DeriveInput) -> syn::Result<TokenStream> {
+    let (variants, representation) = get_enum_variants(input)?;
+    let (generics, lifetime) = reader_generics(input)?;
+
+    let enum_name = input.ident.to_string();
+
+    let expected: Vec<&str> = variants.iter().map(|v| v.json_name.as_str()).collect();
+    let expected = expected.join(", ");
+
+    let unknown_variant = quote! {
+        __other => ::std::result::Result::Err(::my_json::json_reader::JsonParseError::new(format!(
+            "Unknown variant '{}' of enum {}. Expected one of: {}",
+            __other, #enum_name, #expected
+        )))
+    };
+
+    let body = match &representation {
+        EnumRepresentation::External => {
+            read_external(&variants, &lifetime, &enum_name, &unknown_variant)
+        }
+        EnumRepresentation::Internal { tag } => {
+            read_internal(&variants, &lifetime, &enum_name, tag, &unknown_variant)
+        }
+        EnumRepresentation::Adjacent { tag, content } => read_adjacent(
+            &variants,
+            &lifetime,
+            &enum_name,
+            tag,
+            content,
+            &unknown_variant,
+        ),
+        EnumRepresentation::Untagged => read_untagged(&variants, &lifetime, &enum_name),
+    };
+
+    Ok(impl_reader(input, &generics, &lifetime, body))
+}
+
+// `"Unit"` or `{"Variant":content}`.
+fn read_external(
+    variants: &[JsonVariant],
+    lifetime: &Lifetime,
+    enum_name: &str,
+    unknown_variant: &TokenStream,
+) -> TokenStream {
+    let mut string_arms = Vec::new();
+    let mut object_arms = Vec::new();
+
+    for variant in variants {
+        let json_name = variant.json_name.as_str();
+
+        if let VariantShape::Unit = variant.shape {
+            let ident = variant.ident;
+            string_arms.push(quote!(#json_name => ::std::result::Result::Ok(Self::#ident)));
+            object_arms.push(quote! {
+                #json_name => ::std::result::Result::Err(::my_json::json_reader::JsonParseError::new(format!(
+                    "Variant '{}' of enum {} has no content and must be a plain string",
+                    #json_name, #enum_name
+                )))
+            });
+            continue;
+        }
+
+        let read = read_content(variant, lifetime, enum_name, &quote!(&__content));
+        let read = with_path_key(read, json_name);
+
+        string_arms.push(quote! {
+            #json_name => ::std::result::Result::Err(::my_json::json_reader::JsonParseError::new(format!(
+                "Variant '{}' of enum {} has content and must be an object with a single key",
+                #json_name, #enum_name
+            )))
+        });
+        object_arms.push(quote!(#json_name => ::std::result::Result::Ok(#read)));
+    }
+
+    quote! {
+        if value.as_slice().first() == ::std::option::Option::Some(&b'"') {
+            let __name = <::std::string::String as ::my_json::json_reader::JsonValueReader<#lifetime>>::from_json_value(value)?;
+
+            return match __name.as_str() {
+                #(#string_arms,)*
+                #unknown_variant,
+            };
+        }
+
+        let __inner: &#lifetime [u8] = ::my_json::json_reader::json_object_slice(
+            value,
+            ::std::any::type_name::<Self>(),
+        )?;
+
+        let __iterator = ::my_json::json_reader::JsonFirstLineIterator::new(__inner);
+
+        let __single_key_error = || {
+            ::my_json::json_reader::JsonParseError::new(format!(
+                "Enum {} must be a string or an object with exactly one key naming the variant",
+                #enum_name
+            ))
+        };
+
+        let (__key, __content) = match __iterator.get_next() {
+            ::std::option::Option::Some(__item) => __item?,
+            ::std::option::Option::None => return ::std::result::Result::Err(__single_key_error()),
+        };
+
+        if __iterator.get_next().is_some() {
+            return ::std::result::Result::Err(__single_key_error());
+        }
+
+        let __content = ::my_json::json_reader::JsonValueRef::new(__content.data.clone(), __inner);
+
+        match __key.as_str()?.as_str() {
+            #(#object_arms,)*
+            #unknown_variant,
+        }
+    }
+}
+
+// `{"tag":"Variant", ...fields}`. The variant's own fields are read from the same object, which
+// is why a tuple variant is rejected at compile time.
+fn read_internal(
+    variants: &[JsonVariant],
+    lifetime: &Lifetime,
+    enum_name: &str,
+    tag: &str,
+    unknown_variant: &TokenStream,
+) -> TokenStream {
+    let arms = variants.iter().map(|variant| {
+        let json_name = variant.json_name.as_str();
+        let read = read_content(variant, lifetime, enum_name, &quote!(value));
+        quote!(#json_name => ::std::result::Result::Ok(#read))
+    });
+
+    let find_tag = find_tag(lifetime, enum_name, tag, None);
+
+    quote! {
+        #find_tag
+
+        match __tag.as_str() {
+            #(#arms,)*
+            #unknown_variant,
+        }
+    }
+}
+
+// `{"tag":"Variant","content":content}`; a unit variant has no content key.
+fn read_adjacent(
+    variants: &[JsonVariant],
+    lifetime: &Lifetime,
+    enum_name: &str,
+    tag: &str,
+    content: &str,
+    unknown_variant: &TokenStream,
+) -> TokenStream {
+    let arms = variants.iter().map(|variant| {
+        let json_name = variant.json_name.as_str();
+
+        if let VariantShape::Unit = variant.shape {
+            let ident = variant.ident;
+            return quote!(#json_name => ::std::result::Result::Ok(Self::#ident));
+        }
+
+        let read = read_content(variant, lifetime, enum_name, &quote!(__content));
+        let read = with_path_key(read, content);
+
+        quote! {
+            #json_name => {
+                let __content = match &__content {
+                    ::std::option::Option::Some(__content) => __content,
+                    ::std::option::Option::None => {
+                        return ::std::result::Result::Err(::my_json::json_reader::JsonParseError::new(format!(
+                            "Content field '{}' of variant '{}' of enum {} is missing",
+                            #content, #json_name, #enum_name
+                        )))
+                    }
+                };
+
+                ::std::result::Result::Ok(#read)
+            }
+        }
+    });
+
+    let find_tag = find_tag(lifetime, enum_name, tag, Some(content));
+
+    quote! {
+        #find_tag
+
+        match __tag.as_str() {
+            #(#arms,)*
+            #unknown_variant,
+        }
+    }
+}
+
+// Just the content: every variant is tried in declaration order and the first that reads wins.
+fn read_untagged(variants: &[JsonVariant], lifetime: &Lifetime, enum_name: &str) -> TokenStream {
+    let attempts = variants.iter().map(|variant| {
+        let read = match variant.shape {
+            VariantShape::Unit => {
+                let ident = variant.ident;
+                quote! {
+                    {
+                        if !::my_json::json_utils::is_null(value.as_slice()) {
+                            return ::std::result::Result::Err(::my_json::json_reader::JsonParseError::new(
+                                "not null".to_string(),
+                            ));
+                        }
+
+                        Self::#ident
+                    }
+                }
+            }
+            _ => read_content(variant, lifetime, enum_name, &quote!(value)),
+        };
+
+        quote! {
+            #[allow(clippy::redundant_closure_call)]
+            let __attempt = (|| -> ::std::result::Result<Self, ::my_json::json_reader::JsonParseError> {
+                ::std::result::Result::Ok(#read)
+            })();
+
+            if let ::std::result::Result::Ok(__result) = __attempt {
+                return ::std::result::Result::Ok(__result);
+            }
+        }
+    });
+
+    quote! {
+        #(#attempts)*
+
+        ::std::result::Result::Err(::my_json::json_reader::JsonParseError::new(format!(
+            "JSON value did not match any variant of untagged enum {}",
+            #enum_name
+        )))
+    }
+}
+
+// Scans the object for the tag (and, for adjacent tagging, the content) and binds `__tag`.
+fn find_tag(lifetime: &Lifetime, enum_name: &str, tag: &str, content: Option<&str>) -> TokenStream {
+    let (content_slot, content_arm) = match content {
+        Some(content) => (
+            quote! {
+                let mut __content: ::std::option::Option<::my_json::json_reader::JsonValueRef<#lifetime>> =
+                    ::std::option::Option::None;
+            },
+            quote!(#content => __content = ::std::option::Option::Some(__value),),
+        ),
+        None => (quote!(), quote!()),
+    };
+
+    quote! {
+        let __inner: &#lifetime [u8] = ::my_json::json_reader::json_object_slice(
+            value,
+            ::std::any::type_name::<Self>(),
+        )?;
+
+        let mut __tag: ::std::option::Option<::std::string::String> = ::std::option::Option::None;
+        #content_slot
+
+        let __iterator = ::my_json::json_reader::JsonFirstLineIterator::new(__inner);
+
+        while let ::std::option::Option::Some(__item) = __iterator.get_next() {
+            let (__key, __value) = __item?;
+            let __value = ::my_json::json_reader::JsonValueRef::new(__value.data.clone(), __inner);
+
+            match __key.as_str()?.as_str() {
+                #tag => {
+                    __tag = ::std::option::Option::Some(
+                        <::std::string::String as ::my_json::json_reader::JsonValueReader<#lifetime>>::from_json_value(&__value)?,
+                    );
+                }
+                #content_arm
+                _ => {}
+            }
+        }
+
+        let __tag = match __tag {
+            ::std::option::Option::Some(__tag) => __tag,
+            ::std::option::Option::None => {
+                return ::std::result::Result::Err(::my_json::json_reader::JsonParseError::new(format!(
+                    "Tag field '{}' of enum {} is missing",
+                    #tag, #enum_name
+                )))
+            }
+        };
+    }
+}
+
+/// `read`, with any error it raises marked as raised under `key`.
+fn with_path_key(read: TokenStream, key: &str) -> TokenStream {
+    quote! {
+        {
+            #[allow(clippy::redundant_closure_call)]
+            let __result = (|| -> ::std::result::Result<Self, ::my_json::json_reader::JsonParseError> {
+                ::std::result::Result::Ok(#read)
+            })();
+
+            __result.map_err(|err| err.with_path_key(#key))?
+        }
+    }
+}
+
+/// An expression that reads the variant from `content` (a `&JsonValueRef`) and evaluates to
+/// `Self::Variant...`, returning early on the first error.
+fn read_content(
+    variant: &JsonVariant,
+    lifetime: &Lifetime,
+    enum_name: &str,
+    content: &TokenStream,
+) -> TokenStream {
+    let ident = variant.ident;
+
+    match &variant.shape {
+        VariantShape::Unit => quote!(Self::#ident),
+        VariantShape::Newtype(ty) => quote! {
+            Self::#ident(<#ty as ::my_json::json_reader::JsonValueReader<#lifetime>>::from_json_value(#content)?)
+        },
+        VariantShape::Tuple(types) => {
+            let json_name = variant.json_name.as_str();
+            let count = types.len();
+
+            let elements: Vec<_> = (0..count)
+                .map(|no| format_ident!("__element_{}", no))
+                .collect();
+
+            let wrong_length = quote! {
+                ::my_json::json_reader::JsonParseError::new(format!(
+                    "Variant '{}' of enum {} must be an array of {} elements",
+                    #json_name, #enum_name, #count
+                ))
+            };
+
+            let reads = elements.iter().zip(types).enumerate().map(|(no, (element, ty))| {
+                quote! {
+                    let #element = match __elements.get_next() {
+                        ::std::option::Option::Some(__item) => {
+                            let __item = __item?;
+                            let __item = ::my_json::json_reader::JsonValueRef::new(__item.data.clone(), __array);
+                            <#ty as ::my_json::json_reader::JsonValueReader<#lifetime>>::from_json_value(&__item)
+                                .map_err(|err| err.with_path_index(#no))?
+                        }
+                        ::std::option::Option::None => return ::std::result::Result::Err(#wrong_length),
+                    };
+                }
+            });
+
+            quote! {
+                {
+                    let __array: &#lifetime [u8] = ::my_json::json_reader::json_array_slice(
+                        #content,
+                        ::std::any::type_name::<Self>(),
+                    )?;
+
+                    let __elements = ::my_json::json_reader::JsonArrayIterator::new(__array)?;
+
+                    #(#reads)*
+
+                    if __elements.get_next().is_some() {
+                        return ::std::result::Result::Err(#wrong_length);
+                    }
+
+                    Self::#ident(#(#elements),*)
+                }
+            }
+        }
+        VariantShape::Struct(fields) => {
+            read_named_fields(fields, lifetime, content, quote!(Self::#ident))
+        }
+    }
+}
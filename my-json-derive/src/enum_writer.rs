@@ -0,0 +1,201 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::DeriveInput;
+
+use crate::attributes::{
+    get_enum_variants, EnumRepresentation, JsonField, JsonVariant, VariantShape,
+};
+use crate::writer::{impl_writer, option_inner, write_named_fields};
+
+pub fn generate(input: &DeriveInput) -> syn::Result<TokenStream> {
+    let (variants, representation) = get_enum_variants(input)?;
+
+    let arms = variants.iter().map(|variant| {
+        let pattern = variant_pattern(variant);
+        let write = write_variant(variant, &representation);
+        quote!(#pattern => { #write })
+    });
+
+    Ok(impl_writer(
+        input,
+        quote! {
+            match self {
+                #(#arms)*
+            }
+        },
+    ))
+}
+
+// Binds every field of the variant as `__binding_N`, in declaration order.
+fn variant_pattern(variant: &JsonVariant) -> TokenStream {
+    let ident = variant.ident;
+
+    match &variant.shape {
+        VariantShape::Unit => quote!(Self::#ident),
+        VariantShape::Newtype(_) => quote!(Self::#ident(__binding_0)),
+        VariantShape::Tuple(types) => {
+            let bindings = (0..types.len()).map(|no| format_ident!("__binding_{}", no));
+            quote!(Self::#ident(#(#bindings),*))
+        }
+        VariantShape::Struct(fields) => {
+            let bindings = fields.iter().enumerate().map(|(no, field)| {
+                let field_ident = field.ident;
+                let binding = format_ident!("__binding_{}", no);
+                quote!(#field_ident: #binding)
+            });
+            quote!(Self::#ident { #(#bindings),* })
+        }
+    }
+}
+
+fn write_variant(variant: &JsonVariant, representation: &EnumRepresentation) -> TokenStream {
+    let json_name = variant.json_name.as_str();
+
+    let object_writer = quote! {
+        ::my_json::json_writer::JsonObjectWriter::from_string(::std::mem::take(dest))
+            .with_options(*options)
+    };
+
+    if let VariantShape::Unit = variant.shape {
+        return match representation {
+            EnumRepresentation::External => quote! {
+                ::my_json::json_writer::JsonValueWriter::write_with_options(&#json_name, dest, options);
+            },
+            EnumRepresentation::Internal { tag } | EnumRepresentation::Adjacent { tag, .. } => {
+                quote! {
+                    *dest = #object_writer.write(#tag, #json_name).build();
+                }
+            }
+            EnumRepresentation::Untagged => quote!(dest.push_str("null");),
+        };
+    }
+
+    if let EnumRepresentation::Internal { tag } = representation {
+        // The content's own fields go next to the tag, so only the object shapes are allowed here
+        // - a tuple variant, or a newtype one that is not an object, is rejected when the
+        // attributes are read.
+        return match &variant.shape {
+            VariantShape::Struct(fields) => {
+                let writes = write_named_fields(fields, binding);
+
+                quote! {
+                    #[allow(unused_mut)]
+                    let mut __writer = #object_writer.write(#tag, #json_name);
+                    #(#writes)*
+                    *dest = __writer.build();
+                }
+            }
+            _ => quote! {
+                *dest = #object_writer
+                    .write(#tag, #json_name)
+                    .write_flattened(__binding_0)
+                    .build();
+            },
+        };
+    }
+
+    let content_expr = variant_content(variant);
+
+    match representation {
+        EnumRepresentation::External => quote! {
+            let __content = #content_expr;
+            *dest = #object_writer
+                .write(#json_name, ::my_json::json_writer::RawJsonObject::new(__content))
+                .build();
+        },
+        EnumRepresentation::Adjacent { tag, content } => quote! {
+            let __content = #content_expr;
+            *dest = #object_writer
+                .write(#tag, #json_name)
+                .write(#content, ::my_json::json_writer::RawJsonObject::new(__content))
+                .build();
+        },
+        _ => quote! {
+            let __content = #content_expr;
+            dest.push_str(&__content);
+        },
+    }
+}
+
+/// An expression evaluating to the variant's content, rendered as a `String`.
+fn variant_content(variant: &JsonVariant) -> TokenStream {
+    match &variant.shape {
+        VariantShape::Unit => quote!(::std::string::String::from("null")),
+        VariantShape::Newtype(ty) => {
+            let write = write_element(ty, &format_ident!("__binding_0"));
+            quote! {
+                {
+                    let mut __content = ::std::string::String::new();
+                    #write
+                    __content
+                }
+            }
+        }
+        VariantShape::Tuple(types) => {
+            let writes = types.iter().enumerate().map(|(no, ty)| {
+                let write = write_element(ty, &format_ident!("__binding_{}", no));
+                if no == 0 {
+                    write
+                } else {
+                    quote! {
+                        __content.push(',');
+                        #write
+                    }
+                }
+            });
+
+            quote! {
+                {
+                    let mut __content = ::std::string::String::from("[");
+                    #(#writes)*
+                    __content.push(']');
+                    __content
+                }
+            }
+        }
+        VariantShape::Struct(fields) => {
+            let writes = write_named_fields(fields, binding);
+
+            quote! {
+                {
+                    #[allow(unused_mut)]
+                    let mut __writer = ::my_json::json_writer::JsonObjectWriter::new()
+                        .with_options(*options);
+                    #(#writes)*
+                    __writer.build()
+                }
+            }
+        }
+    }
+}
+
+fn binding(no: usize, _: &JsonField) -> TokenStream {
+    let binding = format_ident!("__binding_{}", no);
+    quote!(#binding)
+}
+
+// One value into `__content`, in its own `[` `]` when it is array-valued - the same wrapping
+// `JsonObjectWriter::write` applies.
+fn write_element(ty: &syn::Type, binding: &syn::Ident) -> TokenStream {
+    if let Some(inner) = option_inner(ty) {
+        let value = format_ident!("{}_value", binding);
+        let write = write_element(inner, &value);
+
+        return quote! {
+            match #binding {
+                ::std::option::Option::Some(#value) => { #write }
+                ::std::option::Option::None => __content.push_str("null"),
+            }
+        };
+    }
+
+    quote! {
+        if <#ty as ::my_json::json_writer::JsonValueWriter>::IS_ARRAY {
+            __content.push('[');
+        }
+        ::my_json::json_writer::JsonValueWriter::write_with_options(#binding, &mut __content, options);
+        if <#ty as ::my_json::json_writer::JsonValueWriter>::IS_ARRAY {
+            __content.push(']');
+        }
+    }
+}
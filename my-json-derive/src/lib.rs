@@ -0,0 +1,70 @@
+//! Derive macros for my-json's `JsonValueReader` / `JsonValueWriter`. Use them through
+//! `my_json` with the `derive` feature rather than depending on this crate directly - the
+//! generated code refers to `::my_json`.
+
+mod attributes;
+mod enum_reader;
+mod enum_writer;
+mod reader;
+mod rename_rule;
+mod writer;
+
+use proc_macro::TokenStream;
+use syn::{parse_macro_input, DeriveInput};
+
+/// Generates a `JsonValueReader` for a struct with named fields or an enum.
+///
+/// Keys not matched by any field are ignored; an unknown enum variant is an error naming it and
+/// the expected ones. See the `JsonValueWriter` derive for the supported `#[json(...)]`
+/// attributes - both derives read the same ones.
+#[proc_macro_derive(JsonValueReader, attributes(json))]
+pub fn derive_json_value_reader(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    match reader::generate(&input) {
+        Ok(result) => result.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+/// Generates a `JsonValueWriter` for a struct with named fields (written as a JSON object) or
+/// an enum.
+///
+/// Attributes, shared with the `JsonValueReader` derive:
+///
+/// * `#[json(rename_all = "camelCase")]` on the struct - also `lowercase`, `UPPERCASE`,
+///   `PascalCase`, `snake_case`, `SCREAMING_SNAKE_CASE`, `kebab-case`, `SCREAMING-KEBAB-CASE`.
+///   On an enum it renames the variants; on a struct variant, its fields.
+/// * `#[json(rename = "name")]` - the key for one field; wins over `rename_all`.
+/// * `#[json(skip)]` - never written, and read as `Default::default()`.
+/// * `#[json(default)]` / `#[json(default = "path")]` - the value read when the key is absent.
+/// * `#[json(flatten)]` - the field's own object fields are written into, and read from, the
+///   enclosing object.
+/// * `#[json(skip_serializing_if = "path")]` - omits the key when `path(&field)` is true. The
+///   reader must be able to read the key's absence back, so pair it with an `Option` field or
+///   with `default`.
+/// * `#[json(lenient)]` - read through `LenientJsonValueReader`: a number may arrive as a
+///   string, a bool as `1` / `0` or a string, and an empty string is `None`. Written as usual.
+///
+/// An enum is externally tagged by default - a unit variant is `"Variant"`, any other is
+/// `{"Variant":content}`, where a newtype's content is its value, a tuple's an array and a struct
+/// variant's an object. On the enum:
+///
+/// * `#[json(tag = "type")]` - internally tagged, `{"type":"Variant", ...fields}`. The content
+///   must be an object, so tuple variants are rejected, and so is a newtype variant of a number,
+///   string, sequence or `Option`.
+/// * `#[json(tag = "type", content = "payload")]` - adjacently tagged,
+///   `{"type":"Variant","payload":content}`.
+/// * `#[json(untagged)]` - just the content (`null` for a unit variant); the reader tries the
+///   variants in declaration order.
+///
+/// A variant takes `#[json(rename = "...")]`.
+#[proc_macro_derive(JsonValueWriter, attributes(json))]
+pub fn derive_json_value_writer(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    match writer::generate(&input) {
+        Ok(result) => result.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
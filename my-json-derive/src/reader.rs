@@ -0,0 +1,179 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{DeriveInput, GenericParam, Generics, Lifetime, LifetimeParam};
+
+use crate::attributes::{get_struct_fields, FieldDefault, JsonField};
+
+pub fn generate(input: &DeriveInput) -> syn::Result<TokenStream> {
+    if let syn::Data::Enum(_) = &input.data {
+        return crate::enum_reader::generate(input);
+    }
+
+    let fields = get_struct_fields(input)?;
+    let (generics, lifetime) = reader_generics(input)?;
+
+    let body = read_named_fields(&fields, &lifetime, &quote!(value), quote!(Self));
+
+    Ok(impl_reader(
+        input,
+        &generics,
+        &lifetime,
+        quote!(::std::result::Result::Ok(#body)),
+    ))
+}
+
+/// The generics of the generated impl, and the lifetime it reads with. A type that borrows from
+/// the document (e.g. a `StrOrString<'s>` field) reads with its own lifetime; any other type
+/// gets a fresh one.
+pub fn reader_generics(input: &DeriveInput) -> syn::Result<(Generics, Lifetime)> {
+    let lifetimes: Vec<&LifetimeParam> = input.generics.lifetimes().collect();
+
+    if lifetimes.len() > 1 {
+        return Err(syn::Error::new(
+            lifetimes[1].lifetime.span(),
+            "JsonValueReader can be derived only for types with at most one lifetime",
+        ));
+    }
+
+    let mut generics = input.generics.clone();
+
+    let lifetime = match lifetimes.first() {
+        Some(lifetime) => lifetime.lifetime.clone(),
+        None => {
+            let lifetime = Lifetime::new("'__s", proc_macro2::Span::call_site());
+            generics.params.insert(
+                0,
+                GenericParam::Lifetime(LifetimeParam::new(lifetime.clone())),
+            );
+            lifetime
+        }
+    };
+
+    for param in generics.type_params_mut() {
+        param
+            .bounds
+            .push(syn::parse_quote!(::my_json::json_reader::JsonValueReader<#lifetime>));
+    }
+
+    Ok((generics, lifetime))
+}
+
+pub fn impl_reader(
+    input: &DeriveInput,
+    generics: &Generics,
+    lifetime: &Lifetime,
+    body: TokenStream,
+) -> TokenStream {
+    let name = &input.ident;
+    let (impl_generics, _, _) = generics.split_for_impl();
+    let (_, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    quote! {
+        impl #impl_generics ::my_json::json_reader::JsonValueReader<#lifetime> for #name #ty_generics #where_clause {
+            fn from_json_value(
+                value: &::my_json::json_reader::JsonValueRef<#lifetime>,
+            ) -> ::std::result::Result<Self, ::my_json::json_reader::JsonParseError> {
+                #body
+            }
+        }
+    }
+}
+
+/// A block that reads `fields` from the object `value` (a `&JsonValueRef`) and evaluates to
+/// `constructor { ... }`, returning early on the first error.
+pub fn read_named_fields(
+    fields: &[JsonField],
+    lifetime: &Lifetime,
+    value: &TokenStream,
+    constructor: TokenStream,
+) -> TokenStream {
+    let mut slots = Vec::new();
+    let mut arms = Vec::new();
+    let mut inits = Vec::new();
+
+    for (no, field) in fields.iter().enumerate() {
+        let ident = field.ident;
+        let ty = field.ty;
+        let json_name = field.json_name.as_str();
+        let slot = format_ident!("__field_{}", no);
+
+        let read_as = quote!(<#ty as ::my_json::json_reader::JsonValueReader<#lifetime>>);
+
+        // A lenient field is read as `Lenient<T>` and unwrapped.
+        let (read_as, unwrap) = if field.attrs.lenient {
+            (
+                quote!(<::my_json::json_reader::Lenient<#ty> as ::my_json::json_reader::JsonValueReader<#lifetime>>),
+                quote!(.0),
+            )
+        } else {
+            (read_as, quote!())
+        };
+
+        if field.attrs.skip {
+            inits.push(quote!(#ident: ::std::default::Default::default()));
+            continue;
+        }
+
+        if field.attrs.flatten {
+            inits.push(quote!(#ident: #read_as::from_json_value(#value)?));
+            continue;
+        }
+
+        slots
+            .push(quote!(let mut #slot: ::std::option::Option<#ty> = ::std::option::Option::None;));
+        arms.push(quote! {
+            #json_name => {
+                #slot = ::std::option::Option::Some(
+                    #read_as::from_json_value(&__value)
+                        .map_err(|err| err.with_path_key(#json_name))?
+                        #unwrap,
+                )
+            }
+        });
+
+        let absent = match &field.attrs.default {
+            Some(FieldDefault::Trait) => quote!(::std::default::Default::default()),
+            Some(FieldDefault::Path(path)) => quote!(#path()),
+            None => quote! {
+                #read_as::from_absent_json_value(#json_name)
+                    .map_err(|err| err.with_path_key(#json_name))?
+                    #unwrap
+            },
+        };
+
+        inits.push(quote! {
+            #ident: match #slot {
+                ::std::option::Option::Some(v) => v,
+                ::std::option::Option::None => #absent,
+            }
+        });
+    }
+
+    quote! {
+        {
+            let __inner: &#lifetime [u8] = ::my_json::json_reader::json_object_slice(
+                #value,
+                ::std::any::type_name::<Self>(),
+            )?;
+
+            #(#slots)*
+
+            let __iterator = ::my_json::json_reader::JsonFirstLineIterator::new(__inner);
+
+            while let ::std::option::Option::Some(__item) = __iterator.get_next() {
+                let (__key, __value) = __item?;
+                let __value = ::my_json::json_reader::JsonValueRef::new(__value.data.clone(), __inner);
+
+                #[allow(clippy::match_single_binding)]
+                match __key.as_str()?.as_str() {
+                    #(#arms,)*
+                    _ => {}
+                }
+            }
+
+            #constructor {
+                #(#inits,)*
+            }
+        }
+    }
+}
@@ -0,0 +1,157 @@
+/// A `#[json(rename_all = "...")]` case convention. Field names are taken to be `snake_case`, as
+/// Rust names them; each rule re-spells a name from its `_`-separated words.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RenameRule {
+    Lower,
+    Upper,
+    Camel,
+    Pascal,
+    Snake,
+    ScreamingSnake,
+    Kebab,
+    ScreamingKebab,
+}
+
+impl RenameRule {
+    pub const ALL: &'static [(&'static str, RenameRule)] = &[
+        ("lowercase", RenameRule::Lower),
+        ("UPPERCASE", RenameRule::Upper),
+        ("camelCase", RenameRule::Camel),
+        ("PascalCase", RenameRule::Pascal),
+        ("snake_case", RenameRule::Snake),
+        ("SCREAMING_SNAKE_CASE", RenameRule::ScreamingSnake),
+        ("kebab-case", RenameRule::Kebab),
+        ("SCREAMING-KEBAB-CASE", RenameRule::ScreamingKebab),
+    ];
+
+    pub fn from_str(src: &str) -> Option<Self> {
+        Self::ALL
+            .iter()
+            .find(|(name, _)| *name == src)
+            .map(|(_, rule)| *rule)
+    }
+
+    pub fn apply(&self, field_name: &str) -> String {
+        let words = field_name.split('_').filter(|word| !word.is_empty());
+
+        match self {
+            RenameRule::Lower => field_name.replace('_', "").to_lowercase(),
+            RenameRule::Upper => field_name.replace('_', "").to_uppercase(),
+            RenameRule::Camel => {
+                let mut result = String::new();
+                for (no, word) in words.enumerate() {
+                    if no == 0 {
+                        result.push_str(&word.to_lowercase());
+                    } else {
+                        push_capitalized(&mut result, word);
+                    }
+                }
+                result
+            }
+            RenameRule::Pascal => {
+                let mut result = String::new();
+                for word in words {
+                    push_capitalized(&mut result, word);
+                }
+                result
+            }
+            RenameRule::Snake => join(words, '_', false),
+            RenameRule::ScreamingSnake => join(words, '_', true),
+            RenameRule::Kebab => join(words, '-', false),
+            RenameRule::ScreamingKebab => join(words, '-', true),
+        }
+    }
+
+    /// The same rules for an enum variant, whose Rust name is `PascalCase`.
+    pub fn apply_to_variant(&self, variant_name: &str) -> String {
+        match self {
+            RenameRule::Pascal => variant_name.to_string(),
+            _ => self.apply(&pascal_to_snake(variant_name)),
+        }
+    }
+}
+
+fn pascal_to_snake(src: &str) -> String {
+    let mut result = String::new();
+    for (no, c) in src.chars().enumerate() {
+        if c.is_uppercase() && no > 0 {
+            result.push('_');
+        }
+        result.extend(c.to_lowercase());
+    }
+    result
+}
+
+fn push_capitalized(dest: &mut String, word: &str) {
+    let mut chars = word.chars();
+    if let Some(first) = chars.next() {
+        dest.extend(first.to_uppercase());
+        dest.push_str(&chars.as_str().to_lowercase());
+    }
+}
+
+fn join<'s>(words: impl Iterator<Item = &'s str>, separator: char, upper: bool) -> String {
+    let mut result = String::new();
+    for (no, word) in words.enumerate() {
+        if no > 0 {
+            result.push(separator);
+        }
+        if upper {
+            result.push_str(&word.to_uppercase());
+        } else {
+            result.push_str(&word.to_lowercase());
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RenameRule;
+
+    #[test]
+    fn test_every_rule() {
+        let cases = [
+            ("lowercase", "sellamount"),
+            ("UPPERCASE", "SELLAMOUNT"),
+            ("camelCase", "sellAmount"),
+            ("PascalCase", "SellAmount"),
+            ("snake_case", "sell_amount"),
+            ("SCREAMING_SNAKE_CASE", "SELL_AMOUNT"),
+            ("kebab-case", "sell-amount"),
+            ("SCREAMING-KEBAB-CASE", "SELL-AMOUNT"),
+        ];
+
+        for (rule, expected) in cases {
+            let rule = RenameRule::from_str(rule).unwrap();
+            assert_eq!(rule.apply("sell_amount"), expected);
+        }
+    }
+
+    #[test]
+    fn test_single_word_and_unknown_rule() {
+        assert_eq!(RenameRule::Camel.apply("id"), "id");
+        assert_eq!(RenameRule::Pascal.apply("id"), "Id");
+        assert!(RenameRule::from_str("Camel").is_none());
+    }
+
+    #[test]
+    fn test_variant_names() {
+        assert_eq!(
+            RenameRule::Snake.apply_to_variant("OrderPlaced"),
+            "order_placed"
+        );
+        assert_eq!(
+            RenameRule::Camel.apply_to_variant("OrderPlaced"),
+            "orderPlaced"
+        );
+        assert_eq!(
+            RenameRule::Pascal.apply_to_variant("OrderPlaced"),
+            "OrderPlaced"
+        );
+        assert_eq!(
+            RenameRule::ScreamingKebab.apply_to_variant("Cancel"),
+            "CANCEL"
+        );
+    }
+}
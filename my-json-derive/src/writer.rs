@@ -0,0 +1,147 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::DeriveInput;
+
+use crate::attributes::{get_struct_fields, JsonField};
+
+pub fn generate(input: &DeriveInput) -> syn::Result<TokenStream> {
+    if let syn::Data::Enum(_) = &input.data {
+        return crate::enum_writer::generate(input);
+    }
+
+    let fields = get_struct_fields(input)?;
+
+    let writes = write_named_fields(&fields, |_, field| {
+        let ident = field.ident;
+        quote!(&self.#ident)
+    });
+
+    Ok(impl_writer(
+        input,
+        quote! {
+            #[allow(unused_mut)]
+            let mut __writer = ::my_json::json_writer::JsonObjectWriter::from_string(
+                ::std::mem::take(dest),
+            )
+            .with_options(*options);
+
+            #(#writes)*
+
+            *dest = __writer.build();
+        },
+    ))
+}
+
+/// `body` writes `self` into `dest` with `options`.
+pub fn impl_writer(input: &DeriveInput, body: TokenStream) -> TokenStream {
+    let name = &input.ident;
+
+    let mut generics = input.generics.clone();
+    for param in generics.type_params_mut() {
+        param
+            .bounds
+            .push(syn::parse_quote!(::my_json::json_writer::JsonValueWriter));
+    }
+    let (impl_generics, _, _) = generics.split_for_impl();
+    let (_, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    quote! {
+        impl #impl_generics ::my_json::json_writer::JsonValueWriter for #name #ty_generics #where_clause {
+            const IS_ARRAY: bool = false;
+
+            fn write(&self, dest: &mut String) {
+                self.write_with_options(dest, &::my_json::json_writer::JsonWriterOptions::default())
+            }
+
+            fn write_with_options(
+                &self,
+                dest: &mut String,
+                options: &::my_json::json_writer::JsonWriterOptions,
+            ) {
+                #body
+            }
+        }
+    }
+}
+
+/// Statements that write `fields` through the `JsonObjectWriter` bound to `__writer`;
+/// `accessor` gives a `&T` expression for the field at a position.
+pub fn write_named_fields(
+    fields: &[JsonField],
+    accessor: impl Fn(usize, &JsonField) -> TokenStream,
+) -> Vec<TokenStream> {
+    let mut writes = Vec::new();
+
+    for (no, field) in fields.iter().enumerate() {
+        let json_name = field.json_name.as_str();
+
+        if field.attrs.skip {
+            continue;
+        }
+
+        let value = accessor(no, field);
+
+        if field.attrs.flatten {
+            match option_inner(field.ty) {
+                Some(_) => writes.push(quote! {
+                    if let ::std::option::Option::Some(__value) = #value {
+                        __writer = __writer.write_flattened(__value);
+                    }
+                }),
+                None => writes.push(quote!(__writer = __writer.write_flattened(#value);)),
+            }
+            continue;
+        }
+
+        let write = match option_inner(field.ty) {
+            Some(_) => quote! {
+                __writer = match #value {
+                    ::std::option::Option::Some(__value) => __writer.write_ref(#json_name, __value),
+                    ::std::option::Option::None => {
+                        __writer.write(#json_name, ::my_json::json_writer::JsonNullValue)
+                    }
+                };
+            },
+            None => quote!(__writer = __writer.write_ref(#json_name, #value);),
+        };
+
+        match &field.attrs.skip_serializing_if {
+            Some(path) => writes.push(quote! {
+                if !#path(#value) {
+                    #write
+                }
+            }),
+            None => writes.push(write),
+        }
+    }
+
+    writes
+}
+
+/// `T` when `ty` is spelt `Option<T>`. `JsonValueWriter` is implemented for the `Option` of the
+/// library's own types only, so the derive writes an `Option` itself: `None` as `null`, `Some` as
+/// the value it holds.
+pub fn option_inner(ty: &syn::Type) -> Option<&syn::Type> {
+    let syn::Type::Path(path) = ty else {
+        return None;
+    };
+
+    if path.qself.is_some() {
+        return None;
+    }
+
+    let segment = path.path.segments.last()?;
+
+    if segment.ident != "Option" {
+        return None;
+    }
+
+    let syn::PathArguments::AngleBracketed(arguments) = &segment.arguments else {
+        return None;
+    };
+
+    match arguments.args.first() {
+        Some(syn::GenericArgument::Type(inner)) if arguments.args.len() == 1 => Some(inner),
+        _ => None,
+    }
+}
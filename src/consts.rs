@@ -19,3 +19,6 @@ pub const START_OF_NULL_UPPER_CASE: u8 = 'N' as u8;
 pub const START_OF_NULL_LOWER_CASE: u8 = 'n' as u8;
 
 pub static EMPTY_ARRAY: &'static [u8] = &[OPEN_ARRAY, CLOSE_ARRAY];
+
+/// Starts every record of an RFC 7464 JSON text sequence (`application/json-seq`).
+pub const RECORD_SEPARATOR: u8 = 0x1E;
@@ -48,7 +48,7 @@ fn j_replace_internal<'s>(
     match j_prop_name {
         super::JPropName::Name(j_prop_name) => {
             while let Some(next) = reader.get_next() {
-                let (key, value) = next.unwrap();
+                let (key, value) = next?;
 
                 let key = key.as_str()?;
 
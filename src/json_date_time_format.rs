@@ -0,0 +1,452 @@
+use std::fmt::Write;
+use std::marker::PhantomData;
+use std::ops::Deref;
+
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, SecondsFormat, Utc};
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+use crate::json_reader::{preview, raw_slice, JsonParseError, JsonValueReader, JsonValueRef};
+use crate::json_writer::{JsonValueWriter, JsonWriterOptions};
+
+/// One wire representation of a date-time, pinned.
+///
+/// A plain `DateTimeAsMicroseconds` is written as RFC 3339 UTC and read back by guessing: a
+/// number's unit is picked by its magnitude, which goes wrong for a timestamp near 1970 (`1000`
+/// is read as seconds whatever the sender meant). A field typed [`JsonDateTime<F>`] is written
+/// in, and only read from, the representation `F` - so partners that agree on unix seconds or on
+/// a `+02:00` offset get exactly that, and it round-trips exactly.
+///
+/// Implement it for a format of your own, or use a [`JsonDateTimeLayout`] for a `strftime` one.
+pub trait JsonDateTimeFormat {
+    /// Appends `value` as a complete JSON token - quoted, for a string format.
+    fn write_date_time(value: DateTimeAsMicroseconds, dest: &mut String);
+
+    /// Reads the raw JSON token back; `None` when it is not in this format.
+    fn read_date_time(raw: &str) -> Option<DateTimeAsMicroseconds>;
+}
+
+// Integer unix timestamps. Precision below the unit is dropped on write, towards the past, so a
+// value written as seconds reads back as the start of its second - on either side of 1970.
+macro_rules! impl_unix_date_time_format {
+    ($($(#[$doc:meta])* $name:ident => $micros_per_unit:literal),* $(,)?) => {
+        $(
+            $(#[$doc])*
+            #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+            pub struct $name;
+
+            impl JsonDateTimeFormat for $name {
+                fn write_date_time(value: DateTimeAsMicroseconds, dest: &mut String) {
+                    let units = value.unix_microseconds.div_euclid($micros_per_unit);
+                    let _ = write!(dest, "{}", units);
+                }
+
+                fn read_date_time(raw: &str) -> Option<DateTimeAsMicroseconds> {
+                    let units: i64 = raw.parse().ok()?;
+                    Some(DateTimeAsMicroseconds::new(units.checked_mul($micros_per_unit)?))
+                }
+            }
+        )*
+    };
+}
+
+impl_unix_date_time_format!(
+    /// A JSON integer of seconds since the unix epoch.
+    UnixSeconds => 1_000_000,
+    /// A JSON integer of milliseconds since the unix epoch.
+    UnixMilliseconds => 1_000,
+    /// A JSON integer of microseconds since the unix epoch - lossless.
+    UnixMicroseconds => 1,
+);
+
+/// An RFC 3339 string in UTC with 6 fractional digits, `"2021-04-25T17:30:03.000000Z"` - what a
+/// plain `DateTimeAsMicroseconds` is written as, without the guessing on read. Any offset is
+/// accepted on read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Rfc3339Utc;
+
+impl JsonDateTimeFormat for Rfc3339Utc {
+    fn write_date_time(value: DateTimeAsMicroseconds, dest: &mut String) {
+        write_rfc3339(value, FixedOffset::east_opt(0).unwrap(), true, dest);
+    }
+
+    fn read_date_time(raw: &str) -> Option<DateTimeAsMicroseconds> {
+        read_rfc3339(raw)
+    }
+}
+
+/// An RFC 3339 string at a fixed offset from UTC, in seconds:
+/// `Rfc3339Offset<7200>` writes `"2021-04-25T19:30:03.000000+02:00"`. Any offset is accepted on
+/// read. The offset must be within a day either way, which is checked at compile time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Rfc3339Offset<const OFFSET_SECONDS: i32>;
+
+impl<const OFFSET_SECONDS: i32> Rfc3339Offset<OFFSET_SECONDS> {
+    const OFFSET: FixedOffset = match FixedOffset::east_opt(OFFSET_SECONDS) {
+        Some(offset) => offset,
+        None => panic!("Rfc3339Offset must be within 24 hours of UTC"),
+    };
+}
+
+impl<const OFFSET_SECONDS: i32> JsonDateTimeFormat for Rfc3339Offset<OFFSET_SECONDS> {
+    fn write_date_time(value: DateTimeAsMicroseconds, dest: &mut String) {
+        write_rfc3339(value, Self::OFFSET, false, dest);
+    }
+
+    fn read_date_time(raw: &str) -> Option<DateTimeAsMicroseconds> {
+        read_rfc3339(raw)
+    }
+}
+
+/// A `strftime` layout (see `chrono::format::strftime`) for [`Layout<L>`], e.g.
+///
+/// ```ignore
+/// struct DayFirst;
+///
+/// impl JsonDateTimeLayout for DayFirst {
+///     const LAYOUT: &'static str = "%d.%m.%Y %H:%M:%S";
+/// }
+///
+/// // "25.04.2021 17:30:03"
+/// type Traded = JsonDateTime<Layout<DayFirst>>;
+/// ```
+///
+/// A layout without an offset (`%z`) is written and read at `OFFSET_SECONDS` from UTC; a layout
+/// without a time reads as midnight.
+pub trait JsonDateTimeLayout {
+    const LAYOUT: &'static str;
+    const OFFSET_SECONDS: i32 = 0;
+}
+
+/// A JSON string in the `strftime` layout `L`.
+pub struct Layout<L>(PhantomData<fn() -> L>);
+
+impl<L: JsonDateTimeLayout> Layout<L> {
+    fn offset() -> Option<FixedOffset> {
+        FixedOffset::east_opt(L::OFFSET_SECONDS)
+    }
+}
+
+// A layout with an invalid specifier can not be rendered; that is written as `null` rather than
+// a panic, like the out-of-range values every string format writes as `null`.
+impl<L: JsonDateTimeLayout> JsonDateTimeFormat for Layout<L> {
+    fn write_date_time(value: DateTimeAsMicroseconds, dest: &mut String) {
+        let (Some(date_time), Some(offset)) = (to_chrono(value), Self::offset()) else {
+            dest.push_str("null");
+            return;
+        };
+
+        let mut formatted = String::new();
+
+        if write!(
+            formatted,
+            "{}",
+            date_time.with_timezone(&offset).format(L::LAYOUT)
+        )
+        .is_err()
+        {
+            dest.push_str("null");
+            return;
+        }
+
+        write_string(&formatted, dest);
+    }
+
+    fn read_date_time(raw: &str) -> Option<DateTimeAsMicroseconds> {
+        let src = string_content(raw)?;
+
+        if let Ok(date_time) = DateTime::parse_from_str(src, L::LAYOUT) {
+            return Some(DateTimeAsMicroseconds::new(date_time.timestamp_micros()));
+        }
+
+        let naive = match NaiveDateTime::parse_from_str(src, L::LAYOUT) {
+            Ok(naive) => naive,
+            Err(_) => NaiveDate::parse_from_str(src, L::LAYOUT)
+                .ok()?
+                .and_hms_opt(0, 0, 0)?,
+        };
+
+        let date_time = naive.and_local_timezone(Self::offset()?).single()?;
+
+        Some(DateTimeAsMicroseconds::new(date_time.timestamp_micros()))
+    }
+}
+
+fn to_chrono(value: DateTimeAsMicroseconds) -> Option<DateTime<Utc>> {
+    DateTime::from_timestamp_micros(value.unix_microseconds)
+}
+
+// A value outside the range chrono can render has no RFC 3339 form and is written as `null`.
+fn write_rfc3339(
+    value: DateTimeAsMicroseconds,
+    offset: FixedOffset,
+    use_z: bool,
+    dest: &mut String,
+) {
+    match to_chrono(value) {
+        Some(date_time) => {
+            let formatted = date_time
+                .with_timezone(&offset)
+                .to_rfc3339_opts(SecondsFormat::Micros, use_z);
+            write_string(&formatted, dest);
+        }
+        None => dest.push_str("null"),
+    }
+}
+
+fn read_rfc3339(raw: &str) -> Option<DateTimeAsMicroseconds> {
+    let date_time = DateTime::parse_from_rfc3339(string_content(raw)?).ok()?;
+    Some(DateTimeAsMicroseconds::new(date_time.timestamp_micros()))
+}
+
+fn write_string(value: &str, dest: &mut String) {
+    dest.push('"');
+    crate::json_string_value::write_escaped_json_string_value(value, dest);
+    dest.push('"');
+}
+
+// The text of a string token. A date-time never needs an escape, so one carrying an escape is
+// simply not in any of these formats.
+fn string_content(raw: &str) -> Option<&str> {
+    let content = raw.strip_prefix('"')?.strip_suffix('"')?;
+
+    if content.contains('\\') {
+        return None;
+    }
+
+    Some(content)
+}
+
+/// A `DateTimeAsMicroseconds` that is written and read in the format `F` only - see
+/// [`JsonDateTimeFormat`].
+///
+/// ```ignore
+/// #[derive(JsonValueReader, JsonValueWriter)]
+/// struct Trade {
+///     executed: JsonDateTime<UnixMilliseconds>,        // 1619371803000
+///     settles: Option<JsonDateTime<Rfc3339Offset<7200>>>,
+/// }
+/// ```
+pub struct JsonDateTime<F> {
+    pub value: DateTimeAsMicroseconds,
+    format: PhantomData<fn() -> F>,
+}
+
+impl<F> JsonDateTime<F> {
+    pub fn new(value: DateTimeAsMicroseconds) -> Self {
+        Self {
+            value,
+            format: PhantomData,
+        }
+    }
+
+    pub fn into_inner(self) -> DateTimeAsMicroseconds {
+        self.value
+    }
+}
+
+// The impls below are written out by hand: a derive would require `F` itself to be `Clone`,
+// `PartialEq`, ..., and a format marker has no reason to be.
+impl<F> Clone for JsonDateTime<F> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<F> Copy for JsonDateTime<F> {}
+
+impl<F> std::fmt::Debug for JsonDateTime<F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("JsonDateTime").field(&self.value).finish()
+    }
+}
+
+impl<F> PartialEq for JsonDateTime<F> {
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value
+    }
+}
+
+impl<F> Eq for JsonDateTime<F> {}
+
+impl<F> PartialOrd for JsonDateTime<F> {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<F> Ord for JsonDateTime<F> {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.value.cmp(&other.value)
+    }
+}
+
+impl<F> std::hash::Hash for JsonDateTime<F> {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.value.hash(state)
+    }
+}
+
+impl<F> Deref for JsonDateTime<F> {
+    type Target = DateTimeAsMicroseconds;
+
+    fn deref(&self) -> &Self::Target {
+        &self.value
+    }
+}
+
+impl<F> From<DateTimeAsMicroseconds> for JsonDateTime<F> {
+    fn from(value: DateTimeAsMicroseconds) -> Self {
+        Self::new(value)
+    }
+}
+
+impl<F: JsonDateTimeFormat> JsonValueWriter for JsonDateTime<F> {
+    const IS_ARRAY: bool = false;
+    fn write(&self, dest: &mut String) {
+        F::write_date_time(self.value, dest);
+    }
+
+    fn write_with_options(&self, dest: &mut String, _options: &JsonWriterOptions) {
+        F::write_date_time(self.value, dest);
+    }
+}
+
+impl<'s, F: JsonDateTimeFormat> JsonValueReader<'s> for JsonDateTime<F> {
+    fn from_json_value(value: &JsonValueRef<'s>) -> Result<Self, JsonParseError> {
+        let raw = raw_slice(value);
+
+        std::str::from_utf8(raw)
+            .ok()
+            .and_then(F::read_date_time)
+            .map(Self::new)
+            .ok_or_else(|| {
+                JsonParseError::new(format!(
+                    "JSON value {} can not be read as a date-time in format {}",
+                    preview(raw),
+                    std::any::type_name::<F>()
+                ))
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::json_reader::read_json_value;
+
+    fn write<T: JsonValueWriter>(value: &T) -> String {
+        let mut result = String::new();
+        value.write(&mut result);
+        result
+    }
+
+    fn round_trip<F: JsonDateTimeFormat>(micros: i64, expected: &str) {
+        let value = JsonDateTime::<F>::new(DateTimeAsMicroseconds::new(micros));
+
+        let json = write(&value);
+        assert_eq!(json, expected);
+
+        let back: JsonDateTime<F> = read_json_value(json.as_bytes()).unwrap();
+        assert_eq!(back, value);
+    }
+
+    // 2021-04-25T17:30:03Z
+    const SECOND: i64 = 1_619_371_803_000_000;
+
+    #[test]
+    fn unix_formats_round_trip_including_near_the_epoch() {
+        round_trip::<UnixSeconds>(SECOND, "1619371803");
+        round_trip::<UnixMilliseconds>(SECOND + 250_000, "1619371803250");
+        round_trip::<UnixMicroseconds>(SECOND + 250_123, "1619371803250123");
+
+        // near 1970, where guessing the unit by magnitude goes wrong
+        round_trip::<UnixSeconds>(1_000_000_000, "1000");
+        round_trip::<UnixMilliseconds>(1_000_000, "1000");
+        round_trip::<UnixMicroseconds>(1_000, "1000");
+        round_trip::<UnixSeconds>(-1_000_000, "-1");
+
+        // precision below the unit is dropped towards the past
+        let value = JsonDateTime::<UnixSeconds>::new(DateTimeAsMicroseconds::new(-1));
+        assert_eq!(write(&value), "-1");
+
+        // a number in a string, a fraction or an overflowing value is not the format
+        assert!(read_json_value::<JsonDateTime<UnixSeconds>>(b"\"1000\"").is_err());
+        assert!(read_json_value::<JsonDateTime<UnixSeconds>>(b"1000.5").is_err());
+        assert!(read_json_value::<JsonDateTime<UnixSeconds>>(b"9223372036854775807").is_err());
+    }
+
+    #[test]
+    fn rfc3339_formats_round_trip() {
+        round_trip::<Rfc3339Utc>(SECOND + 5, r#""2021-04-25T17:30:03.000005Z""#);
+        round_trip::<Rfc3339Offset<7200>>(SECOND, r#""2021-04-25T19:30:03.000000+02:00""#);
+        round_trip::<Rfc3339Offset<-19800>>(SECOND, r#""2021-04-25T12:00:03.000000-05:30""#);
+
+        // any offset reads back to the same instant
+        let value: JsonDateTime<Rfc3339Utc> =
+            read_json_value(br#""2021-04-25T19:30:03+02:00""#).unwrap();
+        assert_eq!(value.unix_microseconds, SECOND);
+
+        assert!(read_json_value::<JsonDateTime<Rfc3339Utc>>(b"1619371803").is_err());
+        assert!(read_json_value::<JsonDateTime<Rfc3339Utc>>(br#""2021-04-25""#).is_err());
+    }
+
+    struct DayFirst;
+
+    impl JsonDateTimeLayout for DayFirst {
+        const LAYOUT: &'static str = "%d.%m.%Y %H:%M:%S";
+        const OFFSET_SECONDS: i32 = 3600;
+    }
+
+    struct DateOnly;
+
+    impl JsonDateTimeLayout for DateOnly {
+        const LAYOUT: &'static str = "%Y-%m-%d";
+    }
+
+    struct WithOffset;
+
+    impl JsonDateTimeLayout for WithOffset {
+        const LAYOUT: &'static str = "%Y/%m/%d %H:%M %z";
+    }
+
+    struct Broken;
+
+    impl JsonDateTimeLayout for Broken {
+        const LAYOUT: &'static str = "%Q";
+    }
+
+    #[test]
+    fn custom_layouts_round_trip() {
+        round_trip::<Layout<DayFirst>>(SECOND, r#""25.04.2021 18:30:03""#);
+        round_trip::<Layout<DateOnly>>(1_619_308_800_000_000, r#""2021-04-25""#);
+        round_trip::<Layout<WithOffset>>(1_619_371_800_000_000, r#""2021/04/25 17:30 +0000""#);
+
+        let value: JsonDateTime<Layout<WithOffset>> =
+            read_json_value(br#""2021/04/25 19:30 +0200""#).unwrap();
+        assert_eq!(value.unix_microseconds, 1_619_371_800_000_000);
+
+        assert!(read_json_value::<JsonDateTime<Layout<DayFirst>>>(br#""2021-04-25""#).is_err());
+
+        // an unusable layout is written as null, never a panic
+        let value = JsonDateTime::<Layout<Broken>>::new(DateTimeAsMicroseconds::new(SECOND));
+        assert_eq!(write(&value), "null");
+    }
+
+    #[test]
+    fn out_of_range_values_are_written_as_null() {
+        let value = JsonDateTime::<Rfc3339Utc>::new(DateTimeAsMicroseconds::new(i64::MAX));
+        assert_eq!(write(&value), "null");
+    }
+
+    #[test]
+    fn json_value_ref_reads_a_pinned_format() {
+        let value = crate::j_path::get_value(br#"{"ts":1000}"#, "ts")
+            .unwrap()
+            .unwrap();
+
+        let pinned = value.try_get_date_time_as::<UnixMilliseconds>().unwrap();
+        assert_eq!(pinned.unix_microseconds, 1_000_000);
+
+        assert!(value.try_get_date_time_as::<Rfc3339Utc>().is_err());
+    }
+}
@@ -14,6 +14,10 @@ impl<'s> JsonArrayIterator<'s> {
         Ok(result)
     }
 
+    pub fn as_slice(&self) -> &[u8] {
+        self.iterator.get_src_slice()
+    }
+
     pub fn get_next(&'s self) -> Option<Result<JsonValueRef<'s>, JsonParseError>> {
         let result = self.iterator.get_next()?;
 
@@ -27,9 +31,13 @@ impl<'s> JsonArrayIterator<'s> {
     }
 }
 
+// Can not fail: a slice that is not an array comes back as an `Err` from the first `get_next`.
+// `JsonArrayIterator::new` is the form that rejects it straight away.
 impl<'s> Into<JsonArrayIterator<'s>> for &'s [u8] {
     fn into(self) -> JsonArrayIterator<'s> {
-        JsonArrayIterator::new(self).unwrap()
+        JsonArrayIterator {
+            iterator: JsonArrayIteratorInner::new_deferred(SliceIterator::new(self)),
+        }
     }
 }
 
@@ -26,16 +26,36 @@ impl<TArrayOfBytesIterator: ArrayOfBytesIterator> JsonArrayIteratorInner<TArrayO
         let result = sync_reader::skip_white_spaces(&mut data);
 
         match result {
-            Ok(_) => Ok(Self {
-                data,
-                initialized: Cell::new(false),
-            }),
+            Ok(_) => Ok(Self::new_deferred(data)),
             Err(result) => Err(JsonParseError::CanNotFindStartOfTheArrayObject(
                 result.into_string(),
             )),
         }
     }
 
+    /// Checks nothing up front: a payload that does not open with `[` - an empty one included -
+    /// is reported as an `Err` by the first `get_next`.
+    pub fn new_deferred(data: TArrayOfBytesIterator) -> Self {
+        Self::resume(data, false)
+    }
+
+    /// Picks up where an earlier iterator over the same bytes stopped: `data` is already at its
+    /// position, and `initialized` is set once the `[` has been read.
+    pub(crate) fn resume(data: TArrayOfBytesIterator, initialized: bool) -> Self {
+        Self {
+            data,
+            initialized: Cell::new(initialized),
+        }
+    }
+
+    pub(crate) fn get_pos(&self) -> usize {
+        self.data.get_pos()
+    }
+
+    pub(crate) fn is_initialized(&self) -> bool {
+        self.initialized.get()
+    }
+
     pub fn get_src_slice(&self) -> &[u8] {
         self.data.get_src_slice()
     }
@@ -174,6 +194,14 @@ impl<TArrayOfBytesIterator: ArrayOfBytesIterator> JsonArrayIteratorInner<TArrayO
     }
 }
 
+impl<TRead: std::io::Read> JsonArrayIteratorInner<super::ReadIterator<TRead>> {
+    /// Drops the elements read so far from memory. Resolve the values `get_next` returned before
+    /// calling it: their positions are no longer valid afterwards.
+    pub fn release_consumed(&mut self) {
+        self.data.release_consumed();
+    }
+}
+
 impl<'s> TryInto<JsonArrayIteratorInner<SliceIterator<'s>>> for &'s [u8] {
     type Error = JsonParseError;
     fn try_into(self) -> Result<JsonArrayIteratorInner<SliceIterator<'s>>, Self::Error> {
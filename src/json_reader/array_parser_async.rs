@@ -1,27 +1,122 @@
 use super::bytes_of_array_reader::*;
-use super::JsonParseError;
+use super::{JsonParseError, JsonValueReaderOwned};
 
 use rust_extensions::array_of_bytes_iterator::*;
 
 pub struct JsonArrayIteratorAsync<TArrayOfBytesIterator: ArrayOfBytesIteratorAsync> {
     data: TArrayOfBytesIterator,
     initialized: bool,
+    finished: bool,
+    // Where `get_next_ref` reads each element into.
+    buffer: Vec<u8>,
 }
 
 impl<TArrayOfBytesIterator: ArrayOfBytesIteratorAsync>
     JsonArrayIteratorAsync<TArrayOfBytesIterator>
 {
-    pub async fn new(mut data: TArrayOfBytesIterator) -> Self {
-        async_reader::skip_white_spaces(&mut data).await.unwrap();
+    // Nothing is read here: a payload that does not open with `[` - an empty one included - is
+    // reported as an `Err` by the first `get_next`.
+    pub async fn new(data: TArrayOfBytesIterator) -> Self {
         Self {
             data,
             initialized: false,
+            finished: false,
+            buffer: Vec::new(),
+        }
+    }
+
+    /// Carries on a read from a [`Self::checkpoint`] taken over the same payload. `data` can be at
+    /// the start of the payload or already at the checkpoint's offset.
+    ///
+    /// From the start, the elements up to the checkpoint are read past, not handed out, and one of
+    /// them has to end exactly at the offset. Already at the offset, only the bytes from there on
+    /// are known, so a `,` or the closing `]` has to come next.
+    pub async fn resume(
+        mut data: TArrayOfBytesIterator,
+        checkpoint: usize,
+    ) -> Result<Self, JsonParseError> {
+        if checkpoint == 0 {
+            return Ok(Self::new(data).await);
+        }
+
+        if data.get_pos() < checkpoint {
+            return Self::read_up_to(data, checkpoint).await;
+        }
+
+        async_reader::advance_to(&mut data, checkpoint).await?;
+
+        while let Some(next_value) = data.peek_value() {
+            if !async_reader::is_space(next_value.value) {
+                break;
+            }
+
+            data.get_next().await?;
+        }
+
+        let finished = match data.peek_value() {
+            Some(next_value)
+                if next_value.value == crate::consts::COMMA
+                    || next_value.value == crate::consts::CLOSE_ARRAY =>
+            {
+                false
+            }
+            None => true,
+            Some(next_value) => {
+                return Err(JsonParseError::new(format!(
+                    "Checkpoint offset {} is not between array elements. Found '{}' at position {}",
+                    checkpoint, next_value.value as char, next_value.pos
+                )));
+            }
+        };
+
+        Ok(Self {
+            data,
+            initialized: true,
+            finished,
+            buffer: Vec::new(),
+        })
+    }
+
+    // Skips whole elements until one ends at the checkpoint, so an offset inside an element - one
+    // that happens to sit before a `,` of a nested array included - is an error.
+    async fn read_up_to(
+        data: TArrayOfBytesIterator,
+        checkpoint: usize,
+    ) -> Result<Self, JsonParseError> {
+        let mut result = Self::new(data).await;
+
+        while result.data.get_pos() < checkpoint {
+            match result.next_element_start().await? {
+                Some(start_value) => skip_element(&mut result.data, start_value).await?,
+                None => break,
+            }
+
+            release_read(&mut result.data).await?;
+        }
+
+        if result.data.get_pos() != checkpoint {
+            return Err(JsonParseError::new(format!(
+                "Checkpoint offset {} is not between array elements",
+                checkpoint
+            )));
+        }
+
+        Ok(result)
+    }
+
+    /// Where the read has got to, to [`Self::resume`] it from later: just past the last element
+    /// handed out, or `0` before the first.
+    pub fn checkpoint(&self) -> usize {
+        if self.initialized {
+            self.data.get_pos()
+        } else {
+            0
         }
     }
 
     async fn init(&mut self) -> Result<(), JsonParseError> {
         let result =
-            async_reader::next_token_must_be(&mut self.data, crate::consts::OPEN_ARRAY).await;
+            async_reader::next_token_must_be(&mut self.data, crate::consts::OPEN_ARRAY).await?;
 
         match result {
             FoundResult::Ok(_) => {
@@ -42,120 +137,124 @@ impl<TArrayOfBytesIterator: ArrayOfBytesIteratorAsync>
         }
     }
 
+    /// The next element, read as `T`. An element that is not a `T` is an `Err` for that
+    /// element only; the iteration can go on.
+    pub async fn read_as<T: JsonValueReaderOwned>(&mut self) -> Option<Result<T, JsonParseError>> {
+        let item = self.get_next().await?;
+        Some(item.and_then(|bytes| T::from_json_slice(&bytes)))
+    }
+
     pub async fn get_next(&mut self) -> Option<Result<Vec<u8>, JsonParseError>> {
+        let start_value = match self.next_element_start().await {
+            Ok(start_value) => start_value?,
+            Err(err) => return Some(Err(err)),
+        };
+
+        if let Err(err) = skip_element(&mut self.data, start_value).await {
+            return Some(Err(err));
+        }
+
+        let result = self.data.get_slice_to_current_pos(start_value.pos).await;
+        return Some(result.map_err(JsonParseError::from));
+    }
+
+    // The first byte of the next element, or `None` past the end of the array.
+    async fn next_element_start(&mut self) -> Result<Option<NextValue>, JsonParseError> {
+        if self.finished {
+            return Ok(None);
+        }
+
         let start_value = if !self.initialized {
-            match self.init().await {
-                Ok(_) => match async_reader::skip_white_spaces(&mut self.data).await {
-                    Ok(value) => value,
-                    Err(err) => return Some(Err(err)),
-                },
-                Err(err) => return Some(Err(err)),
-            }
+            self.init().await?;
+            async_reader::skip_white_spaces(&mut self.data).await?
         } else {
-            let next_pos = match async_reader::skip_white_spaces_and_get_next(&mut self.data).await
-            {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
+            let next_pos = async_reader::skip_white_spaces_and_get_next(&mut self.data).await?;
 
             match next_pos.value {
                 crate::consts::CLOSE_ARRAY => {
-                    return None;
+                    self.finished = true;
+                    return Ok(None);
                 }
-                crate::consts::COMMA => match async_reader::skip_white_spaces(&mut self.data).await
-                {
-                    Ok(value) => value,
-                    Err(err) => return Some(Err(err)),
-                },
+                crate::consts::COMMA => async_reader::skip_white_spaces(&mut self.data).await?,
                 _ => {
-                    return Some(Err(JsonParseError::new(format!(
+                    return Err(JsonParseError::new(format!(
                         "Invalid token found ['{}'] at position {}",
                         next_pos.value as char, next_pos.pos
-                    ))));
+                    )));
                 }
             }
         };
 
-        match start_value.value {
-            crate::consts::CLOSE_ARRAY => {
-                return None;
-            }
-            crate::consts::DOUBLE_QUOTE => {
-                match async_reader::find_the_end_of_the_string(&mut self.data).await {
-                    Ok(_) => {}
-                    Err(err) => return Some(Err(err)),
-                }
-            }
+        if start_value.value == crate::consts::CLOSE_ARRAY {
+            self.finished = true;
+            return Ok(None);
+        }
 
-            crate::consts::OPEN_ARRAY => {
-                match async_reader::find_the_end_of_array(&mut self.data).await {
-                    Ok(_) => {}
-                    Err(err) => return Some(Err(err)),
-                }
-            }
+        Ok(Some(start_value))
+    }
+}
 
-            crate::consts::OPEN_BRACKET => {
-                match async_reader::find_the_end_of_json(&mut self.data).await {
-                    Ok(_) => {}
-                    Err(err) => return Some(Err(err)),
-                }
-            }
-            crate::consts::START_OF_NULL_UPPER_CASE => {
-                if let Err(err) = async_reader::check_json_symbol(&mut self.data, "null").await {
-                    return Some(Err(err));
-                }
-            }
+impl<TArrayOfBytesIterator: ArrayOfBytesIteratorAsync + Send + Sync>
+    JsonArrayIteratorAsync<TArrayOfBytesIterator>
+{
+    /// [`Self::get_next`] without allocating for every element: the element is read into a
+    /// buffer the iterator keeps, and the slice lent out is valid until the next call.
+    pub async fn get_next_ref(&mut self) -> Option<Result<&[u8], JsonParseError>> {
+        let start_value = match self.next_element_start().await {
+            Ok(start_value) => start_value?,
+            Err(err) => return Some(Err(err)),
+        };
 
-            crate::consts::START_OF_NULL_LOWER_CASE => {
-                if let Err(err) = async_reader::check_json_symbol(&mut self.data, "null").await {
-                    return Some(Err(err));
-                }
-            }
+        self.buffer.clear();
 
-            crate::consts::START_OF_TRUE_UPPER_CASE => {
-                if let Err(err) = async_reader::check_json_symbol(&mut self.data, "true").await {
-                    return Some(Err(err));
-                }
-            }
+        let mut src = RecordingIterator::new(&mut self.data, &mut self.buffer);
 
-            crate::consts::START_OF_TRUE_LOWER_CASE => {
-                if let Err(err) = async_reader::check_json_symbol(&mut self.data, "true").await {
-                    return Some(Err(err));
-                }
-            }
-
-            crate::consts::START_OF_FALSE_UPPER_CASE => {
-                if let Err(err) = async_reader::check_json_symbol(&mut self.data, "false").await {
-                    return Some(Err(err));
-                }
-            }
+        if let Err(err) = skip_element(&mut src, start_value).await {
+            return Some(Err(err));
+        }
 
-            crate::consts::START_OF_FALSE_LOWER_CASE => {
-                if let Err(err) = async_reader::check_json_symbol(&mut self.data, "false").await {
-                    return Some(Err(err));
-                }
-            }
+        if let Err(err) = release_read(&mut self.data).await {
+            return Some(Err(err.into()));
+        }
 
-            _ => {
-                if async_reader::is_number(start_value.value) {
-                    match async_reader::find_the_end_of_the_number(&mut self.data).await {
-                        Ok(_) => {}
-                        Err(err) => return Some(Err(err)),
-                    }
-                } else {
-                    return Some(Err(JsonParseError::new(format!(
-                        "Invalid token found '{}' at position {}",
-                        start_value.value as char, start_value.pos
-                    ))));
-                }
-            }
-        };
+        Some(Ok(&self.buffer))
+    }
+}
 
-        let result = self
-            .data
-            .get_slice_to_current_pos(start_value.pos)
-            .await
-            .unwrap();
-        return Some(Ok(result));
+// Reads past the element that starts with `start_value`.
+async fn skip_element(
+    src: &mut impl ArrayOfBytesIteratorAsync,
+    start_value: NextValue,
+) -> Result<(), JsonParseError> {
+    match start_value.value {
+        crate::consts::DOUBLE_QUOTE => {
+            async_reader::find_the_end_of_the_string(src).await?;
+        }
+        crate::consts::OPEN_ARRAY => {
+            async_reader::find_the_end_of_array(src).await?;
+        }
+        crate::consts::OPEN_BRACKET => {
+            async_reader::find_the_end_of_json(src).await?;
+        }
+        crate::consts::START_OF_NULL_UPPER_CASE | crate::consts::START_OF_NULL_LOWER_CASE => {
+            async_reader::check_json_symbol(src, "null").await?;
+        }
+        crate::consts::START_OF_TRUE_UPPER_CASE | crate::consts::START_OF_TRUE_LOWER_CASE => {
+            async_reader::check_json_symbol(src, "true").await?;
+        }
+        crate::consts::START_OF_FALSE_UPPER_CASE | crate::consts::START_OF_FALSE_LOWER_CASE => {
+            async_reader::check_json_symbol(src, "false").await?;
+        }
+        value if async_reader::is_number(value) => {
+            async_reader::find_the_end_of_the_number(src).await?;
+        }
+        value => {
+            return Err(JsonParseError::new(format!(
+                "Invalid token found '{}' at position {}",
+                value as char, start_value.pos
+            )));
+        }
     }
+
+    Ok(())
 }
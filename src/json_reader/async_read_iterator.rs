@@ -0,0 +1,347 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Mutex, PoisonError};
+
+use rust_extensions::array_of_bytes_iterator::{ArrayOfBytesIteratorAsync, NextValue};
+use tokio::io::{AsyncRead, AsyncReadExt};
+
+const DEFAULT_CHUNK_SIZE: usize = 64 * 1024;
+
+/// An [`ArrayOfBytesIteratorAsync`] over a [`tokio::io::AsyncRead`] - a file, a socket, an HTTP
+/// body - that reads the source as it goes instead of loading it up front.
+///
+/// Positions are offsets in the payload. Every slice is handed out as an owned copy, so the bytes
+/// before the start of the last slice handed out are never asked for again by the readers and are
+/// dropped as reading goes on: memory use is bounded by the largest value, not the payload. A
+/// slice from before that point is an `InvalidInput` error.
+///
+/// `get_slice_to_end` ends where reading has got to.
+pub struct AsyncReadIterator<TRead: AsyncRead + Unpin + Send> {
+    // Only ever touched through `&mut self`: the lock is there for `Sync`, not taken.
+    src: Mutex<TRead>,
+    // The bytes from `buffer_start` on. The one at `pos` is always loaded if the source has it,
+    // so `peek_value` can answer without reading.
+    buffer: Vec<u8>,
+    buffer_start: usize,
+    pos: usize,
+    keep_from: AtomicUsize,
+    chunk_size: usize,
+    eof: bool,
+}
+
+impl<TRead: AsyncRead + Unpin + Send> AsyncReadIterator<TRead> {
+    /// Reads the first chunk of `src`, 64 KiB at most.
+    pub async fn new(src: TRead) -> std::io::Result<Self> {
+        Self::new_with_chunk_size(src, DEFAULT_CHUNK_SIZE).await
+    }
+
+    /// `chunk_size` is how much is read from the source at a time.
+    pub async fn new_with_chunk_size(src: TRead, chunk_size: usize) -> std::io::Result<Self> {
+        let mut result = Self {
+            src: Mutex::new(src),
+            buffer: Vec::new(),
+            buffer_start: 0,
+            pos: 0,
+            keep_from: AtomicUsize::new(0),
+            chunk_size: chunk_size.max(1),
+            eof: false,
+        };
+
+        result.fill().await?;
+        Ok(result)
+    }
+
+    /// The source, with whatever has been read from it but not yet handed out lost.
+    pub fn into_inner(self) -> TRead {
+        self.src
+            .into_inner()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn loaded_to(&self) -> usize {
+        self.buffer_start + self.buffer.len()
+    }
+
+    // Drops what can no longer be asked for, then reads until there is something past `pos` or
+    // the source is over.
+    async fn fill(&mut self) -> std::io::Result<()> {
+        let keep_from = self.keep_from.load(Ordering::Relaxed).min(self.pos);
+
+        if keep_from > self.buffer_start {
+            self.buffer.drain(..keep_from - self.buffer_start);
+            self.buffer_start = keep_from;
+        }
+
+        let src = self.src.get_mut().unwrap_or_else(PoisonError::into_inner);
+
+        while !self.eof && self.pos >= self.buffer_start + self.buffer.len() {
+            let len = self.buffer.len();
+            self.buffer.resize(len + self.chunk_size, 0);
+
+            let read = src.read(&mut self.buffer[len..]).await;
+            self.buffer.truncate(len + *read.as_ref().unwrap_or(&0));
+
+            if read? == 0 {
+                self.eof = true;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn index_of(&self, pos: usize) -> std::io::Result<usize> {
+        if pos < self.buffer_start {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!(
+                    "Position {} has already been released. The earliest one kept is {}",
+                    pos, self.buffer_start
+                ),
+            ));
+        }
+
+        Ok(pos - self.buffer_start)
+    }
+}
+
+#[async_trait::async_trait]
+impl<TRead: AsyncRead + Unpin + Send> ArrayOfBytesIteratorAsync for AsyncReadIterator<TRead> {
+    fn peek_value(&self) -> Option<NextValue> {
+        let value = *self.buffer.get(self.pos - self.buffer_start)?;
+
+        Some(NextValue {
+            pos: self.pos,
+            value,
+        })
+    }
+
+    async fn get_next(&mut self) -> std::io::Result<Option<NextValue>> {
+        let result = match self.peek_value() {
+            Some(result) => result,
+            None => return Ok(None),
+        };
+
+        self.pos += 1;
+
+        if self.pos == self.loaded_to() {
+            self.fill().await?;
+        }
+
+        Ok(Some(result))
+    }
+
+    fn get_pos(&self) -> usize {
+        self.pos
+    }
+
+    async fn get_slice_to_current_pos(&self, from_pos: usize) -> std::io::Result<Vec<u8>> {
+        let from = self.index_of(from_pos)?;
+        let result = self.buffer[from..self.pos - self.buffer_start].to_vec();
+
+        self.keep_from.fetch_max(from_pos, Ordering::Relaxed);
+        Ok(result)
+    }
+
+    async fn get_slice_to_end(&self, from_pos: usize) -> std::io::Result<Vec<u8>> {
+        let from = self.index_of(from_pos)?;
+        Ok(self.buffer[from..].to_vec())
+    }
+
+    // The bytes read past are handed out here and not kept.
+    async fn advance(&mut self, amount: usize) -> std::io::Result<Option<Vec<u8>>> {
+        let mut result = Vec::with_capacity(amount.min(self.chunk_size));
+
+        while result.len() < amount {
+            let from = self.pos - self.buffer_start;
+            let available = &self.buffer[from..];
+
+            if available.is_empty() {
+                return Ok(None);
+            }
+
+            let taken = available.len().min(amount - result.len());
+            result.extend_from_slice(&available[..taken]);
+
+            self.pos += taken;
+            self.keep_from.store(self.pos, Ordering::Relaxed);
+
+            if self.pos == self.loaded_to() {
+                self.fill().await?;
+            }
+        }
+
+        Ok(Some(result))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::pin::Pin;
+    use std::task::{Context, Poll};
+
+    use rust_extensions::array_of_bytes_iterator::ArrayOfBytesIteratorAsync;
+    use tokio::io::{AsyncRead, ReadBuf};
+
+    use super::AsyncReadIterator;
+    use crate::json_reader::{
+        JsonArrayAtPathIteratorAsync, JsonArrayIteratorAsync, JsonLIteratorAsync,
+    };
+
+    // Hands out at most three bytes a read, the way a socket hands out what has arrived.
+    struct Trickle(Vec<u8>);
+
+    impl AsyncRead for Trickle {
+        fn poll_read(
+            mut self: Pin<&mut Self>,
+            _: &mut Context<'_>,
+            buf: &mut ReadBuf<'_>,
+        ) -> Poll<std::io::Result<()>> {
+            let len = self.0.len().min(buf.remaining()).min(3);
+            buf.put_slice(&self.0[..len]);
+            self.0.drain(..len);
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    #[tokio::test]
+    async fn json_l_is_read_from_a_reader() {
+        let mut payload = String::new();
+        for i in 0..200 {
+            payload.push_str(&format!(
+                "{{\"id\":{},\"pad\":\"{}\"}}\n",
+                i,
+                "x".repeat(i % 13)
+            ));
+        }
+
+        for skip_bad_records in [false, true] {
+            let src = Trickle(payload.clone().into_bytes());
+            let src = AsyncReadIterator::new_with_chunk_size(src, 16)
+                .await
+                .unwrap();
+            let mut iterator = JsonLIteratorAsync::new(src).await;
+
+            if skip_bad_records {
+                iterator = iterator.with_skip_bad_records();
+            }
+
+            let mut count = 0;
+
+            while let Some(record) = iterator.get_next_record().await {
+                let record = record.unwrap();
+                let expected = payload.lines().nth(count).unwrap();
+
+                assert_eq!(record.data, expected.as_bytes());
+                assert_eq!(
+                    &payload[record.offset..record.offset + expected.len()],
+                    expected
+                );
+
+                count += 1;
+            }
+
+            assert_eq!(count, 200);
+
+            // The lending reader asks for no slices, and has to let the source go all the same.
+            let src = Trickle(payload.clone().into_bytes());
+            let src = AsyncReadIterator::new_with_chunk_size(src, 16)
+                .await
+                .unwrap();
+            let mut iterator = JsonLIteratorAsync::new(src).await;
+
+            if skip_bad_records {
+                iterator = iterator.with_skip_bad_records();
+            }
+
+            let mut lines = payload.lines();
+
+            while let Some(record) = iterator.get_next_ref().await {
+                assert_eq!(record.unwrap(), lines.next().unwrap().as_bytes());
+            }
+
+            assert!(lines.next().is_none());
+        }
+    }
+
+    #[tokio::test]
+    async fn bytes_before_the_last_slice_are_released() {
+        let payload = "x".repeat(1000).into_bytes();
+        let mut src = AsyncReadIterator::new_with_chunk_size(Trickle(payload), 16)
+            .await
+            .unwrap();
+
+        let mut largest_buffer = 0;
+
+        for pos in 1..1000 {
+            src.get_next().await.unwrap().unwrap();
+
+            if pos % 10 == 0 {
+                let slice = src.get_slice_to_current_pos(pos - 10).await.unwrap();
+                assert_eq!(slice.len(), 10);
+            }
+
+            largest_buffer = largest_buffer.max(src.buffer.len());
+        }
+
+        assert!(largest_buffer < 64, "{}", largest_buffer);
+
+        let err = src.get_slice_to_current_pos(0).await.unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+    }
+
+    #[tokio::test]
+    async fn a_skipped_value_is_released_as_it_is_read_past() {
+        use crate::json_reader::bytes_of_array_reader::{async_reader, ReleasingIterator};
+
+        let mut payload = String::from("[");
+        for i in 0..500_000 {
+            payload.push_str(&format!("{},", i % 10));
+        }
+        payload.push_str("0] ");
+
+        let mut src = AsyncReadIterator::new(payload.as_bytes()).await.unwrap();
+        let start = src.peek_value().unwrap();
+
+        async_reader::find_the_end_of_the_object_value(
+            &mut ReleasingIterator::new(&mut src),
+            start.value,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(src.get_pos(), payload.len() - 1);
+        assert!(src.buffer.len() < 256 * 1024, "{}", src.buffer.len());
+
+        let json = format!(
+            r#"{{"included":{},"data":[1,2]}}"#,
+            &payload[..payload.len() - 1]
+        );
+        let src = AsyncReadIterator::new(json.as_bytes()).await.unwrap();
+        let mut iterator = JsonArrayAtPathIteratorAsync::new(src, "data").await;
+
+        assert_eq!(iterator.get_next().await.unwrap().unwrap(), b"1");
+        assert_eq!(iterator.get_next().await.unwrap().unwrap(), b"2");
+        assert!(iterator.get_next().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn array_is_read_from_a_reader() {
+        let json = br#"[{"a":1}, [2, 3], "four"]"#.to_vec();
+        let src = AsyncReadIterator::new(Trickle(json)).await.unwrap();
+
+        let mut iterator = JsonArrayIteratorAsync::new(src).await;
+        let mut items = Vec::new();
+
+        while let Some(item) = iterator.get_next().await {
+            items.push(item.unwrap());
+        }
+
+        assert_eq!(
+            items,
+            vec![
+                br#"{"a":1}"#.to_vec(),
+                b"[2, 3]".to_vec(),
+                br#""four""#.to_vec()
+            ]
+        );
+    }
+}
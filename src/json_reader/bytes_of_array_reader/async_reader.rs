@@ -55,9 +55,11 @@ pub async fn find_the_end_of_the_object_value(
             let result = find_the_end_of_json_object_or_array(src).await?;
             return Ok(result.pos + 1);
         }
-        _ => {
-            panic!("Somehow we are getting here")
-        }
+        _ => Err(JsonParseError::new(format!(
+            "Invalid token found '{}' at position {}. Expected the start of a value",
+            value_start as char,
+            src.get_pos()
+        ))),
     }
 }
 
@@ -66,7 +68,7 @@ pub async fn find_the_end_of_json_object_or_array(
 ) -> Result<NextValue, JsonParseError> {
     let start_pos = src.get_pos();
 
-    let next_value = src.get_next().await.unwrap();
+    let next_value = src.get_next().await?;
 
     if next_value.is_none() {
         return Err(JsonParseError::new(format!(
@@ -84,11 +86,13 @@ pub async fn find_the_end_of_json_object_or_array(
     {
         next_value.value
     } else {
-        panic!(
-            "Bug... It has to be {} or {} symbol",
+        return Err(JsonParseError::new(format!(
+            "Error reading value as object or array. Expected '{}' or '{}' at position {} but found '{}'",
             crate::consts::OPEN_BRACKET as char,
-            crate::consts::OPEN_ARRAY as char
-        )
+            crate::consts::OPEN_ARRAY as char,
+            next_value.pos,
+            next_value.value as char
+        )));
     };
 
     brackets.push(open_open_bracket);
@@ -98,7 +102,7 @@ pub async fn find_the_end_of_json_object_or_array(
     //    brackets.iter().map(|x| *x as char).collect::<Vec<char>>()
     //);
 
-    while let Some(next_value) = src.get_next().await.unwrap() {
+    while let Some(next_value) = src.get_next().await? {
         match next_value.value {
             crate::consts::DOUBLE_QUOTE => {
                 //let start = next_value.pos;
@@ -159,16 +163,14 @@ pub async fn find_the_end_of_json_object_or_array(
                         return Ok(next_value);
                     }
                 } else {
-                    let json_start =
-                        String::from_utf8(src.get_slice_to_current_pos(start_pos).await.unwrap())
-                            .unwrap();
+                    let json_start = src.get_slice_to_current_pos(start_pos).await?;
 
                     //  std::fs::write("/Users/amigin/Downloads/bug.jsonl", json_start.as_str())
                     //     .unwrap();
                     return Err(JsonParseError::new(format!(
                         "Error reading value as array. Start {}. Error pos {}. Open bracket '{}' does not match close bracket '{}'. Json: {}",
                         start_pos, next_value.pos, open_bracket as u8,  next_value.value as u8,
-                        &json_start[..256]
+                        crate::json_reader::preview(&json_start)
                     )));
                 }
             }
@@ -198,7 +200,7 @@ pub async fn find_the_end_of_json(
         // An empty object has no key to read. Mirrors the sync `find_the_end_of_json`; the `}` is
         // consumed so the position ends up past it, exactly as the non-empty path below leaves it.
         if key_start.value == crate::consts::CLOSE_BRACKET {
-            src.get_next().await.unwrap();
+            src.get_next().await?;
             return Ok(key_start.pos);
         }
 
@@ -225,7 +227,14 @@ pub async fn find_the_end_of_json(
 pub async fn find_the_end_of_array(
     src: &mut impl ArrayOfBytesIteratorAsync,
 ) -> Result<NextValue, JsonParseError> {
-    let _open_array_token = src.get_next().await.unwrap().unwrap();
+    let start_pos = src.get_pos();
+
+    if src.get_next().await?.is_none() {
+        return Err(JsonParseError::new(format!(
+            "Error reading value as array. Start {}. We reached the end of the payload",
+            start_pos
+        )));
+    }
 
     // Empty array: `]` can legally come first, and peeking straight for a value start rejects it.
     // Kept in step with the sync `find_the_end_of_array`, which had the same bug.
@@ -233,10 +242,9 @@ pub async fn find_the_end_of_array(
         skip_white_spaces_and_peek_expected_token(src, ExpectedJsonValueStartOrEndOfArray).await?;
 
     if first_token.value == crate::consts::CLOSE_ARRAY {
-        // Consume it, leaving the position past the end of this array. The peek above already
-        // proved the token is buffered, so this mirrors the unwrap the rest of this file uses.
-        let close_token = src.get_next().await.unwrap().unwrap();
-        return Ok(close_token);
+        // Consume it, leaving the position past the end of this array.
+        src.get_next().await?;
+        return Ok(first_token);
     }
 
     loop {
@@ -256,20 +264,55 @@ pub async fn find_the_end_of_array(
 pub async fn next_token_must_be(
     raw: &mut impl ArrayOfBytesIteratorAsync,
     token: u8,
-) -> FoundResult {
-    while let Some(next_value) = raw.get_next().await.unwrap() {
+) -> Result<FoundResult, JsonParseError> {
+    while let Some(next_value) = raw.get_next().await? {
         if is_space(next_value.value) {
             continue;
         }
 
         if next_value.value == token {
-            return FoundResult::Ok(next_value);
+            return Ok(FoundResult::Ok(next_value));
         } else {
-            return FoundResult::InvalidTokenFound(next_value);
+            return Ok(FoundResult::InvalidTokenFound(next_value));
+        }
+    }
+
+    return Ok(FoundResult::EndOfJson);
+}
+
+/// Moves `src` forward to `pos`, reading what is in between in bounded chunks. `src` can already
+/// be at `pos`, for a source opened at an offset.
+pub async fn advance_to(
+    src: &mut impl ArrayOfBytesIteratorAsync,
+    pos: usize,
+) -> Result<(), JsonParseError> {
+    const CHUNK_SIZE: usize = 64 * 1024;
+
+    let current_pos = src.get_pos();
+
+    if current_pos > pos {
+        return Err(JsonParseError::new(format!(
+            "Can not move back to position {}. The payload is already at {}",
+            pos, current_pos
+        )));
+    }
+
+    let mut remaining = pos - current_pos;
+
+    while remaining > 0 {
+        let amount = remaining.min(CHUNK_SIZE);
+
+        if src.advance(amount).await?.is_none() {
+            return Err(JsonParseError::new(format!(
+                "Position {} is beyond the end of the payload",
+                pos
+            )));
         }
+
+        remaining -= amount;
     }
 
-    return FoundResult::EndOfJson;
+    Ok(())
 }
 
 pub async fn skip_white_spaces(
@@ -281,7 +324,7 @@ pub async fn skip_white_spaces(
             return Ok(next_value);
         }
 
-        src.get_next().await.unwrap();
+        src.get_next().await?;
     }
 
     Err(JsonParseError::new(format!(
@@ -294,7 +337,7 @@ pub async fn skip_white_spaces_and_get_next(
     src: &mut impl ArrayOfBytesIteratorAsync,
 ) -> Result<NextValue, JsonParseError> {
     let start_pos = src.get_pos();
-    while let Some(next_value) = src.get_next().await.unwrap() {
+    while let Some(next_value) = src.get_next().await? {
         if next_value.value > 32 {
             return Ok(next_value);
         }
@@ -328,7 +371,7 @@ pub async fn skip_white_spaces_and_peek_expected_token(
             }
         }
 
-        src.get_next().await.unwrap();
+        src.get_next().await?;
     }
 
     Err(JsonParseError::new(format!(
@@ -342,7 +385,7 @@ pub async fn skip_white_spaces_and_get_expected_token(
     expected_token: impl ExpectedToken,
 ) -> Result<NextValue, JsonParseError> {
     let start_pos = src.get_pos();
-    while let Some(next_value) = src.get_next().await.unwrap() {
+    while let Some(next_value) = src.get_next().await? {
         if next_value.value > 32 {
             match expected_token.we_are_expecting_token(next_value.value) {
                 Ok(_) => {
@@ -371,28 +414,28 @@ pub async fn find_the_end_of_the_string(
 ) -> Result<NextValue, JsonParseError> {
     let start_pos = src.get_pos();
 
-    src.get_next().await.unwrap();
+    src.get_next().await?;
 
-    while let Some(next_value) = src.get_next().await.unwrap() {
+    while let Some(next_value) = src.get_next().await? {
         if next_value.value == '\\' as u8 {
-            src.get_next().await.unwrap();
+            src.get_next().await?;
             continue;
         }
 
         if next_value.value >= 0xF0 {
-            src.get_next().await.unwrap();
-            src.get_next().await.unwrap();
-            src.get_next().await.unwrap();
+            src.get_next().await?;
+            src.get_next().await?;
+            src.get_next().await?;
             continue;
         }
         if next_value.value >= 0xE0 {
-            src.get_next().await.unwrap();
-            src.get_next().await.unwrap();
+            src.get_next().await?;
+            src.get_next().await?;
             continue;
         }
 
         if next_value.value >= 0xC0 {
-            src.get_next().await.unwrap();
+            src.get_next().await?;
             continue;
         }
 
@@ -412,26 +455,26 @@ pub async fn skip_to_the_end_of_the_string(
 ) -> Result<NextValue, JsonParseError> {
     let start_pos = src.get_pos();
 
-    while let Some(next_value) = src.get_next().await.unwrap() {
+    while let Some(next_value) = src.get_next().await? {
         if next_value.value == '\\' as u8 {
-            src.get_next().await.unwrap();
+            src.get_next().await?;
             continue;
         }
 
         if next_value.value >= 0xF0 {
-            src.get_next().await.unwrap();
-            src.get_next().await.unwrap();
-            src.get_next().await.unwrap();
+            src.get_next().await?;
+            src.get_next().await?;
+            src.get_next().await?;
             continue;
         }
         if next_value.value >= 0xE0 {
-            src.get_next().await.unwrap();
-            src.get_next().await.unwrap();
+            src.get_next().await?;
+            src.get_next().await?;
             continue;
         }
 
         if next_value.value >= 0xC0 {
-            src.get_next().await.unwrap();
+            src.get_next().await?;
             continue;
         }
 
@@ -455,7 +498,7 @@ pub async fn find_the_end_of_the_number(
             return Ok(next_value);
         }
 
-        src.get_next().await.unwrap();
+        src.get_next().await?;
     }
 
     Err(JsonParseError::new(format!(
@@ -489,7 +532,7 @@ pub async fn check_json_symbol(
     symbol: &str,
 ) -> Result<(), JsonParseError> {
     let pos = src.get_pos();
-    let value = src.advance(symbol.len()).await.unwrap();
+    let value = src.advance(symbol.len()).await?;
 
     match value {
         Some(value) => {
@@ -515,7 +558,7 @@ pub async fn check_json_symbol(
         None => {
             return Err(JsonParseError::new(format!(
                 "Error Parsing {symbol}. Invalid token found ['{}'] at position {}",
-                String::from_utf8_lossy(src.get_slice_to_end(pos).await.unwrap().as_slice()),
+                String::from_utf8_lossy(src.get_slice_to_end(pos).await?.as_slice()),
                 pos
             )));
         }
@@ -1,5 +1,7 @@
 pub mod async_reader;
 mod expected_token;
+mod recording_iterator;
+pub(crate) use recording_iterator::{release_read, RecordingIterator, ReleasingIterator};
 pub mod sync_reader;
 pub use expected_token::*;
 use rust_extensions::array_of_bytes_iterator::NextValue;
@@ -0,0 +1,132 @@
+use rust_extensions::array_of_bytes_iterator::*;
+
+// Passes an async source through, copying every byte read from it into `buffer`. Scanning a
+// value through it leaves the value in a buffer the caller reuses, where asking the source for
+// the slice would allocate a `Vec` every time.
+pub(crate) struct RecordingIterator<'s, TArrayOfBytesIterator: ArrayOfBytesIteratorAsync> {
+    src: &'s mut TArrayOfBytesIterator,
+    buffer: &'s mut Vec<u8>,
+}
+
+impl<'s, TArrayOfBytesIterator: ArrayOfBytesIteratorAsync>
+    RecordingIterator<'s, TArrayOfBytesIterator>
+{
+    pub(crate) fn new(src: &'s mut TArrayOfBytesIterator, buffer: &'s mut Vec<u8>) -> Self {
+        Self { src, buffer }
+    }
+}
+
+#[async_trait::async_trait]
+impl<'s, TArrayOfBytesIterator: ArrayOfBytesIteratorAsync + Send + Sync> ArrayOfBytesIteratorAsync
+    for RecordingIterator<'s, TArrayOfBytesIterator>
+{
+    fn peek_value(&self) -> Option<NextValue> {
+        self.src.peek_value()
+    }
+
+    async fn get_next(&mut self) -> std::io::Result<Option<NextValue>> {
+        let result = self.src.get_next().await?;
+
+        if let Some(next_value) = result {
+            self.buffer.push(next_value.value);
+        }
+
+        Ok(result)
+    }
+
+    fn get_pos(&self) -> usize {
+        self.src.get_pos()
+    }
+
+    async fn get_slice_to_current_pos(&self, from_pos: usize) -> std::io::Result<Vec<u8>> {
+        self.src.get_slice_to_current_pos(from_pos).await
+    }
+
+    async fn get_slice_to_end(&self, from_pos: usize) -> std::io::Result<Vec<u8>> {
+        self.src.get_slice_to_end(from_pos).await
+    }
+
+    async fn advance(&mut self, amount: usize) -> std::io::Result<Option<Vec<u8>>> {
+        let result = self.src.advance(amount).await?;
+
+        if let Some(bytes) = &result {
+            self.buffer.extend_from_slice(bytes);
+        }
+
+        Ok(result)
+    }
+}
+
+// Passes an async source through, letting go of what has been read every `RELEASE_EVERY` bytes.
+// A value scanned through it to skip it is never asked for, so a source that drops what has been
+// read keeps no more than that much of it, however big the value is. A slice asked for from
+// before the last release - only an error message asks - starts where the source still has it.
+pub(crate) struct ReleasingIterator<'s, TArrayOfBytesIterator: ArrayOfBytesIteratorAsync> {
+    src: &'s mut TArrayOfBytesIterator,
+    released_to: usize,
+}
+
+const RELEASE_EVERY: usize = 64 * 1024;
+
+impl<'s, TArrayOfBytesIterator: ArrayOfBytesIteratorAsync>
+    ReleasingIterator<'s, TArrayOfBytesIterator>
+{
+    pub(crate) fn new(src: &'s mut TArrayOfBytesIterator) -> Self {
+        let released_to = src.get_pos();
+        Self { src, released_to }
+    }
+
+    async fn release_every_so_often(&mut self) -> std::io::Result<()> {
+        let pos = self.src.get_pos();
+
+        if pos - self.released_to >= RELEASE_EVERY {
+            release_read(self.src).await?;
+            self.released_to = pos;
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl<'s, TArrayOfBytesIterator: ArrayOfBytesIteratorAsync + Send + Sync> ArrayOfBytesIteratorAsync
+    for ReleasingIterator<'s, TArrayOfBytesIterator>
+{
+    fn peek_value(&self) -> Option<NextValue> {
+        self.src.peek_value()
+    }
+
+    async fn get_next(&mut self) -> std::io::Result<Option<NextValue>> {
+        let result = self.src.get_next().await?;
+        self.release_every_so_often().await?;
+        Ok(result)
+    }
+
+    fn get_pos(&self) -> usize {
+        self.src.get_pos()
+    }
+
+    async fn get_slice_to_current_pos(&self, from_pos: usize) -> std::io::Result<Vec<u8>> {
+        let from_pos = from_pos.max(self.released_to);
+        self.src.get_slice_to_current_pos(from_pos).await
+    }
+
+    async fn get_slice_to_end(&self, from_pos: usize) -> std::io::Result<Vec<u8>> {
+        let from_pos = from_pos.max(self.released_to);
+        self.src.get_slice_to_end(from_pos).await
+    }
+
+    async fn advance(&mut self, amount: usize) -> std::io::Result<Option<Vec<u8>>> {
+        let result = self.src.advance(amount).await?;
+        self.release_every_so_often().await?;
+        Ok(result)
+    }
+}
+
+// A source that drops what has been read - `AsyncReadIterator` - keeps everything from the start
+// of the last slice it handed out. Recorded values are never asked for, so an empty slice at the
+// current position is asked for instead, to let it go.
+pub(crate) async fn release_read(src: &mut impl ArrayOfBytesIteratorAsync) -> std::io::Result<()> {
+    src.get_slice_to_current_pos(src.get_pos()).await?;
+    Ok(())
+}
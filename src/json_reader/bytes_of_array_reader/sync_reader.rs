@@ -55,9 +55,11 @@ pub fn find_the_end_of_the_object_value(
             let result = find_the_end_of_json_object_or_array(src)?;
             return Ok(result.pos + 1);
         }
-        _ => {
-            panic!("Somehow we are getting here")
-        }
+        _ => Err(JsonParseError::new(format!(
+            "Invalid token found '{}' at position {}. Expected the start of a value",
+            value_start as char,
+            src.get_pos()
+        ))),
     }
 }
 
@@ -84,11 +86,13 @@ pub fn find_the_end_of_json_object_or_array(
     {
         next_value.value
     } else {
-        panic!(
-            "Bug... It has to be {} or {} symbol",
+        return Err(JsonParseError::new(format!(
+            "Error reading value as object or array. Expected '{}' or '{}' at position {} but found '{}'",
             crate::consts::OPEN_BRACKET as char,
-            crate::consts::OPEN_ARRAY as char
-        )
+            crate::consts::OPEN_ARRAY as char,
+            next_value.pos,
+            next_value.value as char
+        )));
     };
 
     brackets.push(open_open_bracket);
@@ -191,7 +195,14 @@ pub fn find_the_end_of_json(src: &impl ArrayOfBytesIterator) -> Result<usize, Js
 }
 
 pub fn find_the_end_of_array(src: &impl ArrayOfBytesIterator) -> Result<NextValue, JsonParseError> {
-    let _open_array_token = src.get_next().unwrap();
+    let start_pos = src.get_pos();
+
+    if src.get_next().is_none() {
+        return Err(JsonParseError::new(format!(
+            "Error reading value as array. Start {}. We reached the end of the payload",
+            start_pos
+        )));
+    }
 
     // An empty array holds no value to walk over, so `]` can legally come first. Peeking straight
     // for a value start (as this used to) rejected it, and an empty array nested inside another
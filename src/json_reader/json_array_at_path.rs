@@ -0,0 +1,438 @@
+use rust_extensions::array_of_bytes_iterator::*;
+
+use super::bytes_of_array_reader::*;
+use super::JsonParseError;
+
+/// Streams the elements of the array at `path` - object keys joined with `.`, such as `data` or
+/// `result.items` - inside a larger document. An empty path is an array at the root.
+///
+/// The fields on the way are read past, not kept, so the array can sit behind anything. The
+/// fields registered with [`Self::with_capture`] are kept: the ones before the array can be read
+/// with [`Self::get_captured`] once the first element is out, and the ones after it once
+/// `get_next` has returned `None`.
+pub struct JsonArrayAtPathIterator<TArrayOfBytesIterator: ArrayOfBytesIterator> {
+    data: TArrayOfBytesIterator,
+    walk: PathWalk,
+}
+
+impl<TArrayOfBytesIterator: ArrayOfBytesIterator> JsonArrayAtPathIterator<TArrayOfBytesIterator> {
+    pub fn new(data: TArrayOfBytesIterator, path: &str) -> Self {
+        Self {
+            data,
+            walk: PathWalk::new(path),
+        }
+    }
+
+    /// Keeps the value at `path`, whatever its type, as it is read past. A key in `path` may lead
+    /// through objects the array is not in, such as `meta.total`.
+    pub fn with_capture(mut self, path: &str) -> Self {
+        self.walk.add_capture(path);
+        self
+    }
+
+    /// The raw JSON of a field registered with [`Self::with_capture`], once it has been read.
+    pub fn get_captured(&self, path: &str) -> Option<&[u8]> {
+        self.walk.get_captured(path)
+    }
+
+    pub fn get_src_slice(&self) -> &[u8] {
+        self.data.get_src_slice()
+    }
+
+    pub fn get_next(&mut self) -> Option<Result<&[u8], JsonParseError>> {
+        if self.walk.finished {
+            return None;
+        }
+
+        match self.read_next() {
+            Ok(Some(start)) => Some(Ok(self.data.get_slice_to_current_pos(start))),
+            Ok(None) => {
+                self.walk.finished = true;
+                None
+            }
+            Err(err) => {
+                self.walk.finished = true;
+                Some(Err(err))
+            }
+        }
+    }
+
+    // The start of the next element, or `None` once the array and the document after it have
+    // been read.
+    fn read_next(&mut self) -> Result<Option<usize>, JsonParseError> {
+        if !self.walk.array_found {
+            self.open_root()?;
+
+            if !self.walk.array_found && !self.walk_fields()? {
+                return Err(self.walk.not_found());
+            }
+
+            self.walk.array_found = true;
+        }
+
+        let first_element = std::mem::replace(&mut self.walk.first_element, false);
+
+        let token = if first_element {
+            sync_reader::skip_white_spaces_and_peek_expected_token(
+                &self.data,
+                ExpectedJsonValueStartOrEndOfArray,
+            )?
+        } else {
+            let token = sync_reader::skip_white_spaces_and_get_expected_token(
+                &self.data,
+                ExpectedEndOfArrayOrComma,
+            )?;
+
+            if token.value == crate::consts::COMMA {
+                sync_reader::skip_white_spaces_and_peek_expected_token(
+                    &self.data,
+                    ExpectedJsonValueStart,
+                )?
+            } else {
+                token
+            }
+        };
+
+        if token.value == crate::consts::CLOSE_ARRAY {
+            // Only peeked when the array is empty.
+            if first_element {
+                self.data.get_next();
+            }
+
+            self.walk_fields()?;
+            return Ok(None);
+        }
+
+        sync_reader::find_the_end_of_the_object_value(&self.data, token.value)?;
+        Ok(Some(token.pos))
+    }
+
+    fn open_root(&mut self) -> Result<(), JsonParseError> {
+        let token = if self.walk.is_root_array() {
+            crate::consts::OPEN_ARRAY
+        } else {
+            crate::consts::OPEN_BRACKET
+        };
+
+        let found = sync_reader::next_token_must_be(&self.data, token);
+        self.walk.root_opened(found)
+    }
+
+    // `Ok(true)` once the array at the path is open, `Ok(false)` once the document is over.
+    fn walk_fields(&mut self) -> Result<bool, JsonParseError> {
+        while self.walk.open_objects > 0 {
+            let token = if self.walk.first_field {
+                sync_reader::skip_white_spaces_and_get_expected_token(
+                    &self.data,
+                    ExpectedJsonObjectKeyStart,
+                )?
+            } else {
+                let token = sync_reader::skip_white_spaces_and_get_expected_token(
+                    &self.data,
+                    ExpectedTokenJsonObjectSeparatorOrCloseBracket,
+                )?;
+
+                if token.value == crate::consts::COMMA {
+                    PathWalk::key_after_comma(
+                        sync_reader::skip_white_spaces_and_get_expected_token(
+                            &self.data,
+                            ExpectedJsonObjectKeyStart,
+                        )?,
+                    )?
+                } else {
+                    token
+                }
+            };
+
+            if self.walk.close_object(token)? {
+                continue;
+            }
+
+            sync_reader::skip_to_the_end_of_the_string(&self.data)?;
+            let key = field_key(self.data.get_slice_to_current_pos(token.pos));
+
+            sync_reader::skip_white_spaces_and_get_expected_token(
+                &self.data,
+                ExpectedJsonObjectKeyValueSeparator,
+            )?;
+
+            let value_start = sync_reader::skip_white_spaces_and_peek_expected_token(
+                &self.data,
+                ExpectedJsonValueStart,
+            )?;
+
+            match self.walk.field_role(&key, value_start)? {
+                FieldRole::Array => {
+                    self.data.get_next();
+                    return Ok(true);
+                }
+                FieldRole::Enter => {
+                    self.data.get_next();
+                    self.walk.enter(key);
+                }
+                FieldRole::Capture(index) => {
+                    sync_reader::find_the_end_of_the_object_value(&self.data, value_start.value)?;
+                    let value = self.data.get_slice_to_current_pos(value_start.pos).to_vec();
+                    self.walk.capture(index, value);
+                }
+                FieldRole::Skip => {
+                    sync_reader::find_the_end_of_the_object_value(&self.data, value_start.value)?;
+                }
+            }
+        }
+
+        Ok(false)
+    }
+}
+
+pub(crate) enum FieldRole {
+    Array,
+    Enter,
+    Capture(usize),
+    Skip,
+}
+
+// Where a walk to the array is and what it is looking for, shared by the sync and async
+// iterators: only reading the bytes differs.
+pub(crate) struct PathWalk {
+    path: String,
+    array: Vec<String>,
+    captures: Vec<(String, Vec<String>)>,
+    captured: Vec<Option<Vec<u8>>>,
+    // The keys of the objects the walk is in, below the root one.
+    parents: Vec<String>,
+    pub(crate) open_objects: usize,
+    pub(crate) first_field: bool,
+    pub(crate) array_found: bool,
+    pub(crate) first_element: bool,
+    pub(crate) finished: bool,
+}
+
+impl PathWalk {
+    pub(crate) fn new(path: &str) -> Self {
+        Self {
+            path: path.to_string(),
+            array: split_path(path),
+            captures: Vec::new(),
+            captured: Vec::new(),
+            parents: Vec::new(),
+            open_objects: 0,
+            first_field: true,
+            array_found: false,
+            first_element: true,
+            finished: false,
+        }
+    }
+
+    pub(crate) fn add_capture(&mut self, path: &str) {
+        self.captures.push((path.to_string(), split_path(path)));
+        self.captured.push(None);
+    }
+
+    pub(crate) fn get_captured(&self, path: &str) -> Option<&[u8]> {
+        let index = self.captures.iter().position(|(itm, _)| itm == path)?;
+        self.captured[index].as_deref()
+    }
+
+    pub(crate) fn capture(&mut self, index: usize, value: Vec<u8>) {
+        self.captured[index] = Some(value);
+    }
+
+    pub(crate) fn is_root_array(&self) -> bool {
+        self.array.is_empty()
+    }
+
+    pub(crate) fn not_found(&self) -> JsonParseError {
+        JsonParseError::new(format!("No array found at path '{}'", self.path))
+    }
+
+    pub(crate) fn root_opened(&mut self, found: FoundResult) -> Result<(), JsonParseError> {
+        let expected = if self.is_root_array() {
+            "array"
+        } else {
+            "object"
+        };
+
+        match found {
+            FoundResult::Ok(_) => {
+                if self.is_root_array() {
+                    self.array_found = true;
+                } else {
+                    self.open_objects = 1;
+                }
+
+                Ok(())
+            }
+            FoundResult::EndOfJson => Err(JsonParseError::new(format!(
+                "Can not find the start of the root {}",
+                expected
+            ))),
+            FoundResult::InvalidTokenFound(value) => Err(JsonParseError::new(format!(
+                "We were looking for the start of the root {} but found '{}' at position {}",
+                expected, value.value as char, value.pos
+            ))),
+        }
+    }
+
+    // `Ok(true)` when `token` closed an object; the opening quote of a key otherwise.
+    pub(crate) fn close_object(&mut self, token: NextValue) -> Result<bool, JsonParseError> {
+        if token.value != crate::consts::CLOSE_BRACKET {
+            self.first_field = false;
+            return Ok(false);
+        }
+
+        self.open_objects -= 1;
+        self.parents.pop();
+        self.first_field = false;
+        Ok(true)
+    }
+
+    // `ExpectedJsonObjectKeyStart` lets the `}` of an empty object through, which can not
+    // follow a `,`.
+    pub(crate) fn key_after_comma(token: NextValue) -> Result<NextValue, JsonParseError> {
+        if token.value == crate::consts::CLOSE_BRACKET {
+            return Err(JsonParseError::new(format!(
+                "Expected a key after ',' but found '}}' at position {}",
+                token.pos
+            )));
+        }
+
+        Ok(token)
+    }
+
+    pub(crate) fn enter(&mut self, key: String) {
+        self.parents.push(key);
+        self.open_objects += 1;
+        self.first_field = true;
+    }
+
+    pub(crate) fn field_role(
+        &self,
+        key: &str,
+        value_start: NextValue,
+    ) -> Result<FieldRole, JsonParseError> {
+        let depth = self.parents.len();
+        let on_path = |path: &[String]| {
+            path.len() > depth && path[depth] == key && path.starts_with(&self.parents)
+        };
+
+        if !self.array_found && self.array.len() == depth + 1 && on_path(&self.array) {
+            if value_start.value != crate::consts::OPEN_ARRAY {
+                return Err(JsonParseError::new(format!(
+                    "The value at path '{}' is not an array. Found '{}' at position {}",
+                    self.path, value_start.value as char, value_start.pos
+                )));
+            }
+
+            return Ok(FieldRole::Array);
+        }
+
+        let capture = self
+            .captures
+            .iter()
+            .position(|(_, path)| path.len() == depth + 1 && on_path(path));
+
+        if let Some(index) = capture {
+            return Ok(FieldRole::Capture(index));
+        }
+
+        let leads_on = (!self.array_found && on_path(&self.array))
+            || self.captures.iter().any(|(_, path)| on_path(path));
+
+        if leads_on && value_start.value == crate::consts::OPEN_BRACKET {
+            return Ok(FieldRole::Enter);
+        }
+
+        Ok(FieldRole::Skip)
+    }
+}
+
+fn split_path(path: &str) -> Vec<String> {
+    path.split('.')
+        .filter(|itm| !itm.is_empty())
+        .map(|itm| itm.to_string())
+        .collect()
+}
+
+// A key is matched as written in the document, between its quotes.
+pub(crate) fn field_key(raw: &[u8]) -> String {
+    String::from_utf8_lossy(&raw[1..raw.len() - 1]).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read_all(
+        iterator: &mut JsonArrayAtPathIterator<SliceIterator>,
+    ) -> Vec<Result<String, String>> {
+        let mut result = Vec::new();
+
+        while let Some(item) = iterator.get_next() {
+            result.push(
+                item.map(|item| String::from_utf8(item.to_vec()).unwrap())
+                    .map_err(|err| err.to_string()),
+            );
+        }
+
+        result
+    }
+
+    #[test]
+    fn elements_of_a_nested_array() {
+        let src = br#"{"meta": {"total": 3, "page": {"n": 1}}, "skip": [1, {"data": [0]}],
+            "result": {"count": 2, "items": [{"id": 1}, [2], "three"], "after": true}, "tail": null}"#;
+
+        let mut iterator = JsonArrayAtPathIterator::new(SliceIterator::new(src), "result.items")
+            .with_capture("meta.total")
+            .with_capture("result.after")
+            .with_capture("tail")
+            .with_capture("missing");
+
+        assert_eq!(iterator.get_next().unwrap().unwrap(), br#"{"id": 1}"#);
+        assert_eq!(iterator.get_captured("meta.total").unwrap(), b"3");
+        assert!(iterator.get_captured("result.after").is_none());
+
+        assert_eq!(
+            read_all(&mut iterator),
+            vec![Ok("[2]".to_string()), Ok("\"three\"".to_string())]
+        );
+
+        assert_eq!(iterator.get_captured("result.after").unwrap(), b"true");
+        assert_eq!(iterator.get_captured("tail").unwrap(), b"null");
+        assert!(iterator.get_captured("missing").is_none());
+    }
+
+    #[test]
+    fn empty_root_and_missing_arrays() {
+        let mut iterator =
+            JsonArrayAtPathIterator::new(SliceIterator::new(br#"{"data": []}"#), "data");
+        assert!(read_all(&mut iterator).is_empty());
+
+        let mut iterator = JsonArrayAtPathIterator::new(SliceIterator::new(b" [1, 2] "), "");
+        assert_eq!(
+            read_all(&mut iterator),
+            vec![Ok("1".to_string()), Ok("2".to_string())]
+        );
+
+        let mut iterator =
+            JsonArrayAtPathIterator::new(SliceIterator::new(br#"{"meta": {"data": []}}"#), "data");
+        let result = read_all(&mut iterator);
+        assert_eq!(result.len(), 1);
+        assert!(result[0]
+            .as_ref()
+            .unwrap_err()
+            .contains("No array found at path 'data'"));
+
+        let mut iterator =
+            JsonArrayAtPathIterator::new(SliceIterator::new(br#"{"data": {"a": 1}}"#), "data");
+        assert!(read_all(&mut iterator)[0]
+            .as_ref()
+            .unwrap_err()
+            .contains("is not an array"));
+
+        let mut iterator =
+            JsonArrayAtPathIterator::new(SliceIterator::new(br#"{"a": 1,}"#), "data");
+        assert!(read_all(&mut iterator)[0].is_err());
+    }
+}
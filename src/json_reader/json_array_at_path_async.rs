@@ -0,0 +1,216 @@
+use rust_extensions::array_of_bytes_iterator::*;
+
+use super::bytes_of_array_reader::*;
+use super::json_array_at_path::{field_key, FieldRole, PathWalk};
+use super::{JsonParseError, JsonValueReaderOwned};
+
+/// The async counterpart of [`JsonArrayAtPathIterator`](super::JsonArrayAtPathIterator): streams
+/// the elements of the array at `path`, such as `data` or `result.items`, from an
+/// [`ArrayOfBytesIteratorAsync`].
+///
+/// The fields before the array are read past, not kept. Over an `AsyncReadIterator` a skipped
+/// field is dropped as it is read past, so memory use is bounded by the largest element or
+/// captured field, however big the fields skipped on the way are.
+pub struct JsonArrayAtPathIteratorAsync<TArrayOfBytesIterator: ArrayOfBytesIteratorAsync> {
+    data: TArrayOfBytesIterator,
+    walk: PathWalk,
+}
+
+impl<TArrayOfBytesIterator: ArrayOfBytesIteratorAsync + Send + Sync>
+    JsonArrayAtPathIteratorAsync<TArrayOfBytesIterator>
+{
+    // Nothing is read here: the walk to the array is done by the first `get_next`.
+    pub async fn new(data: TArrayOfBytesIterator, path: &str) -> Self {
+        Self {
+            data,
+            walk: PathWalk::new(path),
+        }
+    }
+
+    /// Keeps the value at `path`, whatever its type, as it is read past - see
+    /// [`JsonArrayAtPathIterator::with_capture`](super::JsonArrayAtPathIterator::with_capture).
+    pub fn with_capture(mut self, path: &str) -> Self {
+        self.walk.add_capture(path);
+        self
+    }
+
+    /// The raw JSON of a field registered with [`Self::with_capture`], once it has been read.
+    pub fn get_captured(&self, path: &str) -> Option<&[u8]> {
+        self.walk.get_captured(path)
+    }
+
+    /// The next element, read as `T`. An element that is not a `T` is an `Err` for that
+    /// element only; the iteration can go on.
+    pub async fn read_as<T: JsonValueReaderOwned>(&mut self) -> Option<Result<T, JsonParseError>> {
+        let item = self.get_next().await?;
+        Some(item.and_then(|bytes| T::from_json_slice(&bytes)))
+    }
+
+    pub async fn get_next(&mut self) -> Option<Result<Vec<u8>, JsonParseError>> {
+        if self.walk.finished {
+            return None;
+        }
+
+        let result = match self.read_next().await {
+            Ok(Some(start)) => self
+                .data
+                .get_slice_to_current_pos(start)
+                .await
+                .map_err(JsonParseError::from),
+            Ok(None) => {
+                self.walk.finished = true;
+                return None;
+            }
+            Err(err) => Err(err),
+        };
+
+        if result.is_err() {
+            self.walk.finished = true;
+        }
+
+        Some(result)
+    }
+
+    // The start of the next element, or `None` once the array and the document after it have
+    // been read.
+    async fn read_next(&mut self) -> Result<Option<usize>, JsonParseError> {
+        if !self.walk.array_found {
+            self.open_root().await?;
+
+            if !self.walk.array_found && !self.walk_fields().await? {
+                return Err(self.walk.not_found());
+            }
+
+            self.walk.array_found = true;
+        }
+
+        let first_element = std::mem::replace(&mut self.walk.first_element, false);
+
+        let token = if first_element {
+            async_reader::skip_white_spaces_and_peek_expected_token(
+                &mut self.data,
+                ExpectedJsonValueStartOrEndOfArray,
+            )
+            .await?
+        } else {
+            let token = async_reader::skip_white_spaces_and_get_expected_token(
+                &mut self.data,
+                ExpectedEndOfArrayOrComma,
+            )
+            .await?;
+
+            if token.value == crate::consts::COMMA {
+                async_reader::skip_white_spaces_and_peek_expected_token(
+                    &mut self.data,
+                    ExpectedJsonValueStart,
+                )
+                .await?
+            } else {
+                token
+            }
+        };
+
+        if token.value == crate::consts::CLOSE_ARRAY {
+            // Only peeked when the array is empty.
+            if first_element {
+                self.data.get_next().await?;
+            }
+
+            self.walk_fields().await?;
+            return Ok(None);
+        }
+
+        async_reader::find_the_end_of_the_object_value(&mut self.data, token.value).await?;
+        Ok(Some(token.pos))
+    }
+
+    async fn open_root(&mut self) -> Result<(), JsonParseError> {
+        let token = if self.walk.is_root_array() {
+            crate::consts::OPEN_ARRAY
+        } else {
+            crate::consts::OPEN_BRACKET
+        };
+
+        let found = async_reader::next_token_must_be(&mut self.data, token).await?;
+        self.walk.root_opened(found)
+    }
+
+    // `Ok(true)` once the array at the path is open, `Ok(false)` once the document is over.
+    async fn walk_fields(&mut self) -> Result<bool, JsonParseError> {
+        while self.walk.open_objects > 0 {
+            let token = if self.walk.first_field {
+                async_reader::skip_white_spaces_and_get_expected_token(
+                    &mut self.data,
+                    ExpectedJsonObjectKeyStart,
+                )
+                .await?
+            } else {
+                let token = async_reader::skip_white_spaces_and_get_expected_token(
+                    &mut self.data,
+                    ExpectedTokenJsonObjectSeparatorOrCloseBracket,
+                )
+                .await?;
+
+                if token.value == crate::consts::COMMA {
+                    PathWalk::key_after_comma(
+                        async_reader::skip_white_spaces_and_get_expected_token(
+                            &mut self.data,
+                            ExpectedJsonObjectKeyStart,
+                        )
+                        .await?,
+                    )?
+                } else {
+                    token
+                }
+            };
+
+            if self.walk.close_object(token)? {
+                continue;
+            }
+
+            async_reader::skip_to_the_end_of_the_string(&mut self.data).await?;
+            let key = field_key(&self.data.get_slice_to_current_pos(token.pos).await?);
+
+            async_reader::skip_white_spaces_and_get_expected_token(
+                &mut self.data,
+                ExpectedJsonObjectKeyValueSeparator,
+            )
+            .await?;
+
+            let value_start = async_reader::skip_white_spaces_and_peek_expected_token(
+                &mut self.data,
+                ExpectedJsonValueStart,
+            )
+            .await?;
+
+            match self.walk.field_role(&key, value_start)? {
+                FieldRole::Array => {
+                    self.data.get_next().await?;
+                    return Ok(true);
+                }
+                FieldRole::Enter => {
+                    self.data.get_next().await?;
+                    self.walk.enter(key);
+                }
+                FieldRole::Capture(index) => {
+                    async_reader::find_the_end_of_the_object_value(
+                        &mut self.data,
+                        value_start.value,
+                    )
+                    .await?;
+                    let value = self.data.get_slice_to_current_pos(value_start.pos).await?;
+                    self.walk.capture(index, value);
+                }
+                FieldRole::Skip => {
+                    async_reader::find_the_end_of_the_object_value(
+                        &mut ReleasingIterator::new(&mut self.data),
+                        value_start.value,
+                    )
+                    .await?;
+                }
+            }
+        }
+
+        Ok(false)
+    }
+}
@@ -0,0 +1,196 @@
+use std::iter::FusedIterator;
+
+use rust_extensions::array_of_bytes_iterator::{ArrayOfBytesIterator, SliceIterator};
+
+use super::{array_iterator_inner::JsonArrayIteratorInner, JsonParseError, JsonValueRef};
+
+/// The elements of a JSON array as a standard [`Iterator`].
+///
+/// [`JsonArrayIterator`](super::JsonArrayIterator) keeps its position behind a `Cell`; this
+/// keeps it in a plain field, so it is `Send` and `Sync` and works with `for`, `map`, `zip` and
+/// `collect`. A clone carries on from where the original is - clone it before the first `next`
+/// to read the array again.
+///
+/// An `Err` is the last item: malformed JSON ends the iteration.
+#[derive(Debug, Clone)]
+pub struct JsonArrayItems<'s> {
+    slice: &'s [u8],
+    pos: usize,
+    initialized: bool,
+    finished: bool,
+}
+
+impl<'s> JsonArrayItems<'s> {
+    pub fn new(slice: &'s [u8]) -> Self {
+        Self {
+            slice,
+            pos: 0,
+            initialized: false,
+            finished: false,
+        }
+    }
+
+    pub fn as_slice(&self) -> &'s [u8] {
+        self.slice
+    }
+}
+
+impl<'s> Iterator for JsonArrayItems<'s> {
+    type Item = Result<JsonValueRef<'s>, JsonParseError>;
+
+    // The reader works on a `SliceIterator`, which is cheap to rebuild: one is set up at the
+    // saved position for each element, and the position it ends on is saved for the next.
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+
+        let data = SliceIterator::new(self.slice);
+        data.advance(self.pos);
+
+        let inner = JsonArrayIteratorInner::resume(data, self.initialized);
+        let result = inner.get_next();
+
+        self.pos = inner.get_pos();
+        self.initialized = inner.is_initialized();
+
+        match result {
+            Some(Ok(value)) => Some(Ok(JsonValueRef::new(value, self.slice))),
+            Some(Err(err)) => {
+                self.finished = true;
+                Some(Err(err))
+            }
+            None => {
+                self.finished = true;
+                None
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.finished {
+            return (0, Some(0));
+        }
+
+        // every element takes at least one byte, and a malformed array ends with one `Err` more
+        (0, Some(self.slice.len() - self.pos + 1))
+    }
+}
+
+impl<'s> FusedIterator for JsonArrayItems<'s> {}
+
+#[cfg(test)]
+mod tests {
+    use super::JsonArrayItems;
+
+    #[test]
+    fn works_with_standard_adapters() {
+        let json = br#"[1, 2, 3, 4]"#;
+
+        let doubled: Vec<i64> = JsonArrayItems::new(json)
+            .map(|item| item.unwrap().unwrap_as_number().unwrap().unwrap() * 2)
+            .filter(|value| *value > 2)
+            .collect();
+
+        assert_eq!(doubled, vec![4, 6, 8]);
+
+        let pairs: Vec<(String, String)> = JsonArrayItems::new(br#"["a","b"]"#)
+            .zip(JsonArrayItems::new(br#"["x","y","z"]"#))
+            .map(|(left, right)| {
+                (
+                    left.unwrap().as_str().unwrap().as_str().to_string(),
+                    right.unwrap().as_str().unwrap().as_str().to_string(),
+                )
+            })
+            .collect();
+
+        assert_eq!(
+            pairs,
+            vec![
+                ("a".to_string(), "x".to_string()),
+                ("b".to_string(), "y".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn a_clone_restarts_from_its_position() {
+        let mut items = JsonArrayItems::new(br#"[{"a":1}, [], "s"]"#);
+        let from_start = items.clone();
+
+        assert_eq!(items.next().unwrap().unwrap().as_bytes(), br#"{"a":1}"#);
+        let from_second = items.clone();
+
+        assert_eq!(from_start.count(), 3);
+        assert_eq!(from_second.count(), 2);
+        assert_eq!(items.count(), 2);
+    }
+
+    #[test]
+    fn an_error_ends_the_iteration() {
+        let mut items = JsonArrayItems::new(b"[1, x, 3]");
+
+        assert!(items.next().unwrap().is_ok());
+        assert!(items.next().unwrap().is_err());
+        assert!(items.next().is_none());
+        assert_eq!(items.size_hint(), (0, Some(0)));
+
+        assert!(JsonArrayItems::new(b"").next().unwrap().is_err());
+        assert!(JsonArrayItems::new(b"[]").next().is_none());
+    }
+
+    #[test]
+    fn size_hint_bounds_what_is_yielded() {
+        for json in [
+            &b""[..],
+            b"[",
+            b"[]",
+            b"[1",
+            b"[1,",
+            b"[1, 2, 3]",
+            b"[1, x, 3]",
+            b"x",
+        ] {
+            let mut items = JsonArrayItems::new(json);
+
+            loop {
+                let (lower, upper) = items.size_hint();
+                let left = items.clone().count();
+
+                assert!(lower <= left && left <= upper.unwrap(), "{:?}", json);
+
+                if items.next().is_none() {
+                    break;
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn can_be_shared_across_threads() {
+        fn assert_send_sync<T: Send + Sync>(_: &T) {}
+
+        let items = JsonArrayItems::new(b"[1,2,3]");
+        assert_send_sync(&items);
+
+        let sum = std::thread::scope(|scope| {
+            let handles: Vec<_> = (0..2)
+                .map(|_| {
+                    let items = items.clone();
+                    scope.spawn(move || {
+                        items
+                            .map(|item| item.unwrap().unwrap_as_number().unwrap().unwrap())
+                            .sum::<i64>()
+                    })
+                })
+                .collect();
+
+            handles
+                .into_iter()
+                .map(|handle| handle.join().unwrap())
+                .sum::<i64>()
+        });
+
+        assert_eq!(sum, 12);
+    }
+}
@@ -0,0 +1,167 @@
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use futures_core::Stream;
+use rust_extensions::array_of_bytes_iterator::ArrayOfBytesIteratorAsync;
+
+use super::{
+    JsonArrayAtPathIteratorAsync, JsonArrayIteratorAsync, JsonLIteratorAsync, JsonParseError,
+};
+
+type ReadNext<TIterator> =
+    Pin<Box<dyn Future<Output = (TIterator, Option<Result<Vec<u8>, JsonParseError>>)> + Send>>;
+
+enum StreamState<TIterator> {
+    Idle(TIterator),
+    Reading(ReadNext<TIterator>),
+    Finished,
+}
+
+/// A [`Stream`] of the items of an async iterator, made by its `into_stream`, so the `StreamExt`
+/// combinators can be used on it.
+///
+/// Every item is what the iterator's `get_next` returns; the stream ends where the iteration
+/// does.
+pub struct JsonAsyncStream<TIterator> {
+    state: StreamState<TIterator>,
+    read_next: fn(TIterator) -> ReadNext<TIterator>,
+}
+
+// The iterator is only ever moved into and out of the boxed future, never pinned in place.
+impl<TIterator> Unpin for JsonAsyncStream<TIterator> {}
+
+impl<TArrayOfBytesIterator: ArrayOfBytesIteratorAsync + Send + 'static>
+    JsonArrayIteratorAsync<TArrayOfBytesIterator>
+{
+    pub fn into_stream(self) -> JsonAsyncStream<Self> {
+        JsonAsyncStream {
+            state: StreamState::Idle(self),
+            read_next: |mut iterator| {
+                Box::pin(async move {
+                    let item = iterator.get_next().await;
+                    (iterator, item)
+                })
+            },
+        }
+    }
+}
+
+impl<TArrayOfBytesIterator: ArrayOfBytesIteratorAsync + Send + 'static>
+    JsonLIteratorAsync<TArrayOfBytesIterator>
+{
+    pub fn into_stream(self) -> JsonAsyncStream<Self> {
+        JsonAsyncStream {
+            state: StreamState::Idle(self),
+            read_next: |mut iterator| {
+                Box::pin(async move {
+                    let item = iterator.get_next().await;
+                    (iterator, item)
+                })
+            },
+        }
+    }
+}
+
+impl<TArrayOfBytesIterator: ArrayOfBytesIteratorAsync + Send + Sync + 'static>
+    JsonArrayAtPathIteratorAsync<TArrayOfBytesIterator>
+{
+    pub fn into_stream(self) -> JsonAsyncStream<Self> {
+        JsonAsyncStream {
+            state: StreamState::Idle(self),
+            read_next: |mut iterator| {
+                Box::pin(async move {
+                    let item = iterator.get_next().await;
+                    (iterator, item)
+                })
+            },
+        }
+    }
+}
+
+impl<TIterator> Stream for JsonAsyncStream<TIterator> {
+    type Item = Result<Vec<u8>, JsonParseError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+
+        loop {
+            match std::mem::replace(&mut this.state, StreamState::Finished) {
+                StreamState::Idle(iterator) => {
+                    this.state = StreamState::Reading((this.read_next)(iterator));
+                }
+                StreamState::Reading(mut read_next) => match read_next.as_mut().poll(cx) {
+                    Poll::Pending => {
+                        this.state = StreamState::Reading(read_next);
+                        return Poll::Pending;
+                    }
+                    Poll::Ready((iterator, Some(item))) => {
+                        this.state = StreamState::Idle(iterator);
+                        return Poll::Ready(Some(item));
+                    }
+                    Poll::Ready((_, None)) => return Poll::Ready(None),
+                },
+                StreamState::Finished => return Poll::Ready(None),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::future::poll_fn;
+    use std::pin::Pin;
+
+    use futures_core::Stream;
+
+    use super::JsonAsyncStream;
+    use crate::json_reader::{AsyncReadIterator, JsonArrayIteratorAsync, JsonLIteratorAsync};
+
+    async fn collect<TIterator>(
+        mut stream: JsonAsyncStream<TIterator>,
+    ) -> Vec<Result<String, String>> {
+        let mut result = Vec::new();
+
+        while let Some(item) = poll_fn(|cx| Pin::new(&mut stream).poll_next(cx)).await {
+            result.push(
+                item.map(|item| String::from_utf8(item).unwrap())
+                    .map_err(|err| err.to_string()),
+            );
+        }
+
+        result
+    }
+
+    #[tokio::test]
+    async fn iterators_as_streams() {
+        let src = AsyncReadIterator::new(&b"[1, {\"a\":2}, \"x\"]"[..])
+            .await
+            .unwrap();
+        let stream = JsonArrayIteratorAsync::new(src).await.into_stream();
+
+        assert_eq!(
+            collect(stream).await,
+            vec![
+                Ok("1".to_string()),
+                Ok("{\"a\":2}".to_string()),
+                Ok("\"x\"".to_string())
+            ]
+        );
+
+        let src = AsyncReadIterator::new(&b"{\"a\":1}\n[2]\n{bad\n3\n"[..])
+            .await
+            .unwrap();
+        let stream = JsonLIteratorAsync::new(src)
+            .await
+            .with_skip_bad_records()
+            .into_stream();
+
+        let result = collect(stream).await;
+
+        assert_eq!(result.len(), 4);
+        assert_eq!(result[0], Ok("{\"a\":1}".to_string()));
+        assert_eq!(result[1], Ok("[2]".to_string()));
+        assert!(result[2].is_err());
+        assert_eq!(result[3], Ok("3".to_string()));
+    }
+}
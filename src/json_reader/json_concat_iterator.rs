@@ -0,0 +1,77 @@
+use rust_extensions::array_of_bytes_iterator::*;
+
+use super::{JsonLIterator, JsonLRecordError, JsonParseError};
+
+/// Splits concatenated JSON - documents written one after another with no separator, such as
+/// `{...}{...}[...]` - into its values. White space between them is allowed but not needed.
+///
+/// Reading a value is how its end is found, so a malformed value is the last item.
+pub struct JsonConcatIterator<TArrayOfBytesIterator: ArrayOfBytesIterator> {
+    // Reading JSON-L strictly is reading values one after another; only the errors differ.
+    inner: JsonLIterator<TArrayOfBytesIterator>,
+}
+
+impl<TArrayOfBytesIterator: ArrayOfBytesIterator> JsonConcatIterator<TArrayOfBytesIterator> {
+    pub fn new(data: TArrayOfBytesIterator) -> Self {
+        Self {
+            inner: JsonLIterator::new(data),
+        }
+    }
+
+    pub fn get_src_slice(&self) -> &[u8] {
+        self.inner.get_src_slice()
+    }
+
+    pub fn get_next(&mut self) -> Option<Result<&[u8], JsonParseError>> {
+        let record = self.inner.get_next_record()?;
+        Some(record.map(|record| record.data).map_err(concat_error))
+    }
+}
+
+pub(crate) fn concat_error(err: JsonLRecordError) -> JsonParseError {
+    JsonParseError::new(format!(
+        "Error reading concatenated JSON value at offset {}. {}",
+        err.offset,
+        err.error.into_string()
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn values_without_separators() {
+        let src = br#"{"a":1}{"b":[2]}[3,4]"s" 5 null{}"#;
+        let mut iterator = JsonConcatIterator::new(SliceIterator::new(src));
+
+        let mut result = Vec::new();
+        while let Some(item) = iterator.get_next() {
+            result.push(std::str::from_utf8(item.unwrap()).unwrap().to_string());
+        }
+
+        assert_eq!(
+            result,
+            vec![
+                r#"{"a":1}"#,
+                r#"{"b":[2]}"#,
+                "[3,4]",
+                r#""s""#,
+                "5",
+                "null",
+                "{}"
+            ]
+        );
+
+        let mut iterator = JsonConcatIterator::new(SliceIterator::new(b"{}{\"a\" 1}[]"));
+        assert!(iterator.get_next().unwrap().is_ok());
+
+        let err = iterator.get_next().unwrap().unwrap_err();
+        assert!(
+            err.to_string().contains("at offset 2"),
+            "{}",
+            err.to_string()
+        );
+        assert!(iterator.get_next().is_none());
+    }
+}
@@ -0,0 +1,32 @@
+use rust_extensions::array_of_bytes_iterator::*;
+
+use super::json_concat_iterator::concat_error;
+use super::{JsonLIteratorAsync, JsonParseError, JsonValueReaderOwned};
+
+/// The async counterpart of [`JsonConcatIterator`](super::JsonConcatIterator): a malformed value
+/// is the last item.
+pub struct JsonConcatIteratorAsync<TArrayOfBytesIterator: ArrayOfBytesIteratorAsync> {
+    inner: JsonLIteratorAsync<TArrayOfBytesIterator>,
+}
+
+impl<TArrayOfBytesIterator: ArrayOfBytesIteratorAsync>
+    JsonConcatIteratorAsync<TArrayOfBytesIterator>
+{
+    pub async fn new(data: TArrayOfBytesIterator) -> Self {
+        Self {
+            inner: JsonLIteratorAsync::new(data).await,
+        }
+    }
+
+    /// The next value, read as `T`. A value that is not a `T` is an `Err` for that value only;
+    /// the iteration can go on.
+    pub async fn read_as<T: JsonValueReaderOwned>(&mut self) -> Option<Result<T, JsonParseError>> {
+        let item = self.get_next().await?;
+        Some(item.and_then(|bytes| T::from_json_slice(&bytes)))
+    }
+
+    pub async fn get_next(&mut self) -> Option<Result<Vec<u8>, JsonParseError>> {
+        let record = self.inner.get_next_record().await?;
+        Some(record.map(|record| record.data).map_err(concat_error))
+    }
+}
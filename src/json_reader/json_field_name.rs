@@ -43,6 +43,49 @@ impl JsonContentOffset {
         )))
     }
 
+    /// [`Self::as_str`] appending the de-escaped name into `dest` instead of allocating.
+    pub fn unescape_into(
+        &self,
+        json: &impl AsJsonSlice,
+        dest: &mut String,
+    ) -> Result<(), JsonParseError> {
+        let body = self.get_escaped_body(json)?;
+        crate::json_string_value::de_escape_json_string_value_into(body, dest);
+        Ok(())
+    }
+
+    /// [`Self::unescape_into`] appending UTF-8 bytes to a `Vec<u8>`.
+    pub fn unescape_into_vec(
+        &self,
+        json: &impl AsJsonSlice,
+        dest: &mut Vec<u8>,
+    ) -> Result<(), JsonParseError> {
+        let body = self.get_escaped_body(json)?;
+        crate::json_string_value::de_escape_json_string_value_into_vec(body, dest);
+        Ok(())
+    }
+
+    /// Checks whether the name, once de-escaped, is exactly `name` - without allocating, even
+    /// when the name carries escapes. A name that can not be parsed equals nothing.
+    pub fn unescaped_eq(&self, json: &impl AsJsonSlice, name: &str) -> bool {
+        match self.get_escaped_body(json) {
+            Ok(body) => crate::json_string_value::json_string_value_eq(body, name),
+            Err(_) => false,
+        }
+    }
+
+    fn get_escaped_body<'s>(&self, json: &'s impl AsJsonSlice) -> Result<&'s str, JsonParseError> {
+        let slice = json.as_slice()[self.start..self.end].as_ref();
+
+        match crate::json_utils::try_get_escaped_string_body(slice) {
+            Some(body) => Ok(body),
+            None => Err(JsonParseError::new(format!(
+                "Can not parse name: {}-{}",
+                self.start, self.end
+            ))),
+        }
+    }
+
     pub fn as_unescaped_str<'s>(
         &self,
         json: &'s impl AsJsonSlice,
@@ -21,6 +21,19 @@ impl<'s> JsonFieldNameRef<'s> {
         self.data.as_str(&self.src_json)
     }
 
+    pub fn unescape_into(&self, dest: &mut String) -> Result<(), JsonParseError> {
+        self.data.unescape_into(&self.src_json, dest)
+    }
+
+    pub fn unescape_into_vec(&self, dest: &mut Vec<u8>) -> Result<(), JsonParseError> {
+        self.data.unescape_into_vec(&self.src_json, dest)
+    }
+
+    /// Compares the de-escaped name with `name` without allocating.
+    pub fn unescaped_eq(&self, name: &str) -> bool {
+        self.data.unescaped_eq(&self.src_json, name)
+    }
+
     pub fn as_slice(&'s self) -> &'s [u8] {
         &self.src_json[self.data.start..self.data.end]
     }
@@ -147,4 +160,38 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn test_unescape_into_and_unescaped_eq() {
+        let json = r#"{"plain":"a\nb","esc\"aped":null,"\u0041":1}"#.as_bytes();
+
+        let parser = JsonFirstLineIterator::new(json);
+        let mut name_buffer = String::new();
+        let mut value_buffer = Vec::new();
+
+        let (name, value) = parser.get_next().unwrap().unwrap();
+        name.unescape_into(&mut name_buffer).unwrap();
+        assert_eq!(name_buffer, "plain");
+        assert!(name.unescaped_eq("plain"));
+        assert!(value.unescape_into_vec(&mut value_buffer));
+        assert_eq!(value_buffer, b"a\nb");
+
+        let (name, value) = parser.get_next().unwrap().unwrap();
+        name_buffer.clear();
+        name.unescape_into(&mut name_buffer).unwrap();
+        assert_eq!(name_buffer, "esc\"aped");
+        assert!(name.unescaped_eq("esc\"aped"));
+        assert!(!name.unescaped_eq("esc\\\"aped"));
+
+        value_buffer.clear();
+        assert!(!value.unescape_into_vec(&mut value_buffer));
+        assert!(value_buffer.is_empty());
+
+        let (name, value) = parser.get_next().unwrap().unwrap();
+        assert!(name.unescaped_eq("A"));
+
+        let mut value_str = String::from("n=");
+        assert!(value.unescape_into(&mut value_str));
+        assert_eq!(value_str, "n=1");
+    }
 }
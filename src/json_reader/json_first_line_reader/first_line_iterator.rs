@@ -34,8 +34,16 @@ impl<'s> JsonFirstLineIterator<'s> {
         }
     }
 
+    /// # Panics
+    ///
+    /// If the payload is not UTF-8. See [`Self::try_as_str`] for bytes that are not trusted.
     pub fn as_str(&'s self) -> &'s str {
-        std::str::from_utf8(self.inner.as_slice()).unwrap()
+        self.try_as_str().unwrap()
+    }
+
+    pub fn try_as_str(&'s self) -> Result<&'s str, JsonParseError> {
+        std::str::from_utf8(self.inner.as_slice())
+            .map_err(|err| JsonParseError::new(format!("Json payload is not UTF-8. {}", err)))
     }
 }
 
@@ -0,0 +1,165 @@
+use rust_extensions::array_of_bytes_iterator::*;
+
+use crate::json_reader::bytes_of_array_reader::*;
+use crate::json_reader::{JsonParseError, JsonValueReaderOwned};
+
+/// A top-level field read by [`JsonFirstLineReaderAsync`]: the de-escaped key and the raw JSON
+/// of its value.
+#[derive(Debug, Clone, PartialEq)]
+pub struct JsonKeyValueOwned {
+    pub name: String,
+    pub value: Vec<u8>,
+}
+
+impl JsonKeyValueOwned {
+    /// The value, read as `T`.
+    pub fn read_value_as<T: JsonValueReaderOwned>(&self) -> Result<T, JsonParseError> {
+        T::from_json_slice(&self.value)
+    }
+}
+
+type KeyFilter = Box<dyn Fn(&str) -> bool + Send + Sync>;
+
+/// The async counterpart of [`JsonFirstLineReaderInner`](super::JsonFirstLineReaderInner): reads
+/// the fields of a top-level object one at a time from an [`ArrayOfBytesIteratorAsync`], so a
+/// large object from a network source is never held whole.
+///
+/// With [`Self::with_key_filter`] the values of the keys that are not wanted are read past
+/// without being copied out.
+pub struct JsonFirstLineReaderAsync<TArrayOfBytesIterator: ArrayOfBytesIteratorAsync> {
+    raw: TArrayOfBytesIterator,
+    key_filter: Option<KeyFilter>,
+    had_init: bool,
+    finished: bool,
+}
+
+impl<TArrayOfBytesIterator: ArrayOfBytesIteratorAsync>
+    JsonFirstLineReaderAsync<TArrayOfBytesIterator>
+{
+    // Nothing is read here: a payload that is not an object is reported by the first `get_next`.
+    pub async fn new(raw: TArrayOfBytesIterator) -> Self {
+        Self {
+            raw,
+            key_filter: None,
+            had_init: false,
+            finished: false,
+        }
+    }
+
+    /// Only the fields whose de-escaped key passes `key_filter` are handed out.
+    pub fn with_key_filter(
+        mut self,
+        key_filter: impl Fn(&str) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        self.key_filter = Some(Box::new(key_filter));
+        self
+    }
+
+    pub async fn get_next(&mut self) -> Option<Result<JsonKeyValueOwned, JsonParseError>> {
+        if self.finished {
+            return None;
+        }
+
+        loop {
+            match self.read_field().await {
+                Ok(Some(Some(item))) => return Some(Ok(item)),
+                Ok(Some(None)) => {}
+                Ok(None) => {
+                    self.finished = true;
+                    return None;
+                }
+                Err(err) => {
+                    self.finished = true;
+                    return Some(Err(err));
+                }
+            }
+        }
+    }
+
+    // `Ok(true)` once the closing `}` has been read.
+    async fn init_if_requires(&mut self) -> Result<bool, JsonParseError> {
+        if self.had_init {
+            let token = async_reader::skip_white_spaces_and_get_expected_token(
+                &mut self.raw,
+                ExpectedTokenJsonObjectSeparatorOrCloseBracket,
+            )
+            .await?;
+
+            if token.value == crate::consts::CLOSE_BRACKET {
+                return Ok(true);
+            }
+        } else {
+            let result = async_reader::skip_white_spaces_and_get_expected_token(
+                &mut self.raw,
+                ExpectedOpenJsonObjectToken,
+            )
+            .await;
+
+            if let Err(err) = result {
+                let msg = err.into_string();
+                return Err(JsonParseError::CanNotFindStartOfTheJsonObject(msg));
+            }
+
+            self.had_init = true;
+        }
+
+        Ok(false)
+    }
+
+    // `Ok(None)` at the end of the object, `Ok(Some(None))` for a field the filter leaves out.
+    async fn read_field(&mut self) -> Result<Option<Option<JsonKeyValueOwned>>, JsonParseError> {
+        if self.init_if_requires().await? {
+            return Ok(None);
+        }
+
+        let key_start = async_reader::skip_white_spaces_and_peek_expected_token(
+            &mut self.raw,
+            ExpectedJsonObjectKeyStart,
+        )
+        .await?;
+
+        if key_start.value == crate::consts::CLOSE_BRACKET {
+            self.raw.get_next().await?;
+            return Ok(None);
+        }
+
+        async_reader::find_the_end_of_the_string(&mut self.raw).await?;
+        let raw_name = self.raw.get_slice_to_current_pos(key_start.pos).await?;
+
+        let name = match crate::json_utils::try_get_string_value(&raw_name) {
+            Some(name) => name.as_str().to_string(),
+            None => {
+                return Err(JsonParseError::new(format!(
+                    "Can not parse name: {}-{}",
+                    key_start.pos,
+                    key_start.pos + raw_name.len()
+                )))
+            }
+        };
+
+        async_reader::skip_white_spaces_and_get_expected_token(
+            &mut self.raw,
+            ExpectedJsonObjectKeyValueSeparator,
+        )
+        .await?;
+
+        let value_start = async_reader::skip_white_spaces_and_peek_expected_token(
+            &mut self.raw,
+            ExpectedJsonValueStart,
+        )
+        .await?;
+
+        async_reader::find_the_end_of_the_object_value(&mut self.raw, value_start.value)
+            .await
+            .map_err(|err| err.with_path_key(&name))?;
+
+        if let Some(key_filter) = &self.key_filter {
+            if !key_filter(&name) {
+                return Ok(Some(None));
+            }
+        }
+
+        let value = self.raw.get_slice_to_current_pos(value_start.pos).await?;
+        Ok(Some(Some(JsonKeyValueOwned { name, value })))
+    }
+}
@@ -15,12 +15,26 @@ pub struct JsonFirstLineReaderInner<TArrayOfBytesIterator: ArrayOfBytesIterator>
 
 impl<TArrayOfBytesIterator: ArrayOfBytesIterator> JsonFirstLineReaderInner<TArrayOfBytesIterator> {
     pub fn new(raw: TArrayOfBytesIterator) -> Self {
+        Self::resume(raw, false)
+    }
+
+    /// Picks up where an earlier reader over the same bytes stopped: `raw` is already at its
+    /// position, and `had_init` is set once the `{` has been read.
+    pub(crate) fn resume(raw: TArrayOfBytesIterator, had_init: bool) -> Self {
         Self {
             raw,
-            had_init: Cell::new(false),
+            had_init: Cell::new(had_init),
         }
     }
 
+    pub(crate) fn get_pos(&self) -> usize {
+        self.raw.get_pos()
+    }
+
+    pub(crate) fn had_init(&self) -> bool {
+        self.had_init.get()
+    }
+
     fn init_if_requires(&self) -> Result<bool, JsonParseError> {
         if self.had_init.get() {
             let token = sync_reader::skip_white_spaces_and_get_expected_token(
@@ -107,6 +121,14 @@ impl<TArrayOfBytesIterator: ArrayOfBytesIterator> JsonFirstLineReaderInner<TArra
     }
 }
 
+impl<TRead: std::io::Read> JsonFirstLineReaderInner<crate::json_reader::ReadIterator<TRead>> {
+    /// Drops the entries read so far from memory. Resolve the entries `get_next` returned before
+    /// calling it: their positions are no longer valid afterwards.
+    pub fn release_consumed(&mut self) {
+        self.raw.release_consumed();
+    }
+}
+
 impl<TArrayOfBytesIterator: ArrayOfBytesIterator> AsJsonSlice
     for JsonFirstLineReaderInner<TArrayOfBytesIterator>
 {
@@ -1,44 +1,299 @@
-use super::{bytes_of_array_reader::*, JsonParseError};
+use super::json_l_record::{
+    check_record_boundary, count_lines, read_record_from, skip_record_value,
+};
+use super::{JsonLCheckpoint, JsonLRecord, JsonLRecordError, JsonParseError};
 use rust_extensions::array_of_bytes_iterator::*;
 
+/// Splits a JSON-L payload into records. A record can be any JSON value - an object, an array or
+/// a scalar - and several can share a line.
+///
+/// By default a malformed record is the last item: nothing after it can be trusted to start where
+/// a record does. With [`Self::with_skip_bad_records`] it is reported and reading carries on.
 pub struct JsonLIterator<TArrayOfBytesIterator: ArrayOfBytesIterator> {
     data: TArrayOfBytesIterator,
+    line: usize,
+    skip_bad_records: bool,
+    finished: bool,
+    // Skip-bad mode reads a line at a time: where the unread part of the line starts, and whether
+    // the line break that ends it is next.
+    line_rest: Option<usize>,
+    at_line_end: bool,
+    // Bytes a `ReadIterator` has dropped from its front, so offsets stay those of the payload.
+    released: usize,
 }
 
 impl<TArrayOfBytesIterator: ArrayOfBytesIterator> JsonLIterator<TArrayOfBytesIterator> {
-    pub fn new(mut data: TArrayOfBytesIterator) -> Self {
-        sync_reader::skip_white_spaces(&mut data).unwrap();
-        Self { data }
+    // Leading white space is skipped by `get_next`, so an empty or blank payload is simply one
+    // with no lines rather than a failed construction.
+    pub fn new(data: TArrayOfBytesIterator) -> Self {
+        Self {
+            data,
+            line: 1,
+            skip_bad_records: false,
+            finished: false,
+            line_rest: None,
+            at_line_end: false,
+            released: 0,
+        }
+    }
+
+    /// Carries on a read from a [`Self::checkpoint`] taken over the same payload. `data` can be at
+    /// the start of the payload or already at the checkpoint's offset.
+    ///
+    /// Over a `ReadIterator` use [`JsonLIterator::resume_reader`], which does not keep the bytes
+    /// before the checkpoint in memory.
+    pub fn resume(
+        data: TArrayOfBytesIterator,
+        checkpoint: JsonLCheckpoint,
+    ) -> Result<Self, JsonParseError> {
+        let pos = data.get_pos();
+
+        if pos > checkpoint.offset || data.advance(checkpoint.offset - pos).is_none() {
+            return Err(JsonParseError::new(format!(
+                "Checkpoint offset {} can not be reached. The payload is at {} and {} bytes long",
+                checkpoint.offset,
+                pos,
+                data.get_src_slice().len()
+            )));
+        }
+
+        let before = match checkpoint.offset {
+            0 => None,
+            offset => data.get_src_slice().get(offset - 1).copied(),
+        };
+
+        check_record_boundary(
+            before,
+            data.peek_value().map(|next_value| next_value.value),
+            checkpoint.offset,
+        )?;
+
+        let mut result = Self::new(data);
+        result.line = checkpoint.line;
+        Ok(result)
+    }
+
+    /// A malformed record is reported with its line, and reading resumes at the next line. A
+    /// record can not span lines in this mode.
+    pub fn with_skip_bad_records(mut self) -> Self {
+        self.skip_bad_records = true;
+        self
     }
 
     pub fn get_src_slice(&self) -> &[u8] {
         self.data.get_src_slice()
     }
 
+    /// Where the read has got to, to [`Self::resume`] it from later.
+    pub fn checkpoint(&self) -> JsonLCheckpoint {
+        JsonLCheckpoint {
+            offset: self.released + self.line_rest.unwrap_or(self.data.get_pos()),
+            line: self.line,
+        }
+    }
+
     pub fn get_next<'s>(&'s mut self) -> Option<Result<&'s [u8], JsonParseError>> {
-        let start_value = match sync_reader::skip_white_spaces(&mut self.data) {
-            Ok(value) => value,
-            Err(_) => return None,
-        };
+        let record = self.get_next_record()?;
+        Some(
+            record
+                .map(|record| record.data)
+                .map_err(JsonParseError::from),
+        )
+    }
+
+    /// The next record with its line and offset.
+    pub fn get_next_record(&mut self) -> Option<Result<JsonLRecord<&[u8]>, JsonLRecordError>> {
+        if self.finished {
+            return None;
+        }
+
+        if self.skip_bad_records {
+            return self.read_record_in_line();
+        }
+
+        let start = self.skip_to_next_record()?;
+        let line = self.line;
+        let offset = self.released + start.pos;
+
+        match skip_record_value(&self.data, start) {
+            Ok(()) => {
+                let data = self.data.get_slice_to_current_pos(start.pos);
+                self.line += count_lines(data);
+
+                Some(Ok(JsonLRecord { line, offset, data }))
+            }
+            Err(error) => {
+                self.finished = true;
+
+                Some(Err(JsonLRecordError {
+                    line,
+                    offset,
+                    error,
+                }))
+            }
+        }
+    }
+
+    // The record is read from its line alone, so a broken one can not run on into the next
+    // line, and whatever happens the next attempt starts after it or on the next line. The line
+    // is read up to its end first: a source that reads as it goes may not have the rest yet.
+    fn read_record_in_line(&mut self) -> Option<Result<JsonLRecord<&[u8]>, JsonLRecordError>> {
+        loop {
+            if let Some(from) = self.line_rest {
+                let rest = self.data.get_slice_to_current_pos(from);
 
-        match start_value.value {
-            crate::consts::OPEN_BRACKET => {
-                match sync_reader::find_the_end_of_json(&mut self.data) {
-                    Ok(_) => {}
-                    Err(err) => return Some(Err(err)),
+                if let Some(skipped) = rest.iter().position(|b| *b > 32) {
+                    let start = from + skipped;
+                    let offset = self.released + start;
+
+                    return Some(match read_record_from(&rest[skipped..]) {
+                        Ok(len) => {
+                            self.line_rest = Some(start + len);
+
+                            Ok(JsonLRecord {
+                                line: self.line,
+                                offset,
+                                data: &rest[skipped..skipped + len],
+                            })
+                        }
+                        Err(error) => {
+                            self.line_rest = None;
+
+                            Err(JsonLRecordError {
+                                line: self.line,
+                                offset,
+                                error,
+                            })
+                        }
+                    });
                 }
             }
 
-            _ => {
-                return Some(Err(JsonParseError::new(format!(
-                    "Error reading value as object. Start {}. We reached the end of the payload",
-                    start_value.pos
-                ))));
+            // An associated function, not a method: `rest` may be borrowed from `data` still.
+            self.line_rest = Some(Self::read_line(
+                &self.data,
+                &mut self.line,
+                &mut self.at_line_end,
+            )?);
+        }
+    }
+
+    // Moves past the next line, stopping at its line break, and returns where it starts.
+    fn read_line(
+        data: &TArrayOfBytesIterator,
+        line: &mut usize,
+        at_line_end: &mut bool,
+    ) -> Option<usize> {
+        if *at_line_end {
+            data.get_next();
+            *line += 1;
+            *at_line_end = false;
+        }
+
+        let from = data.get_pos();
+
+        while let Some(next_value) = data.peek_value() {
+            if next_value.value == b'\n' {
+                *at_line_end = true;
+                return Some(from);
             }
+
+            data.get_next();
+        }
+
+        if data.get_pos() > from {
+            Some(from)
+        } else {
+            None
+        }
+    }
+
+    fn skip_to_next_record(&mut self) -> Option<NextValue> {
+        while let Some(next_value) = self.data.peek_value() {
+            if next_value.value > 32 {
+                return Some(next_value);
+            }
+
+            if next_value.value == b'\n' {
+                self.line += 1;
+            }
+
+            self.data.get_next();
+        }
+
+        None
+    }
+}
+
+impl<TRead: std::io::Read> JsonLIterator<super::ReadIterator<TRead>> {
+    /// [`Self::resume`] over a source read as it goes. From the start of the payload, the bytes
+    /// before the checkpoint are read past a chunk at a time and dropped, so memory use does not
+    /// grow with the offset; a reader already seeked to it is opened with
+    /// [`ReadIterator::starting_at`](super::ReadIterator::starting_at) and nothing is read past.
+    pub fn resume_reader(
+        mut data: super::ReadIterator<TRead>,
+        checkpoint: JsonLCheckpoint,
+    ) -> Result<Self, JsonParseError> {
+        const CHUNK_SIZE: usize = 64 * 1024;
+
+        let pos = data.get_window_start() + data.get_pos();
+
+        if pos > checkpoint.offset {
+            return Err(JsonParseError::new(format!(
+                "Checkpoint offset {} can not be reached. The payload is already at {}",
+                checkpoint.offset, pos
+            )));
+        }
+
+        let mut before = match data.get_pos() {
+            0 => None,
+            pos => data.get_src_slice().get(pos - 1).copied(),
         };
 
-        let result = self.data.get_slice_to_current_pos(start_value.pos);
-        return Some(Ok(result));
+        let mut remaining = checkpoint.offset - pos;
+
+        while remaining > 0 {
+            let amount = remaining.min(CHUNK_SIZE);
+
+            match data.advance(amount) {
+                Some(read) => before = read.last().copied(),
+                None => {
+                    return Err(JsonParseError::new(format!(
+                        "Checkpoint offset {} is beyond the end of the payload",
+                        checkpoint.offset
+                    )))
+                }
+            }
+
+            remaining -= amount;
+            data.release_consumed();
+        }
+
+        check_record_boundary(
+            before,
+            data.peek_value().map(|next_value| next_value.value),
+            checkpoint.offset,
+        )?;
+
+        let released = data.get_window_start();
+
+        let mut result = Self::new(data);
+        result.line = checkpoint.line;
+        result.released = released;
+        Ok(result)
+    }
+
+    /// Drops the records read so far from memory. Called after every record, memory use is
+    /// bounded by the largest record; offsets stay those of the payload.
+    pub fn release_consumed(&mut self) {
+        let keep_from = self.line_rest.unwrap_or(self.data.get_pos());
+
+        self.data.release_to(keep_from);
+        self.released += keep_from;
+
+        if let Some(line_rest) = &mut self.line_rest {
+            *line_rest -= keep_from;
+        }
     }
 }
 
@@ -106,4 +361,139 @@ mod tests {
             println!("{}", std::str::from_utf8(sub_json).unwrap());
         }
     }
+
+    fn records(json: &str, skip_bad_records: bool) -> Vec<Result<(usize, usize, String), usize>> {
+        let mut iterator = JsonLIterator::new(SliceIterator::from_str(json));
+
+        if skip_bad_records {
+            iterator = iterator.with_skip_bad_records();
+        }
+
+        let mut result = Vec::new();
+
+        while let Some(record) = iterator.get_next_record() {
+            result.push(match record {
+                Ok(record) => Ok((
+                    record.line,
+                    record.offset,
+                    String::from_utf8(record.data.to_vec()).unwrap(),
+                )),
+                Err(err) => Err(err.line),
+            });
+        }
+
+        result
+    }
+
+    #[test]
+    fn any_value_is_a_record_and_knows_its_line() {
+        let json = "{\"a\":1}\n[1, 2]\n\n\"s\" 42 true\nnull\n{\n  \"b\": 2\n}\n-1.5e3";
+
+        assert_eq!(
+            records(json, false),
+            vec![
+                Ok((1, 0, "{\"a\":1}".to_string())),
+                Ok((2, 8, "[1, 2]".to_string())),
+                Ok((4, 16, "\"s\"".to_string())),
+                Ok((4, 20, "42".to_string())),
+                Ok((4, 23, "true".to_string())),
+                Ok((5, 28, "null".to_string())),
+                Ok((6, 33, "{\n  \"b\": 2\n}".to_string())),
+                Ok((9, 46, "-1.5e3".to_string())),
+            ]
+        );
+
+        assert!(records("", false).is_empty());
+        assert!(records(" \n\n ", false).is_empty());
+    }
+
+    #[test]
+    fn a_bad_record_ends_a_strict_read() {
+        let json = "{\"a\":1}\n{\"a\" 2}\n{\"a\":3}";
+
+        assert_eq!(
+            records(json, false),
+            vec![Ok((1, 0, "{\"a\":1}".to_string())), Err(2)]
+        );
+
+        let mut iterator = JsonLIterator::new(SliceIterator::from_str(json));
+        iterator.get_next().unwrap().unwrap();
+        let err = iterator.get_next().unwrap().unwrap_err();
+        assert!(err.to_string().contains("line 2"), "{}", err.to_string());
+    }
+
+    #[test]
+    fn skip_bad_records_resumes_at_the_next_line() {
+        let json = "{\"a\":1}\n{\"a\": \"open\n{\"a\":3} {\"a\":4}\nnope\n[5]";
+
+        assert_eq!(
+            records(json, true),
+            vec![
+                Ok((1, 0, "{\"a\":1}".to_string())),
+                Err(2),
+                Ok((3, 20, "{\"a\":3}".to_string())),
+                Ok((3, 28, "{\"a\":4}".to_string())),
+                Err(4),
+                Ok((5, 41, "[5]".to_string())),
+            ]
+        );
+    }
+
+    #[test]
+    fn a_read_resumes_from_its_checkpoint() {
+        let json = "{\"a\":1}\n[2] 3\n{\n\"b\":4\n}\n\"5\"";
+
+        let all = records(json, false);
+
+        for taken_after in 0..all.len() {
+            let mut iterator = JsonLIterator::new(SliceIterator::from_str(json));
+
+            for _ in 0..taken_after {
+                iterator.get_next_record().unwrap().unwrap();
+            }
+
+            let checkpoint = iterator.checkpoint();
+
+            let mut resumed =
+                JsonLIterator::resume(SliceIterator::from_str(json), checkpoint).unwrap();
+
+            let mut rest = Vec::new();
+            while let Some(record) = resumed.get_next_record() {
+                let record = record.unwrap();
+                rest.push(Ok((
+                    record.line,
+                    record.offset,
+                    String::from_utf8(record.data.to_vec()).unwrap(),
+                )));
+            }
+
+            assert_eq!(rest, all[taken_after..].to_vec());
+        }
+    }
+
+    #[test]
+    fn a_checkpoint_off_a_record_boundary_is_rejected() {
+        let json = "{\"a\":12}\n{\"b\":2}";
+
+        for offset in [1, 5, 7, 100] {
+            let checkpoint = JsonLCheckpoint { offset, line: 1 };
+            assert!(
+                JsonLIterator::resume(SliceIterator::from_str(json), checkpoint).is_err(),
+                "{}",
+                offset
+            );
+        }
+
+        let checkpoint = JsonLCheckpoint { offset: 8, line: 1 };
+        let mut resumed = JsonLIterator::resume(SliceIterator::from_str(json), checkpoint).unwrap();
+        let record = resumed.get_next_record().unwrap().unwrap();
+        assert_eq!((record.line, record.data), (2, "{\"b\":2}".as_bytes()));
+
+        let end = JsonLCheckpoint {
+            offset: json.len(),
+            line: 2,
+        };
+        let mut resumed = JsonLIterator::resume(SliceIterator::from_str(json), end).unwrap();
+        assert!(resumed.get_next_record().is_none());
+    }
 }
@@ -1,43 +1,334 @@
-use super::{bytes_of_array_reader::*, JsonParseError};
+use std::ops::Range;
+
+use super::bytes_of_array_reader::{async_reader, release_read, RecordingIterator};
+use super::json_l_record::{
+    check_record_boundary, count_lines, read_record_from, skip_record_value_async,
+};
+use super::{JsonLCheckpoint, JsonLRecord, JsonLRecordError, JsonParseError, JsonValueReaderOwned};
 use rust_extensions::array_of_bytes_iterator::*;
 
+/// The async counterpart of [`JsonLIterator`](super::JsonLIterator): any JSON value is a record,
+/// and a malformed one ends the iteration unless [`Self::with_skip_bad_records`] is set.
 pub struct JsonLIteratorAsync<TArrayOfBytesIterator: ArrayOfBytesIteratorAsync> {
     data: TArrayOfBytesIterator,
+    line: usize,
+    skip_bad_records: bool,
+    finished: bool,
+    // Skip-bad mode reads a line at a time: the line, its number and offset, and how much of it
+    // has been read.
+    line_buffer: Vec<u8>,
+    buffer_line: usize,
+    buffer_offset: usize,
+    buffer_pos: usize,
+    // Where `get_next_record_ref` reads each record into.
+    record_buffer: Vec<u8>,
 }
 
 impl<TArrayOfBytesIterator: ArrayOfBytesIteratorAsync> JsonLIteratorAsync<TArrayOfBytesIterator> {
-    pub async fn new(mut data: TArrayOfBytesIterator) -> Self {
-        async_reader::skip_white_spaces(&mut data).await.unwrap();
-        Self { data }
+    // Leading white space is skipped by `get_next`, so nothing is read here and nothing can fail:
+    // an empty or blank payload is one with no lines.
+    pub async fn new(data: TArrayOfBytesIterator) -> Self {
+        Self {
+            data,
+            line: 1,
+            skip_bad_records: false,
+            finished: false,
+            line_buffer: Vec::new(),
+            buffer_line: 0,
+            buffer_offset: 0,
+            buffer_pos: 0,
+            record_buffer: Vec::new(),
+        }
+    }
+
+    /// Carries on a read from a [`Self::checkpoint`] taken over the same payload. `data` can be at
+    /// the start of the payload - everything up to the checkpoint is then read past, not parsed -
+    /// or already at the checkpoint's offset.
+    pub async fn resume(
+        mut data: TArrayOfBytesIterator,
+        checkpoint: JsonLCheckpoint,
+    ) -> Result<Self, JsonParseError> {
+        // The byte before the offset is what tells a boundary from the middle of a record. It can
+        // only be read when `data` starts before the offset.
+        let before = if data.get_pos() < checkpoint.offset {
+            async_reader::advance_to(&mut data, checkpoint.offset - 1).await?;
+            data.get_next().await?.map(|next_value| next_value.value)
+        } else {
+            None
+        };
+
+        async_reader::advance_to(&mut data, checkpoint.offset).await?;
+
+        check_record_boundary(
+            before,
+            data.peek_value().map(|next_value| next_value.value),
+            checkpoint.offset,
+        )?;
+
+        let mut result = Self::new(data).await;
+        result.line = checkpoint.line;
+        Ok(result)
+    }
+
+    /// Where the read has got to, to [`Self::resume`] it from later.
+    pub fn checkpoint(&self) -> JsonLCheckpoint {
+        // Part of a line read in skip-bad mode may still be waiting in the buffer.
+        if self.buffer_pos < self.line_buffer.len() {
+            return JsonLCheckpoint {
+                offset: self.buffer_offset + self.buffer_pos,
+                line: self.buffer_line,
+            };
+        }
+
+        JsonLCheckpoint {
+            offset: self.data.get_pos(),
+            line: self.line,
+        }
+    }
+
+    /// A malformed record is reported with its line, and reading resumes at the next line. A
+    /// record can not span lines in this mode.
+    pub fn with_skip_bad_records(mut self) -> Self {
+        self.skip_bad_records = true;
+        self
+    }
+
+    /// The next line, read as `T`. A line that is not a `T` is an `Err` for that line only; the
+    /// iteration can go on.
+    pub async fn read_as<T: JsonValueReaderOwned>(&mut self) -> Option<Result<T, JsonParseError>> {
+        let item = self.get_next().await?;
+        Some(item.and_then(|bytes| T::from_json_slice(&bytes)))
     }
 
     pub async fn get_next<'s>(&'s mut self) -> Option<Result<Vec<u8>, JsonParseError>> {
-        let start_value = match async_reader::skip_white_spaces(&mut self.data).await {
-            Ok(value) => value,
-            Err(_) => return None,
+        let record = self.get_next_record().await?;
+        Some(
+            record
+                .map(|record| record.data)
+                .map_err(JsonParseError::from),
+        )
+    }
+
+    /// The next record with its line and offset.
+    pub async fn get_next_record(
+        &mut self,
+    ) -> Option<Result<JsonLRecord<Vec<u8>>, JsonLRecordError>> {
+        if self.finished {
+            return None;
+        }
+
+        if self.skip_bad_records {
+            let record = self.read_record_in_line().await?;
+            return Some(record.map(|record| JsonLRecord {
+                line: record.line,
+                offset: record.offset,
+                data: self.line_buffer[record.data].to_vec(),
+            }));
+        }
+
+        let result = self.read_record().await;
+
+        if let Some(Err(_)) = &result {
+            self.finished = true;
+        }
+
+        result
+    }
+
+    async fn read_record(&mut self) -> Option<Result<JsonLRecord<Vec<u8>>, JsonLRecordError>> {
+        let start = match self.skip_to_next_record().await {
+            Ok(start) => start?,
+            Err(error) => return Some(Err(self.error_here(error))),
+        };
+
+        let line = self.line;
+
+        let result = match skip_record_value_async(&mut self.data, start).await {
+            Ok(()) => self
+                .data
+                .get_slice_to_current_pos(start.pos)
+                .await
+                .map_err(JsonParseError::from),
+            Err(err) => Err(err),
         };
 
-        match start_value.value {
-            crate::consts::OPEN_BRACKET => {
-                match async_reader::find_the_end_of_json_object_or_array(&mut self.data).await {
-                    Ok(_) => {}
-                    Err(err) => return Some(Err(err)),
+        match result {
+            Ok(data) => {
+                self.line += count_lines(&data);
+
+                Some(Ok(JsonLRecord {
+                    line,
+                    offset: start.pos,
+                    data,
+                }))
+            }
+            Err(error) => Some(Err(JsonLRecordError {
+                line,
+                offset: start.pos,
+                error,
+            })),
+        }
+    }
+
+    // The bytes read from an async source are gone, so a broken record could not be backed out
+    // of to find the next line. Reading the line first keeps a broken record within it.
+    // The record's data is its range in the line.
+    async fn read_record_in_line(
+        &mut self,
+    ) -> Option<Result<JsonLRecord<Range<usize>>, JsonLRecordError>> {
+        loop {
+            let rest = &self.line_buffer[self.buffer_pos..];
+
+            if let Some(skipped) = rest.iter().position(|b| *b > 32) {
+                let start = self.buffer_pos + skipped;
+                let offset = self.buffer_offset + start;
+
+                return match read_record_from(&self.line_buffer[start..]) {
+                    Ok(len) => {
+                        self.buffer_pos = start + len;
+
+                        Some(Ok(JsonLRecord {
+                            line: self.buffer_line,
+                            offset,
+                            data: start..start + len,
+                        }))
+                    }
+                    Err(error) => {
+                        self.buffer_pos = self.line_buffer.len();
+
+                        Some(Err(JsonLRecordError {
+                            line: self.buffer_line,
+                            offset,
+                            error,
+                        }))
+                    }
+                };
+            }
+
+            match self.read_line().await {
+                Ok(true) => {}
+                Ok(false) => return None,
+                Err(error) => {
+                    self.finished = true;
+                    return Some(Err(self.error_here(error)));
                 }
             }
+        }
+    }
+
+    // `Ok(false)` once the payload is exhausted.
+    async fn read_line(&mut self) -> Result<bool, JsonParseError> {
+        self.line_buffer.clear();
+        self.buffer_pos = 0;
+        self.buffer_line = self.line;
+        self.buffer_offset = self.data.get_pos();
+
+        while let Some(next_value) = self.data.get_next().await? {
+            if next_value.value == b'\n' {
+                self.line += 1;
+                release_read(&mut self.data).await?;
+                return Ok(true);
+            }
+
+            self.line_buffer.push(next_value.value);
+        }
+
+        Ok(!self.line_buffer.is_empty())
+    }
+
+    async fn skip_to_next_record(&mut self) -> Result<Option<NextValue>, JsonParseError> {
+        while let Some(next_value) = self.data.peek_value() {
+            if next_value.value > 32 {
+                return Ok(Some(next_value));
+            }
 
-            _ => {
-                return Some(Err(JsonParseError::new(format!(
-                    "Error reading value as object. Start {}. We reached the end of the payload",
-                    start_value.pos
-                ))));
+            if next_value.value == b'\n' {
+                self.line += 1;
+            }
+
+            self.data.get_next().await?;
+        }
+
+        Ok(None)
+    }
+
+    fn error_here(&self, error: JsonParseError) -> JsonLRecordError {
+        JsonLRecordError {
+            line: self.line,
+            offset: self.data.get_pos(),
+            error,
+        }
+    }
+}
+
+impl<TArrayOfBytesIterator: ArrayOfBytesIteratorAsync + Send + Sync>
+    JsonLIteratorAsync<TArrayOfBytesIterator>
+{
+    /// [`Self::get_next`] without allocating for every record: the slice lent out is valid
+    /// until the next call.
+    pub async fn get_next_ref(&mut self) -> Option<Result<&[u8], JsonParseError>> {
+        let record = self.get_next_record_ref().await?;
+        Some(
+            record
+                .map(|record| record.data)
+                .map_err(JsonParseError::from),
+        )
+    }
+
+    /// [`Self::get_next_record`] without allocating for every record: the record is read into a
+    /// buffer the iterator keeps, and is valid until the next call.
+    pub async fn get_next_record_ref(
+        &mut self,
+    ) -> Option<Result<JsonLRecord<&[u8]>, JsonLRecordError>> {
+        if self.finished {
+            return None;
+        }
+
+        if self.skip_bad_records {
+            let record = self.read_record_in_line().await?;
+            return Some(record.map(|record| JsonLRecord {
+                line: record.line,
+                offset: record.offset,
+                data: &self.line_buffer[record.data],
+            }));
+        }
+
+        let start = match self.skip_to_next_record().await {
+            Ok(start) => start?,
+            Err(error) => {
+                self.finished = true;
+                return Some(Err(self.error_here(error)));
             }
         };
 
-        let result = self
-            .data
-            .get_slice_to_current_pos(start_value.pos)
-            .await
-            .unwrap();
-        return Some(Ok(result));
+        let line = self.line;
+        self.record_buffer.clear();
+
+        let mut src = RecordingIterator::new(&mut self.data, &mut self.record_buffer);
+
+        let result = match skip_record_value_async(&mut src, start).await {
+            Ok(()) => release_read(&mut self.data)
+                .await
+                .map_err(JsonParseError::from),
+            Err(err) => Err(err),
+        };
+
+        if let Err(error) = result {
+            self.finished = true;
+
+            return Some(Err(JsonLRecordError {
+                line,
+                offset: start.pos,
+                error,
+            }));
+        }
+
+        self.line += count_lines(&self.record_buffer);
+
+        Some(Ok(JsonLRecord {
+            line,
+            offset: start.pos,
+            data: &self.record_buffer,
+        }))
     }
 }
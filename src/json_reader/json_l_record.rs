@@ -0,0 +1,192 @@
+use rust_extensions::array_of_bytes_iterator::*;
+
+use super::{bytes_of_array_reader::*, JsonParseError};
+
+/// One JSON-L record, with where it was found.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JsonLRecord<TData> {
+    /// The line the record starts on, counting from 1.
+    pub line: usize,
+    /// The offset of the record's first byte in the payload.
+    pub offset: usize,
+    pub data: TData,
+}
+
+/// A record that could not be read, with where it was found.
+#[derive(Debug)]
+pub struct JsonLRecordError {
+    /// The line the record starts on, counting from 1.
+    pub line: usize,
+    /// The offset of the record's first byte in the payload.
+    pub offset: usize,
+    pub error: JsonParseError,
+}
+
+impl From<JsonLRecordError> for JsonParseError {
+    fn from(value: JsonLRecordError) -> Self {
+        JsonParseError::new(format!(
+            "Error reading JSON-L record at line {} (offset {}). {}",
+            value.line,
+            value.offset,
+            value.error.into_string()
+        ))
+    }
+}
+
+/// Where a JSON-L read got to: `get_next_record` on an iterator resumed from it reads the record
+/// after the last one handed out before it was taken.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct JsonLCheckpoint {
+    pub offset: usize,
+    /// The line `offset` is on, counting from 1, so records keep their line numbers.
+    pub line: usize,
+}
+
+// A checkpoint is taken between records, so whatever comes next is white space, a record or the
+// end of the payload - and when the byte before is known, it can not run on into the next one.
+// This catches an offset from another payload or a corrupted one; it can not prove an offset is
+// one a read actually stopped at.
+pub(crate) fn check_record_boundary(
+    before: Option<u8>,
+    at: Option<u8>,
+    offset: usize,
+) -> Result<(), JsonParseError> {
+    let at = match at {
+        Some(at) => at,
+        None => return Ok(()),
+    };
+
+    let is_space = |value: u8| value <= 32;
+    let continues_token = |value: u8| value.is_ascii_alphanumeric() || is_number_byte(value);
+
+    let at_ok = is_space(at) || starts_value(at);
+
+    let before_ok = match before {
+        Some(before) if !is_space(before) => {
+            (matches!(before, b'}' | b']' | b'"') || continues_token(before))
+                && !(continues_token(before) && continues_token(at))
+        }
+        _ => true,
+    };
+
+    if at_ok && before_ok {
+        return Ok(());
+    }
+
+    Err(JsonParseError::new(format!(
+        "Checkpoint offset {} is not on a record boundary. Found '{}'",
+        offset, at as char
+    )))
+}
+
+fn starts_value(value: u8) -> bool {
+    matches!(
+        value,
+        crate::consts::OPEN_BRACKET
+            | crate::consts::OPEN_ARRAY
+            | crate::consts::DOUBLE_QUOTE
+            | crate::consts::START_OF_TRUE_LOWER_CASE
+            | crate::consts::START_OF_TRUE_UPPER_CASE
+            | crate::consts::START_OF_FALSE_LOWER_CASE
+            | crate::consts::START_OF_FALSE_UPPER_CASE
+            | crate::consts::START_OF_NULL_LOWER_CASE
+            | crate::consts::START_OF_NULL_UPPER_CASE
+            | b'-'
+            | b'0'..=b'9'
+    )
+}
+
+pub(crate) fn count_lines(src: &[u8]) -> usize {
+    src.iter().filter(|b| **b == b'\n').count()
+}
+
+// A record can be any JSON value. `find_the_end_of_the_number` wants a byte after the number, so
+// a number at the very end of the payload - a common last record - is scanned here instead.
+fn is_number_byte(value: u8) -> bool {
+    value.is_ascii_digit() || matches!(value, b'-' | b'+' | b'.' | b'e' | b'E')
+}
+
+fn check_number(raw: &[u8], start_pos: usize) -> Result<(), JsonParseError> {
+    match crate::json_utils::is_number(raw) {
+        crate::json_utils::NumberType::NaN => Err(JsonParseError::new(format!(
+            "Invalid number found ['{}'] at position {}",
+            String::from_utf8_lossy(raw),
+            start_pos
+        ))),
+        _ => Ok(()),
+    }
+}
+
+/// Moves `src` past the JSON value starting at `start`, which has been peeked but not consumed.
+pub(crate) fn skip_record_value(
+    src: &impl ArrayOfBytesIterator,
+    start: NextValue,
+) -> Result<(), JsonParseError> {
+    match start.value {
+        crate::consts::OPEN_BRACKET => {
+            sync_reader::find_the_end_of_json(src)?;
+        }
+        crate::consts::OPEN_ARRAY => {
+            sync_reader::find_the_end_of_array(src)?;
+        }
+        value if is_number_byte(value) => {
+            while let Some(next_value) = src.peek_value() {
+                if !is_number_byte(next_value.value) {
+                    break;
+                }
+                src.get_next();
+            }
+
+            check_number(src.get_slice_to_current_pos(start.pos), start.pos)?;
+        }
+        value => {
+            sync_reader::find_the_end_of_the_object_value(src, value)?;
+        }
+    }
+
+    Ok(())
+}
+
+pub(crate) async fn skip_record_value_async(
+    src: &mut impl ArrayOfBytesIteratorAsync,
+    start: NextValue,
+) -> Result<(), JsonParseError> {
+    match start.value {
+        crate::consts::OPEN_BRACKET => {
+            async_reader::find_the_end_of_json(src).await?;
+        }
+        crate::consts::OPEN_ARRAY => {
+            async_reader::find_the_end_of_array(src).await?;
+        }
+        value if is_number_byte(value) => {
+            while let Some(next_value) = src.peek_value() {
+                if !is_number_byte(next_value.value) {
+                    break;
+                }
+                src.get_next().await?;
+            }
+
+            let raw = src.get_slice_to_current_pos(start.pos).await?;
+            check_number(&raw, start.pos)?;
+        }
+        value => {
+            async_reader::find_the_end_of_the_object_value(src, value).await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Reads the record `src` starts with - its first byte is the record's - and returns the
+/// record's length.
+pub(crate) fn read_record_from(src: &[u8]) -> Result<usize, JsonParseError> {
+    let src = SliceIterator::new(src);
+
+    let start = src.peek_value().ok_or_else(|| {
+        JsonParseError::new("Error reading JSON-L record. The record is empty".to_string())
+    })?;
+
+    skip_record_value(&src, start)?;
+
+    Ok(src.get_pos())
+}
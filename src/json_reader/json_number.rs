@@ -0,0 +1,202 @@
+use super::json_value_reader::{preview, raw_slice};
+use super::{JsonParseError, JsonValueReader, JsonValueRef};
+
+/// A JSON number, kept as the digits it was written with.
+///
+/// Nothing is parsed until a conversion asks for a type, so no value is lost on the way in:
+/// `u64::MAX`, a 30-digit id and `1E-05` all come through intact, and each conversion is exact
+/// for its target - an integer conversion fails rather than truncates, and
+/// [`Self::to_decimal`] keeps every digit. Only [`Self::as_f64`] rounds, as any `f64` must.
+///
+/// Written back through `JsonValueWriter`, it emits the original digits unchanged.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct JsonNumber<'s> {
+    raw: &'s str,
+}
+
+impl<'s> JsonNumber<'s> {
+    /// `None` unless `raw` is a JSON number.
+    pub fn new(raw: &'s str) -> Option<Self> {
+        match crate::json_utils::is_number(raw.as_bytes()) {
+            crate::json_utils::NumberType::NaN => None,
+            _ => Some(Self { raw }),
+        }
+    }
+
+    /// The digits as written.
+    pub fn as_str(&self) -> &'s str {
+        self.raw
+    }
+
+    /// Whether the number is written without a fraction or an exponent - `1` but not `1.0`
+    /// or `1e3`.
+    pub fn is_integer(&self) -> bool {
+        !self.raw.contains(['.', 'e', 'E'])
+    }
+
+    pub fn as_i64(&self) -> Result<i64, JsonParseError> {
+        i64::try_from(*self)
+    }
+
+    pub fn as_u64(&self) -> Result<u64, JsonParseError> {
+        u64::try_from(*self)
+    }
+
+    pub fn as_i128(&self) -> Result<i128, JsonParseError> {
+        i128::try_from(*self)
+    }
+
+    pub fn as_u128(&self) -> Result<u128, JsonParseError> {
+        u128::try_from(*self)
+    }
+
+    /// The nearest `f64`. A magnitude beyond `f64::MAX` is infinite.
+    pub fn as_f64(&self) -> Result<f64, JsonParseError> {
+        self.raw.parse().map_err(|err| {
+            JsonParseError::new(format!(
+                "JSON number {} can not be read as f64. {}",
+                self.raw, err
+            ))
+        })
+    }
+
+    /// The exact decimal value, exponent form included.
+    #[cfg(feature = "decimal")]
+    pub fn to_decimal(&self) -> Result<rust_decimal::Decimal, JsonParseError> {
+        let result = if self.raw.contains(['e', 'E']) {
+            rust_decimal::Decimal::from_scientific(self.raw)
+        } else {
+            rust_decimal::Decimal::from_str_exact(self.raw)
+        };
+
+        result.map_err(|err| {
+            JsonParseError::new(format!(
+                "JSON number {} can not be read as rust_decimal::Decimal. {}",
+                self.raw, err
+            ))
+        })
+    }
+}
+
+// Exact, or an error: a fraction or an exponent is never rounded away, and an out of range value
+// never wraps.
+macro_rules! impl_try_from_json_number_for_integer {
+    ($($t:ty),* $(,)?) => {
+        $(
+            impl<'s> TryFrom<JsonNumber<'s>> for $t {
+                type Error = JsonParseError;
+
+                fn try_from(value: JsonNumber<'s>) -> Result<Self, Self::Error> {
+                    if !value.is_integer() {
+                        return Err(JsonParseError::new(format!(
+                            "JSON value is a floating point number ({}) and can not be converted to integer type {} without loss",
+                            value.raw,
+                            stringify!($t)
+                        )));
+                    }
+
+                    value.raw.parse().map_err(|_| {
+                        JsonParseError::new(format!(
+                            "JSON number {} is out of range for type {}",
+                            value.raw,
+                            stringify!($t)
+                        ))
+                    })
+                }
+            }
+        )*
+    };
+}
+
+impl_try_from_json_number_for_integer!(
+    i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize
+);
+
+impl<'s> JsonValueReader<'s> for JsonNumber<'s> {
+    fn from_json_value(value: &JsonValueRef<'s>) -> Result<Self, JsonParseError> {
+        let raw = raw_slice(value);
+
+        std::str::from_utf8(raw)
+            .ok()
+            .and_then(JsonNumber::new)
+            .ok_or_else(|| {
+                JsonParseError::new(format!(
+                    "JSON value {} can not be read as a number",
+                    preview(raw)
+                ))
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::JsonNumber;
+    use crate::json_reader::{read_json_value, UnwrappedJsonValue};
+    use crate::json_writer::JsonValueWriter;
+
+    #[test]
+    fn wide_integers_are_kept_exactly() {
+        let json = br#"{"max":18446744073709551615,"id":123456789012345678901234567890}"#;
+
+        let max = crate::j_path::get_value(json, "max").unwrap().unwrap();
+        let max = max.try_get_json_number().unwrap();
+        assert_eq!(max.as_u64().unwrap(), u64::MAX);
+        assert!(max.as_i64().is_err());
+
+        let id = crate::j_path::get_value(json, "id").unwrap().unwrap();
+        let id = id.try_get_json_number().unwrap();
+        assert_eq!(id.as_u128().unwrap(), 123456789012345678901234567890u128);
+        assert_eq!(id.as_str(), "123456789012345678901234567890");
+        assert!(u64::try_from(id).is_err());
+    }
+
+    #[test]
+    fn unwrap_value_no_longer_fails_on_wide_numbers() {
+        let json = br#"{"v":18446744073709551615}"#;
+        let value = crate::j_path::get_value(json, "v").unwrap().unwrap();
+
+        match value.unwrap_value().unwrap() {
+            UnwrappedJsonValue::Number(number) => assert_eq!(number.as_u64().unwrap(), u64::MAX),
+            other => panic!("expected a number, got {}", other.get_type_name()),
+        }
+
+        assert_eq!(value.try_get_number::<u64>().unwrap(), u64::MAX);
+    }
+
+    #[test]
+    fn fractions_and_exponents_are_doubles() {
+        let number = JsonNumber::new("1E-05").unwrap();
+        assert!(!number.is_integer());
+        assert_eq!(number.as_f64().unwrap(), 0.00001);
+        assert!(number.as_i64().is_err());
+
+        assert!(u8::try_from(JsonNumber::new("1.0").unwrap()).is_err());
+        assert!(JsonNumber::new("abc").is_none());
+        assert!(JsonNumber::new("").is_none());
+    }
+
+    #[cfg(feature = "decimal")]
+    #[test]
+    fn decimals_keep_every_digit() {
+        let number = JsonNumber::new("1E-05").unwrap();
+        assert_eq!(number.to_decimal().unwrap().to_string(), "0.00001");
+
+        // more digits than an f64 holds
+        let number = JsonNumber::new("0.1000000000000000055511151231").unwrap();
+        assert_eq!(number.to_decimal().unwrap().to_string(), number.as_str());
+
+        let number = JsonNumber::new("100.00").unwrap();
+        assert_eq!(number.to_decimal().unwrap().scale(), 2);
+    }
+
+    #[test]
+    fn written_back_verbatim() {
+        let number: JsonNumber = read_json_value(b" 1E-05 ").unwrap();
+
+        let mut dest = String::new();
+        number.write(&mut dest);
+        assert_eq!(dest, "1E-05");
+
+        assert!(read_json_value::<JsonNumber>(b"\"1\"").is_err());
+    }
+}
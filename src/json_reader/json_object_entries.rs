@@ -0,0 +1,204 @@
+use std::iter::FusedIterator;
+
+use rust_extensions::array_of_bytes_iterator::{ArrayOfBytesIterator, SliceIterator};
+
+use super::{JsonFieldNameRef, JsonFirstLineReaderInner, JsonParseError, JsonValueRef};
+
+/// The entries of a JSON object as a standard [`Iterator`].
+///
+/// The counterpart of [`JsonArrayItems`](super::JsonArrayItems) for objects: `Send`, `Sync`,
+/// usable with any iterator adapter, and a clone carries on from where the original is.
+/// [`Self::keys`] and [`Self::values`] narrow it down to one side of each entry.
+///
+/// An `Err` is the last item: malformed JSON ends the iteration.
+#[derive(Debug, Clone)]
+pub struct JsonObjectEntries<'s> {
+    slice: &'s [u8],
+    pos: usize,
+    had_init: bool,
+    finished: bool,
+}
+
+impl<'s> JsonObjectEntries<'s> {
+    pub fn new(slice: &'s [u8]) -> Self {
+        Self {
+            slice,
+            pos: 0,
+            had_init: false,
+            finished: false,
+        }
+    }
+
+    pub fn as_slice(&self) -> &'s [u8] {
+        self.slice
+    }
+
+    pub fn keys(
+        self,
+    ) -> impl Iterator<Item = Result<JsonFieldNameRef<'s>, JsonParseError>> + Clone + 's {
+        self.map(|entry| entry.map(|(key, _)| key))
+    }
+
+    pub fn values(
+        self,
+    ) -> impl Iterator<Item = Result<JsonValueRef<'s>, JsonParseError>> + Clone + 's {
+        self.map(|entry| entry.map(|(_, value)| value))
+    }
+}
+
+impl<'s> Iterator for JsonObjectEntries<'s> {
+    type Item = Result<(JsonFieldNameRef<'s>, JsonValueRef<'s>), JsonParseError>;
+
+    // Same approach as `JsonArrayItems`: a `SliceIterator` is rebuilt at the saved position for
+    // each entry.
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+
+        let data = SliceIterator::new(self.slice);
+        data.advance(self.pos);
+
+        let inner = JsonFirstLineReaderInner::resume(data, self.had_init);
+        let result = inner.get_next();
+
+        self.pos = inner.get_pos();
+        self.had_init = inner.had_init();
+
+        match result {
+            Some(Ok(item)) => Some(Ok((
+                JsonFieldNameRef::new(item.name, self.slice),
+                JsonValueRef::new(item.value, self.slice),
+            ))),
+            Some(Err(err)) => {
+                self.finished = true;
+                Some(Err(err))
+            }
+            None => {
+                self.finished = true;
+                None
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.finished {
+            return (0, Some(0));
+        }
+
+        // every entry takes at least four bytes: `"":0`, and a malformed object ends with one
+        // `Err` more
+        (0, Some((self.slice.len() - self.pos) / 4 + 1))
+    }
+}
+
+impl<'s> FusedIterator for JsonObjectEntries<'s> {}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::JsonObjectEntries;
+
+    const JSON: &[u8] = br#"{"id": 7, "name": "Bob", "tags": ["a", "b"], "meta": {}}"#;
+
+    #[test]
+    fn entries_work_with_for_and_collect() {
+        let mut names = Vec::new();
+
+        for entry in JsonObjectEntries::new(JSON) {
+            let (key, _) = entry.unwrap();
+            names.push(key.as_str().unwrap().to_string());
+        }
+
+        assert_eq!(names, vec!["id", "name", "tags", "meta"]);
+
+        let raw: HashMap<String, String> = JsonObjectEntries::new(JSON)
+            .map(|entry| {
+                let (key, value) = entry.unwrap();
+                (
+                    key.as_str().unwrap().to_string(),
+                    String::from_utf8(value.as_bytes().to_vec()).unwrap(),
+                )
+            })
+            .collect();
+
+        assert_eq!(raw["tags"], r#"["a", "b"]"#);
+        assert_eq!(raw["meta"], "{}");
+    }
+
+    #[test]
+    fn keys_and_values() {
+        let keys: Vec<String> = JsonObjectEntries::new(JSON)
+            .keys()
+            .map(|key| key.unwrap().as_str().unwrap().to_string())
+            .collect();
+
+        assert_eq!(keys, vec!["id", "name", "tags", "meta"]);
+
+        let mut values = JsonObjectEntries::new(JSON).values();
+        assert_eq!(
+            values.next().unwrap().unwrap().unwrap_as_number().unwrap(),
+            Some(7)
+        );
+        assert_eq!(values.count(), 3);
+    }
+
+    #[test]
+    fn nested_values_iterate_the_same_way() {
+        let (_, tags) = JsonObjectEntries::new(JSON).nth(2).unwrap().unwrap();
+
+        let tags: Vec<String> = tags
+            .array_items()
+            .unwrap()
+            .map(|tag| tag.unwrap().as_str().unwrap().to_string())
+            .collect();
+        assert_eq!(tags, vec!["a", "b"]);
+
+        let (_, meta) = JsonObjectEntries::new(JSON).last().unwrap().unwrap();
+        assert_eq!(meta.object_entries().unwrap().count(), 0);
+        assert!(meta.array_items().is_err());
+    }
+
+    #[test]
+    fn a_clone_restarts_and_an_error_ends_the_iteration() {
+        let mut entries = JsonObjectEntries::new(JSON);
+        entries.next().unwrap().unwrap();
+
+        assert_eq!(entries.clone().count(), 3);
+        assert_eq!(entries.count(), 3);
+
+        let mut entries = JsonObjectEntries::new(br#"{"a":1, "b" 2}"#);
+        assert!(entries.next().unwrap().is_ok());
+        assert!(entries.next().unwrap().is_err());
+        assert!(entries.next().is_none());
+
+        assert!(JsonObjectEntries::new(b"{}").next().is_none());
+        assert!(JsonObjectEntries::new(b"[]").next().unwrap().is_err());
+    }
+
+    #[test]
+    fn size_hint_bounds_what_is_yielded() {
+        for json in [
+            &b""[..],
+            b"{",
+            b"{}",
+            br#"{"a":1,"#,
+            br#"{"a":1, "b" 2}"#,
+            JSON,
+        ] {
+            let mut entries = JsonObjectEntries::new(json);
+
+            loop {
+                let (lower, upper) = entries.size_hint();
+                let left = entries.clone().count();
+
+                assert!(lower <= left && left <= upper.unwrap(), "{:?}", json);
+
+                if entries.next().is_none() {
+                    break;
+                }
+            }
+        }
+    }
+}
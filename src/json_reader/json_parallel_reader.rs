@@ -0,0 +1,260 @@
+use rust_extensions::array_of_bytes_iterator::SliceIterator;
+
+use super::json_l_record::count_lines;
+use super::{
+    JsonArrayItems, JsonLCheckpoint, JsonLIterator, JsonParseError, JsonValue, JsonValueRef,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Layout {
+    JsonL,
+    Array,
+}
+
+/// Runs a closure over every record of a JSON-L payload, or every element of a top-level JSON
+/// array, on all CPU cores.
+///
+/// The payload is one buffer in memory - a `Vec<u8>`, or the slice of a memory-mapped file. It is
+/// cut into one chunk per thread at record boundaries: JSON-L after a line break, an array
+/// between elements. The results come back in input order.
+///
+/// A JSON-L record must fit on one line here, as JSON-L requires; a record spread over several
+/// lines may be cut in two.
+#[derive(Debug, Clone, Copy)]
+pub struct JsonParallelReader<'s> {
+    src: &'s [u8],
+    layout: Layout,
+    threads: usize,
+}
+
+impl<'s> JsonParallelReader<'s> {
+    pub fn json_l(src: &'s [u8]) -> Self {
+        Self::new(src, Layout::JsonL)
+    }
+
+    pub fn array(src: &'s [u8]) -> Self {
+        Self::new(src, Layout::Array)
+    }
+
+    fn new(src: &'s [u8], layout: Layout) -> Self {
+        let threads = std::thread::available_parallelism()
+            .map(|threads| threads.get())
+            .unwrap_or(1);
+
+        Self {
+            src,
+            layout,
+            threads,
+        }
+    }
+
+    /// Defaults to the number of CPU cores. `0` is taken as `1`.
+    pub fn with_threads(mut self, threads: usize) -> Self {
+        self.threads = threads.max(1);
+        self
+    }
+
+    /// `f` applied to every record, in input order.
+    ///
+    /// Malformed JSON is an `Err` - the first one in input order. `f` may still have run for
+    /// records on either side of it, in other chunks.
+    pub fn map<R: Send>(
+        &self,
+        f: impl Fn(JsonValueRef<'s>) -> R + Sync,
+    ) -> Result<Vec<R>, JsonParseError> {
+        match self.layout {
+            Layout::JsonL => self.map_json_l(&f),
+            Layout::Array => self.map_array(&f),
+        }
+    }
+
+    fn map_json_l<R: Send>(
+        &self,
+        f: &(impl Fn(JsonValueRef<'s>) -> R + Sync),
+    ) -> Result<Vec<R>, JsonParseError> {
+        let src = self.src;
+
+        let mut chunks = Vec::new();
+        let mut checkpoint = JsonLCheckpoint { offset: 0, line: 1 };
+
+        for end in split_at_lines(src, self.threads) {
+            chunks.push((checkpoint, end));
+
+            checkpoint = JsonLCheckpoint {
+                offset: end,
+                line: checkpoint.line + count_lines(&src[checkpoint.offset..end]),
+            };
+        }
+
+        run_in_order(chunks, |(checkpoint, end)| {
+            let mut records = JsonLIterator::resume(SliceIterator::new(&src[..end]), checkpoint)?;
+            let mut result = Vec::new();
+
+            while let Some(record) = records.get_next_record() {
+                let record = record?;
+                let value = JsonValue::new(record.offset, record.offset + record.data.len());
+                result.push(f(JsonValueRef::new(value, src)));
+            }
+
+            Ok(result)
+        })
+    }
+
+    // Where an element ends can only be found by reading up to it, so the elements are found on
+    // this thread first - skipping values is far cheaper than what `f` does with them - and then
+    // shared out.
+    fn map_array<R: Send>(
+        &self,
+        f: &(impl Fn(JsonValueRef<'s>) -> R + Sync),
+    ) -> Result<Vec<R>, JsonParseError> {
+        let items = JsonArrayItems::new(self.src).collect::<Result<Vec<_>, _>>()?;
+
+        let chunk_len = items.len().div_ceil(self.threads).max(1);
+
+        run_in_order(items.chunks(chunk_len).collect(), |chunk| {
+            Ok(chunk.iter().map(|item| f(item.clone())).collect())
+        })
+    }
+}
+
+// The ends of the chunks: the first line break at or after each even split, plus the end of the
+// payload. A line longer than a chunk leaves fewer chunks.
+fn split_at_lines(src: &[u8], chunks: usize) -> Vec<usize> {
+    let mut result = Vec::with_capacity(chunks);
+    let mut start = 0;
+
+    for chunk in 1..chunks {
+        let target = (src.len() * chunk / chunks).max(start);
+
+        match src[target..].iter().position(|b| *b == b'\n') {
+            Some(pos) => {
+                let end = target + pos + 1;
+                if end > start && end < src.len() {
+                    result.push(end);
+                    start = end;
+                }
+            }
+            None => break,
+        }
+    }
+
+    result.push(src.len());
+    result
+}
+
+fn run_in_order<TChunk: Send, R: Send>(
+    chunks: Vec<TChunk>,
+    process: impl Fn(TChunk) -> Result<Vec<R>, JsonParseError> + Sync,
+) -> Result<Vec<R>, JsonParseError> {
+    let process = &process;
+
+    let results: Vec<_> = std::thread::scope(|scope| {
+        let handles: Vec<_> = chunks
+            .into_iter()
+            .map(|chunk| scope.spawn(move || process(chunk)))
+            .collect();
+
+        handles
+            .into_iter()
+            .map(|handle| match handle.join() {
+                Ok(result) => result,
+                Err(panic) => std::panic::resume_unwind(panic),
+            })
+            .collect()
+    });
+
+    let mut result = Vec::new();
+
+    for chunk in results {
+        result.extend(chunk?);
+    }
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{split_at_lines, JsonParallelReader};
+
+    fn json_l_payload(records: usize) -> String {
+        let mut result = String::new();
+
+        for i in 0..records {
+            result.push_str(&format!("{{\"id\":{},\"name\":\"n{}\"}}\n", i, i));
+            if i % 7 == 0 {
+                result.push('\n');
+            }
+        }
+
+        result
+    }
+
+    #[test]
+    fn json_l_results_keep_input_order() {
+        let payload = json_l_payload(1000);
+
+        for threads in [1, 3, 8] {
+            let ids = JsonParallelReader::json_l(payload.as_bytes())
+                .with_threads(threads)
+                .map(|value| {
+                    let id = value.object_entries().unwrap().next().unwrap().unwrap().1;
+                    id.unwrap_as_number().unwrap().unwrap()
+                })
+                .unwrap();
+
+            assert_eq!(ids, (0..1000).collect::<Vec<i64>>());
+        }
+    }
+
+    #[test]
+    fn array_results_keep_input_order() {
+        let payload = format!(
+            "[{}]",
+            (0..1000)
+                .map(|i| format!("{{\"v\":[{}]}}", i))
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+
+        let values = JsonParallelReader::array(payload.as_bytes())
+            .with_threads(6)
+            .map(|value| value.as_slice().len())
+            .unwrap();
+
+        assert_eq!(values.len(), 1000);
+        assert_eq!(values[0], "{\"v\":[0]}".len());
+        assert_eq!(values[999], "{\"v\":[999]}".len());
+
+        let empty = JsonParallelReader::array(b"[]").map(|_| 0).unwrap();
+        assert!(empty.is_empty());
+    }
+
+    #[test]
+    fn the_first_error_is_reported_with_its_line() {
+        let mut payload = json_l_payload(100);
+        payload.push_str("{\"id\":x}\n");
+        payload.push_str(&json_l_payload(100));
+
+        let err = JsonParallelReader::json_l(payload.as_bytes())
+            .with_threads(4)
+            .map(|_| ())
+            .unwrap_err();
+
+        let line = payload.lines().position(|line| line.contains('x')).unwrap() + 1;
+        assert!(
+            err.to_string().contains(&format!("line {} ", line)),
+            "{}",
+            err.to_string()
+        );
+
+        assert!(JsonParallelReader::array(b"[1, x]").map(|_| ()).is_err());
+    }
+
+    #[test]
+    fn chunks_end_after_line_breaks() {
+        let src = b"aaaa\nbb\ncccccccccc\nd";
+        assert_eq!(split_at_lines(src, 4), vec![8, 19, src.len()]);
+        assert_eq!(split_at_lines(b"abc", 4), vec![3]);
+        assert_eq!(split_at_lines(b"", 4), vec![0]);
+    }
+}
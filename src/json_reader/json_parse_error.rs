@@ -1,8 +1,24 @@
+/// Errors are matched on with a `_` arm: `AtPath` was added in 0.4.0, and more kinds of error
+/// may be added without a major release.
 #[derive(Debug)]
+#[non_exhaustive]
 pub enum JsonParseError {
     CanNotFindStartOfTheJsonObject(String),
     CanNotFindStartOfTheArrayObject(String),
     Other(String),
+    /// An error raised while reading a nested value, together with where that value sits
+    /// relative to the value the read started from.
+    AtPath {
+        path: Vec<JsonPathSegment>,
+        error: Box<JsonParseError>,
+    },
+}
+
+/// One step of the path a [`JsonParseError::AtPath`] carries.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum JsonPathSegment {
+    Key(String),
+    Index(usize),
 }
 
 impl JsonParseError {
@@ -10,6 +26,39 @@ impl JsonParseError {
         Self::Other(msg)
     }
 
+    /// Marks the error as raised inside the value under `key`. Readers call this on the way
+    /// back up, so the outermost key ends up first.
+    pub fn with_path_key(self, key: &str) -> Self {
+        self.with_path_segment(JsonPathSegment::Key(key.to_string()))
+    }
+
+    /// Marks the error as raised inside the array element at `index`.
+    pub fn with_path_index(self, index: usize) -> Self {
+        self.with_path_segment(JsonPathSegment::Index(index))
+    }
+
+    fn with_path_segment(self, segment: JsonPathSegment) -> Self {
+        match self {
+            Self::AtPath { mut path, error } => {
+                path.insert(0, segment);
+                Self::AtPath { path, error }
+            }
+            error => Self::AtPath {
+                path: vec![segment],
+                error: Box::new(error),
+            },
+        }
+    }
+
+    /// Where the error was raised, e.g. `$.orders[17].legs[2].qty`; `None` for an error raised
+    /// by the value the read started from.
+    pub fn path(&self) -> Option<String> {
+        match self {
+            Self::AtPath { path, .. } => Some(format_path(path)),
+            _ => None,
+        }
+    }
+
     pub fn to_string(&self) -> String {
         match self {
             Self::CanNotFindStartOfTheJsonObject(msg) => {
@@ -19,6 +68,7 @@ impl JsonParseError {
                 return format!("Can not find start of the array. {}", msg)
             }
             Self::Other(msg) => msg.to_string(),
+            Self::AtPath { path, error } => format!("{}: {}", format_path(path), error.to_string()),
         }
     }
 
@@ -27,6 +77,83 @@ impl JsonParseError {
             Self::CanNotFindStartOfTheJsonObject(msg) => msg,
             Self::CanNotFindStartOfTheArrayObject(msg) => msg,
             Self::Other(msg) => msg,
+            Self::AtPath { .. } => self.to_string(),
         }
     }
 }
+
+// An async source failing to deliver bytes is, to the parser, a payload it can not read - so it
+// surfaces through the same error rather than a panic.
+impl From<std::io::Error> for JsonParseError {
+    fn from(err: std::io::Error) -> Self {
+        Self::new(format!("Error reading the payload. {}", err))
+    }
+}
+
+// A key that is a plain identifier renders as `.key`; anything else as `["key"]`, so a key
+// holding a `.` or a `[` can not be mistaken for more than one step.
+fn format_path(path: &[JsonPathSegment]) -> String {
+    let mut result = String::from("$");
+
+    for segment in path {
+        match segment {
+            JsonPathSegment::Key(key) => {
+                if is_identifier(key) {
+                    result.push('.');
+                    result.push_str(key);
+                } else {
+                    result.push_str("[\"");
+                    crate::json_string_value::write_escaped_json_string_value(key, &mut result);
+                    result.push_str("\"]");
+                }
+            }
+            JsonPathSegment::Index(index) => {
+                result.push('[');
+                result.push_str(&index.to_string());
+                result.push(']');
+            }
+        }
+    }
+
+    result
+}
+
+fn is_identifier(key: &str) -> bool {
+    let mut chars = key.chars();
+
+    match chars.next() {
+        Some(c) if c.is_ascii_alphabetic() || c == '_' => {}
+        _ => return false,
+    }
+
+    chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::JsonParseError;
+
+    #[test]
+    fn test_path_is_built_outside_in() {
+        let err = JsonParseError::new("bad qty".to_string())
+            .with_path_key("qty")
+            .with_path_index(2)
+            .with_path_key("legs")
+            .with_path_index(17)
+            .with_path_key("orders");
+
+        assert_eq!(err.path().unwrap(), "$.orders[17].legs[2].qty");
+        assert_eq!(err.to_string(), "$.orders[17].legs[2].qty: bad qty");
+    }
+
+    #[test]
+    fn test_keys_that_are_not_identifiers_are_quoted() {
+        let err = JsonParseError::new("x".to_string())
+            .with_path_key("a.b")
+            .with_path_key("")
+            .with_path_key("say \"hi\"");
+
+        assert_eq!(err.path().unwrap(), r#"$["say \"hi\""][""]["a.b"]"#);
+        assert!(JsonParseError::new("x".to_string()).path().is_none());
+    }
+}
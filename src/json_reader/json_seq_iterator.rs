@@ -0,0 +1,187 @@
+use std::ops::Range;
+
+use rust_extensions::array_of_bytes_iterator::*;
+
+use super::json_l_record::read_record_from;
+use super::JsonParseError;
+use crate::consts::RECORD_SEPARATOR;
+
+/// Splits an RFC 7464 JSON text sequence (`application/json-seq`) into its values: every record
+/// starts with the `0x1E` record separator, and a value can be of any type and span lines.
+///
+/// The separator marks where the next record starts whatever state the last one was in, so a
+/// malformed record is an `Err` for that record only and the iteration goes on. Empty records are
+/// skipped.
+pub struct JsonSeqIterator<TArrayOfBytesIterator: ArrayOfBytesIterator> {
+    data: TArrayOfBytesIterator,
+}
+
+impl<TArrayOfBytesIterator: ArrayOfBytesIterator> JsonSeqIterator<TArrayOfBytesIterator> {
+    pub fn new(data: TArrayOfBytesIterator) -> Self {
+        Self { data }
+    }
+
+    pub fn get_src_slice(&self) -> &[u8] {
+        self.data.get_src_slice()
+    }
+
+    pub fn get_next(&mut self) -> Option<Result<&[u8], JsonParseError>> {
+        loop {
+            match self.read_record()? {
+                Ok(Some(range)) => return Some(Ok(&self.data.get_src_slice()[range])),
+                Ok(None) => {}
+                Err(err) => return Some(Err(err)),
+            }
+        }
+    }
+
+    // `Ok(None)` for an empty record.
+    fn read_record(&mut self) -> Option<Result<Option<Range<usize>>, JsonParseError>> {
+        let start = self.skip_spaces()?;
+
+        if start.value != RECORD_SEPARATOR {
+            self.skip_to_separator();
+            return Some(Err(missing_separator(start)));
+        }
+
+        self.data.get_next();
+        let from = self.data.get_pos();
+        self.skip_to_separator();
+
+        let record = self.data.get_slice_to_current_pos(from);
+
+        Some(
+            read_seq_record(record, from)
+                .map(|range| range.map(|range| from + range.start..from + range.end)),
+        )
+    }
+
+    fn skip_spaces(&self) -> Option<NextValue> {
+        while let Some(next_value) = self.data.peek_value() {
+            if next_value.value > 32 || next_value.value == RECORD_SEPARATOR {
+                return Some(next_value);
+            }
+
+            self.data.get_next();
+        }
+
+        None
+    }
+
+    fn skip_to_separator(&self) {
+        while let Some(next_value) = self.data.peek_value() {
+            if next_value.value == RECORD_SEPARATOR {
+                return;
+            }
+
+            self.data.get_next();
+        }
+    }
+}
+
+pub(crate) fn missing_separator(found: NextValue) -> JsonParseError {
+    JsonParseError::new(format!(
+        "Expected the json-seq record separator (0x1E) at position {}. Found '{}'",
+        found.pos, found.value as char
+    ))
+}
+
+/// The range of the value within `record` - the bytes between two separators - or `None` when
+/// the record is empty. `offset` is where the record starts in the payload, for errors.
+pub(crate) fn read_seq_record(
+    record: &[u8],
+    offset: usize,
+) -> Result<Option<Range<usize>>, JsonParseError> {
+    let start = match record.iter().position(|b| *b > 32) {
+        Some(start) => start,
+        None => return Ok(None),
+    };
+
+    let len = read_record_from(&record[start..]).map_err(|err| {
+        JsonParseError::new(format!(
+            "Error reading json-seq record at offset {}. {}",
+            offset + start,
+            err.into_string()
+        ))
+    })?;
+
+    let end = start + len;
+    let rest = &record[end..];
+
+    if let Some(pos) = rest.iter().position(|b| *b > 32) {
+        return Err(JsonParseError::new(format!(
+            "Error reading json-seq record at offset {}. Unexpected '{}' after the value at position {}",
+            offset + start,
+            rest[pos] as char,
+            offset + end + pos
+        )));
+    }
+
+    // RFC 7464: a number with nothing after it may have been cut short on its way, and must be
+    // dropped rather than taken for a smaller one.
+    if rest.is_empty() && matches!(record[start], b'-' | b'0'..=b'9') {
+        return Err(JsonParseError::new(format!(
+            "Error reading json-seq record at offset {}. The number is not followed by white space and may be truncated",
+            offset + start
+        )));
+    }
+
+    Ok(Some(start..end))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read_all(src: &[u8]) -> Vec<Result<String, String>> {
+        let mut iterator = JsonSeqIterator::new(SliceIterator::new(src));
+        let mut result = Vec::new();
+
+        while let Some(item) = iterator.get_next() {
+            result.push(
+                item.map(|item| String::from_utf8(item.to_vec()).unwrap())
+                    .map_err(|err| err.to_string()),
+            );
+        }
+
+        result
+    }
+
+    #[test]
+    fn values_of_any_type_and_across_lines() {
+        let src = b"\x1E{\"a\":\n 1}\n\x1E[1,2]\n\x1E\x1E \n\x1E\"s\"\n\x1E12\n\x1Etrue\n";
+
+        let result = read_all(src);
+
+        assert_eq!(
+            result,
+            vec![
+                Ok("{\"a\":\n 1}".to_string()),
+                Ok("[1,2]".to_string()),
+                Ok("\"s\"".to_string()),
+                Ok("12".to_string()),
+                Ok("true".to_string()),
+            ]
+        );
+
+        assert!(read_all(b"").is_empty());
+        assert!(read_all(b" \n").is_empty());
+    }
+
+    #[test]
+    fn a_bad_record_does_not_end_the_sequence() {
+        let src = b"\x1E{\"a\":\n\x1E1\x1E[1] 2\n\x1E{}\n";
+
+        let result = read_all(src);
+
+        assert_eq!(result.len(), 4);
+        assert!(result[0].is_err());
+        assert!(result[1].as_ref().unwrap_err().contains("truncated"));
+        assert!(result[2].as_ref().unwrap_err().contains("Unexpected '2'"));
+        assert_eq!(result[3], Ok("{}".to_string()));
+
+        let result = read_all(b"{}\n\x1E[]\n");
+        assert!(result[0].as_ref().unwrap_err().contains("record separator"));
+        assert_eq!(result[1], Ok("[]".to_string()));
+    }
+}
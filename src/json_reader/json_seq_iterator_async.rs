@@ -0,0 +1,111 @@
+use rust_extensions::array_of_bytes_iterator::*;
+
+use super::json_seq_iterator::{missing_separator, read_seq_record};
+use super::{JsonParseError, JsonValueReaderOwned};
+use crate::consts::RECORD_SEPARATOR;
+
+/// The async counterpart of [`JsonSeqIterator`](super::JsonSeqIterator): a malformed record is an
+/// `Err` for that record only, and empty records are skipped.
+pub struct JsonSeqIteratorAsync<TArrayOfBytesIterator: ArrayOfBytesIteratorAsync> {
+    data: TArrayOfBytesIterator,
+    finished: bool,
+}
+
+impl<TArrayOfBytesIterator: ArrayOfBytesIteratorAsync> JsonSeqIteratorAsync<TArrayOfBytesIterator> {
+    pub async fn new(data: TArrayOfBytesIterator) -> Self {
+        Self {
+            data,
+            finished: false,
+        }
+    }
+
+    /// The next value, read as `T`. A value that is not a `T` is an `Err` for that value only;
+    /// the iteration can go on.
+    pub async fn read_as<T: JsonValueReaderOwned>(&mut self) -> Option<Result<T, JsonParseError>> {
+        let item = self.get_next().await?;
+        Some(item.and_then(|bytes| T::from_json_slice(&bytes)))
+    }
+
+    pub async fn get_next(&mut self) -> Option<Result<Vec<u8>, JsonParseError>> {
+        loop {
+            match self.read_record().await? {
+                Ok(Some(value)) => return Some(Ok(value)),
+                Ok(None) => {}
+                Err(err) => return Some(Err(err)),
+            }
+        }
+    }
+
+    // `Ok(None)` for an empty record. An error reading the source ends the iteration, as nothing
+    // more can be read.
+    async fn read_record(&mut self) -> Option<Result<Option<Vec<u8>>, JsonParseError>> {
+        if self.finished {
+            return None;
+        }
+
+        let start = match self.skip_spaces().await {
+            Ok(start) => start?,
+            Err(err) => return self.fail(err),
+        };
+
+        if start.value != RECORD_SEPARATOR {
+            if let Err(err) = self.skip_to_separator().await {
+                return self.fail(err);
+            }
+
+            return Some(Err(missing_separator(start)));
+        }
+
+        let (from, mut record) = match self.read_raw_record().await {
+            Ok(raw) => raw,
+            Err(err) => return self.fail(err),
+        };
+
+        Some(read_seq_record(&record, from).map(|range| {
+            range.map(|range| {
+                record.truncate(range.end);
+                record.drain(..range.start);
+                record
+            })
+        }))
+    }
+
+    // The bytes between this separator and the next, and where they start.
+    async fn read_raw_record(&mut self) -> Result<(usize, Vec<u8>), JsonParseError> {
+        self.data.get_next().await?;
+        let from = self.data.get_pos();
+        self.skip_to_separator().await?;
+
+        let record = self.data.get_slice_to_current_pos(from).await?;
+        Ok((from, record))
+    }
+
+    fn fail<T>(&mut self, err: JsonParseError) -> Option<Result<T, JsonParseError>> {
+        self.finished = true;
+        Some(Err(err))
+    }
+
+    async fn skip_spaces(&mut self) -> Result<Option<NextValue>, JsonParseError> {
+        while let Some(next_value) = self.data.peek_value() {
+            if next_value.value > 32 || next_value.value == RECORD_SEPARATOR {
+                return Ok(Some(next_value));
+            }
+
+            self.data.get_next().await?;
+        }
+
+        Ok(None)
+    }
+
+    async fn skip_to_separator(&mut self) -> Result<(), JsonParseError> {
+        while let Some(next_value) = self.data.peek_value() {
+            if next_value.value == RECORD_SEPARATOR {
+                return Ok(());
+            }
+
+            self.data.get_next().await?;
+        }
+
+        Ok(())
+    }
+}
@@ -1,6 +1,6 @@
 use rust_extensions::{date_time::DateTimeAsMicroseconds, StrOrString};
 
-use super::{JsonArrayIterator, JsonFirstLineIterator, JsonParseError};
+use super::{JsonArrayIterator, JsonFirstLineIterator, JsonNumber, JsonParseError};
 
 pub trait AsJsonSlice {
     fn as_slice(&self) -> &[u8];
@@ -45,10 +45,10 @@ impl JsonValue {
         match crate::json_utils::is_number(slice) {
             crate::json_utils::NumberType::NaN => {}
             crate::json_utils::NumberType::Number => {
-                return Ok(UnwrappedJsonValue::Number(convert_to_i64(slice)?));
+                return Ok(UnwrappedJsonValue::Number(convert_to_json_number(slice)?));
             }
             crate::json_utils::NumberType::Double => {
-                return Ok(UnwrappedJsonValue::Double(convert_to_f64(slice)?));
+                return Ok(UnwrappedJsonValue::Double(convert_to_json_number(slice)?));
             }
         }
 
@@ -109,20 +109,28 @@ impl JsonValue {
         crate::json_utils::is_null(slice)
     }
 
+    /// `None` for `null`; an error for a double or any other non-integer value.
     pub fn unwrap_as_number<'s>(
         &self,
         as_json_slice: &'s impl AsJsonSlice,
     ) -> Result<Option<i64>, JsonParseError> {
         let slice = as_json_slice.as_slice()[self.start..self.end].as_ref();
-        match crate::json_utils::is_number(slice) {
-            crate::json_utils::NumberType::NaN => {}
-            crate::json_utils::NumberType::Number => {
-                return Ok(Some(convert_to_i64(slice)?));
-            }
-            crate::json_utils::NumberType::Double => {}
+
+        if crate::json_utils::is_null(slice) {
+            return Ok(None);
         }
 
-        panic!("Json value is not a number")
+        match crate::json_utils::is_number(slice) {
+            crate::json_utils::NumberType::NaN => Err(JsonParseError::new(format!(
+                "Json value {} is not a number",
+                super::preview(slice)
+            ))),
+            crate::json_utils::NumberType::Number => Ok(Some(convert_to_i64(slice)?)),
+            crate::json_utils::NumberType::Double => Err(JsonParseError::new(format!(
+                "Json value {} is a double and can not be read as i64",
+                super::preview(slice)
+            ))),
+        }
     }
 
     /*
@@ -319,6 +327,52 @@ impl JsonValue {
         Some(result.into())
     }
 
+    /// [`Self::as_str`] appending into `dest` instead of returning a possibly allocated copy, so a
+    /// loop can reuse one buffer for every value. Returns `false`, leaving `dest` untouched, where
+    /// [`Self::as_str`] returns `None`.
+    pub fn unescape_into(&self, as_json_slice: &impl AsJsonSlice, dest: &mut String) -> bool {
+        let slice = as_json_slice.as_slice()[self.start..self.end].as_ref();
+
+        if crate::json_utils::is_null(slice) {
+            return false;
+        }
+
+        if let Some(body) = crate::json_utils::try_get_escaped_string_body(slice) {
+            crate::json_string_value::de_escape_json_string_value_into(body, dest);
+            return true;
+        }
+
+        match std::str::from_utf8(slice) {
+            Ok(value) => {
+                dest.push_str(value);
+                true
+            }
+            Err(_) => false,
+        }
+    }
+
+    /// [`Self::unescape_into`] appending UTF-8 bytes to a `Vec<u8>`.
+    pub fn unescape_into_vec(&self, as_json_slice: &impl AsJsonSlice, dest: &mut Vec<u8>) -> bool {
+        let slice = as_json_slice.as_slice()[self.start..self.end].as_ref();
+
+        if crate::json_utils::is_null(slice) {
+            return false;
+        }
+
+        if let Some(body) = crate::json_utils::try_get_escaped_string_body(slice) {
+            crate::json_string_value::de_escape_json_string_value_into_vec(body, dest);
+            return true;
+        }
+
+        match std::str::from_utf8(slice) {
+            Ok(value) => {
+                dest.extend_from_slice(value.as_bytes());
+                true
+            }
+            Err(_) => false,
+        }
+    }
+
     /// Returns the value with a single pair of matching surrounding quotes stripped, WITHOUT
     /// resolving JSON escape sequences (the result borrows from the input, so it can not allocate
     /// a de-escaped copy). For a value that needs escapes resolved use [`Self::as_str`].
@@ -368,8 +422,10 @@ impl JsonValue {
 pub enum UnwrappedJsonValue<'s> {
     Null,
     String(&'s str),
-    Number(i64),
-    Double(f64),
+    /// A number written as an integer - digits only.
+    Number(JsonNumber<'s>),
+    /// A number written with a fraction or an exponent.
+    Double(JsonNumber<'s>),
     Boolean(bool),
     Array(JsonArrayIterator<'s>),
     Object(JsonFirstLineIterator<'s>),
@@ -392,8 +448,8 @@ impl<'s> UnwrappedJsonValue<'s> {
 // Conversions from an unwrapped JSON value into the system numeric types.
 //
 // Semantics - STRICT, no silent data loss:
-//   * integer target  - only `Number(i64)` is accepted, range-checked via `TryFrom<i64>`; a
-//                        `Double` is rejected (a fractional JSON number can not become an integer
+//   * integer target  - only `Number` is accepted, range-checked against the target's own
+//                        width (so `u64::MAX` and the 128 bit types work); a `Double` is rejected (a fractional JSON number can not become an integer
 //                        without loss), and every non-numeric variant is rejected.
 //   * float target     - both `Number` and `Double` are accepted, but ONLY when the value is
 //                        representable exactly: the conversion must round-trip back to the original,
@@ -408,20 +464,9 @@ macro_rules! impl_try_from_unwrapped_for_integer {
 
                 fn try_from(value: UnwrappedJsonValue<'s>) -> Result<Self, Self::Error> {
                     match value {
-                        UnwrappedJsonValue::Number(number) => {
-                            <$t>::try_from(number).map_err(|_| {
-                                JsonParseError::new(format!(
-                                    "JSON number {} is out of range for type {}",
-                                    number,
-                                    stringify!($t)
-                                ))
-                            })
+                        UnwrappedJsonValue::Number(number) | UnwrappedJsonValue::Double(number) => {
+                            <$t>::try_from(number)
                         }
-                        UnwrappedJsonValue::Double(number) => Err(JsonParseError::new(format!(
-                            "JSON value is a floating point number ({}) and can not be converted to integer type {} without loss",
-                            number,
-                            stringify!($t)
-                        ))),
                         other => Err(JsonParseError::new(format!(
                             "JSON value of type '{}' can not be converted to integer type {}",
                             other.get_type_name(),
@@ -446,11 +491,18 @@ macro_rules! impl_try_from_unwrapped_for_float {
                 #[allow(clippy::float_cmp)] // exact round-trip comparison is intentional
                 fn try_from(value: UnwrappedJsonValue<'s>) -> Result<Self, Self::Error> {
                     match value {
-                        UnwrappedJsonValue::Number(number) => {
+                        UnwrappedJsonValue::Number(json_number) => {
+                            let number = json_number.as_i128().map_err(|_| {
+                                JsonParseError::new(format!(
+                                    "JSON number {} can not be represented exactly as {} (would lose precision)",
+                                    json_number.as_str(),
+                                    stringify!($t)
+                                ))
+                            })?;
                             let converted = number as $t;
-                            // i128 holds any i64 and any finite f32/f64-of-an-i64 without saturation,
-                            // so this comparison is exact (detects lost integer precision).
-                            if converted as i128 == number as i128 {
+                            // i128 holds the integer and any finite f32/f64 of one without
+                            // saturation, so this comparison is exact (detects lost precision).
+                            if converted as i128 == number {
                                 Ok(converted)
                             } else {
                                 Err(JsonParseError::new(format!(
@@ -461,6 +513,7 @@ macro_rules! impl_try_from_unwrapped_for_float {
                             }
                         }
                         UnwrappedJsonValue::Double(number) => {
+                            let number = number.as_f64()?;
                             let converted = number as $t;
                             // Widening back to f64 must reproduce the original bit-for-bit; catches
                             // both mantissa precision loss and saturation to +/-Infinity.
@@ -543,10 +596,10 @@ impl<'s> TryFrom<UnwrappedJsonValue<'s>> for DateTimeAsMicroseconds {
                 })
             }
             // Unit (sec/ms/us/ns) is auto-detected by magnitude inside `From<i64>`.
-            UnwrappedJsonValue::Number(number) => Ok(number.into()),
+            UnwrappedJsonValue::Number(number) => Ok(number.as_i64()?.into()),
             UnwrappedJsonValue::Double(number) => Err(JsonParseError::new(format!(
                 "JSON value is a floating point number ({}) and can not be converted to DateTimeAsMicroseconds",
-                number
+                number.as_str()
             ))),
             other => Err(JsonParseError::new(format!(
                 "JSON value of type '{}' can not be converted to DateTimeAsMicroseconds",
@@ -596,6 +649,13 @@ fn convert_to_i64(src: &[u8]) -> Result<i64, JsonParseError> {
     }
 }
 
+fn convert_to_json_number(src: &[u8]) -> Result<JsonNumber<'_>, JsonParseError> {
+    let src = convert_to_utf8(src)?;
+    JsonNumber::new(src).ok_or_else(|| {
+        JsonParseError::new(format!("Can convert value {} to a JSON number", src))
+    })
+}
+
 fn convert_to_f64(src: &[u8]) -> Result<f64, JsonParseError> {
     let src = convert_to_utf8(src)?;
     match src.parse() {
@@ -1,8 +1,13 @@
-use std::collections::HashMap;
+use std::borrow::Cow;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
+use std::hash::{BuildHasher, Hash};
+use std::rc::Rc;
+use std::str::FromStr;
+use std::sync::Arc;
 
 use rust_extensions::{date_time::DateTimeAsMicroseconds, StrOrString};
 
-use super::{JsonArrayIterator, JsonFirstLineIterator, JsonParseError, JsonValueRef};
+use super::{JsonArrayIterator, JsonFirstLineIterator, JsonParseError, JsonValue, JsonValueRef};
 
 /// The read half of the `JsonValueWriter` contract: whatever a `JsonValueWriter` puts on the
 /// wire, the matching `JsonValueReader` takes back off it, unchanged.
@@ -31,6 +36,14 @@ use super::{JsonArrayIterator, JsonFirstLineIterator, JsonParseError, JsonValueR
 /// A type mismatch is always an `Err`, never a silent coercion and never a panic. Only an
 /// explicit `null` (or an absent key, see [`Self::from_absent_json_value`]) maps to `None`, and
 /// only for `Option<T>`.
+///
+/// # Error paths
+///
+/// A reader that recurses - `Vec<T>`, `HashMap<String, V>`, a derived struct - marks an error
+/// from a nested value with where that value sits ([`JsonParseError::with_path_index`] /
+/// [`JsonParseError::with_path_key`]), so a failure deep in a payload reads
+/// `$.orders[17].legs[2].qty: JSON value of type 'string' can not be read as u64 ...`. The path
+/// is relative to the value the read started from.
 pub trait JsonValueReader<'s>: Sized {
     /// Reads the value. Returns `Err` if it does not hold this type.
     fn from_json_value(value: &JsonValueRef<'s>) -> Result<Self, JsonParseError>;
@@ -50,10 +63,42 @@ pub trait JsonValueReader<'s>: Sized {
     }
 }
 
+/// The owned counterpart of [`JsonValueReader`], for a value that has to outlive the buffer it
+/// was read from - e.g. an element `JsonArrayIteratorAsync::get_next` hands out as a fresh
+/// `Vec<u8>`, dropped as soon as the next one is read.
+///
+/// Implemented for every type that can be read from a document of **any** lifetime
+/// (`for<'s> JsonValueReader<'s>`), i.e. every type that does not borrow from the document.
+pub trait JsonValueReaderOwned: Sized {
+    /// Reads the value from `src`, which holds exactly one JSON value.
+    fn from_json_slice(src: &[u8]) -> Result<Self, JsonParseError>;
+}
+
+impl<T: for<'s> JsonValueReader<'s>> JsonValueReaderOwned for T {
+    fn from_json_slice(src: &[u8]) -> Result<Self, JsonParseError> {
+        read_json_value(src)
+    }
+}
+
+/// Reads a `T` from `src`, which holds exactly one JSON value; whitespace around it is ignored.
+pub fn read_json_value<'s, T: JsonValueReader<'s>>(src: &'s [u8]) -> Result<T, JsonParseError> {
+    let start = src
+        .iter()
+        .position(|b| !b.is_ascii_whitespace())
+        .unwrap_or(src.len());
+
+    let end = src
+        .iter()
+        .rposition(|b| !b.is_ascii_whitespace())
+        .map_or(start, |pos| pos + 1);
+
+    T::from_json_value(&JsonValueRef::new(JsonValue::new(start, end), src))
+}
+
 /// The value's raw source text, borrowed for the **full** `'s` and not for the (shorter) borrow
 /// of `value` - which is what lets the structural readers below recurse. See
 /// [`JsonValueRef::as_slice`].
-fn raw_slice<'s>(value: &JsonValueRef<'s>) -> &'s [u8] {
+pub(crate) fn raw_slice<'s>(value: &JsonValueRef<'s>) -> &'s [u8] {
     value.as_slice()
 }
 
@@ -100,7 +145,7 @@ fn json_type_name(raw: &[u8]) -> &'static str {
 
 /// A bounded, lossy rendering of a token for an error message - an error about a 10 MB array
 /// must not carry the array.
-fn preview(raw: &[u8]) -> String {
+pub(crate) fn preview(raw: &[u8]) -> String {
     const MAX: usize = 48;
 
     let as_str = String::from_utf8_lossy(raw);
@@ -122,6 +167,37 @@ fn type_mismatch(raw: &[u8], target: &str) -> JsonParseError {
     ))
 }
 
+/// The raw text of an object value, borrowed for the full `'s`, or the same type-mismatch error
+/// every built-in reader gives (`target` names what was being read). This is the entry point of
+/// a struct reader - `#[derive(JsonValueReader)]` starts every generated impl with it.
+pub fn json_object_slice<'s>(
+    value: &JsonValueRef<'s>,
+    target: &str,
+) -> Result<&'s [u8], JsonParseError> {
+    let inner = raw_slice(value);
+
+    if inner.is_empty() || !crate::json_utils::is_object(inner) {
+        return Err(type_mismatch(inner, target));
+    }
+
+    Ok(inner)
+}
+
+/// The array counterpart of [`json_object_slice`] - the entry point of a reader for a derived
+/// tuple variant.
+pub fn json_array_slice<'s>(
+    value: &JsonValueRef<'s>,
+    target: &str,
+) -> Result<&'s [u8], JsonParseError> {
+    let inner = raw_slice(value);
+
+    if inner.is_empty() || !crate::json_utils::is_array(inner) {
+        return Err(type_mismatch(inner, target));
+    }
+
+    Ok(inner)
+}
+
 // Integers: the digits are parsed straight into the target type, so the full width of every type
 // is available - `u64::MAX` and the 128 bit types included. Routing through `i64` (as
 // `try_get_number` does) would cap the readable range at `i64` for all of them.
@@ -335,47 +411,221 @@ impl<'s, T: JsonValueReader<'s>> JsonValueReader<'s> for Vec<T> {
 
         while let Some(item) = iterator.get_next() {
             let item = item?;
+            let index = result.len();
 
             // The iterator hands back a ref borrowed for its own (local) lifetime. `data` is a
             // pair of offsets into `inner` and carries no lifetime, so rebuilding the ref against
             // `inner` re-widens it to `'s` without copying anything.
             let item = JsonValueRef::new(item.data.clone(), inner);
 
-            result.push(T::from_json_value(&item)?);
+            result.push(T::from_json_value(&item).map_err(|err| err.with_path_index(index))?);
         }
 
         Ok(result)
     }
 }
 
-// Mirrors the `HashMap<String, V>` writer, which renders each entry as a `"key":value` pair.
-// Entry order is not part of the contract on either side.
-impl<'s, V: JsonValueReader<'s>> JsonValueReader<'s> for HashMap<String, V> {
+// The other sequences read through the `Vec<T>` reader, so they share its checks and its error
+// paths. A set collapses duplicate elements, the same as inserting them one by one would.
+impl<'s, T: JsonValueReader<'s>> JsonValueReader<'s> for VecDeque<T> {
     fn from_json_value(value: &JsonValueRef<'s>) -> Result<Self, JsonParseError> {
-        let inner: &'s [u8] = raw_slice(value);
+        let items: Vec<T> = JsonValueReader::from_json_value(value)?;
+        Ok(items.into())
+    }
+}
+
+impl<'s, T, S> JsonValueReader<'s> for HashSet<T, S>
+where
+    T: JsonValueReader<'s> + Eq + Hash,
+    S: BuildHasher + Default,
+{
+    fn from_json_value(value: &JsonValueRef<'s>) -> Result<Self, JsonParseError> {
+        let items: Vec<T> = JsonValueReader::from_json_value(value)?;
+        Ok(items.into_iter().collect())
+    }
+}
+
+impl<'s, T: JsonValueReader<'s> + Ord> JsonValueReader<'s> for BTreeSet<T> {
+    fn from_json_value(value: &JsonValueRef<'s>) -> Result<Self, JsonParseError> {
+        let items: Vec<T> = JsonValueReader::from_json_value(value)?;
+        Ok(items.into_iter().collect())
+    }
+}
+
+// A fixed array is an array of exactly `N` elements - a shorter or longer one is an error, never
+// padded or truncated.
+impl<'s, T: JsonValueReader<'s>, const N: usize> JsonValueReader<'s> for [T; N] {
+    fn from_json_value(value: &JsonValueRef<'s>) -> Result<Self, JsonParseError> {
+        let items: Vec<T> = JsonValueReader::from_json_value(value)?;
+        let len = items.len();
+
+        items.try_into().map_err(|_| {
+            JsonParseError::new(format!(
+                "JSON array of {} elements can not be read as {}",
+                len,
+                std::any::type_name::<Self>()
+            ))
+        })
+    }
+}
+
+// A tuple is a fixed-length array whose elements have their own types: `(String, u32)` is
+// `["a",1]`.
+macro_rules! impl_json_value_reader_for_tuple {
+    ($len:literal => $($no:tt $t:ident),+) => {
+        impl<'s, $($t: JsonValueReader<'s>),+> JsonValueReader<'s> for ($($t,)+) {
+            fn from_json_value(value: &JsonValueRef<'s>) -> Result<Self, JsonParseError> {
+                let inner = json_array_slice(value, std::any::type_name::<Self>())?;
+
+                let iterator = JsonArrayIterator::new(inner)?;
+
+                let wrong_length = || {
+                    JsonParseError::new(format!(
+                        "JSON array can not be read as {}. Expected {} elements",
+                        std::any::type_name::<Self>(),
+                        $len
+                    ))
+                };
+
+                let result = ($(
+                    {
+                        let item = match iterator.get_next() {
+                            Some(item) => item?,
+                            None => return Err(wrong_length()),
+                        };
+                        let item = JsonValueRef::new(item.data.clone(), inner);
+                        $t::from_json_value(&item).map_err(|err| err.with_path_index($no))?
+                    },
+                )+);
+
+                if iterator.get_next().is_some() {
+                    return Err(wrong_length());
+                }
 
-        if inner.is_empty() || !crate::json_utils::is_object(inner) {
-            return Err(type_mismatch(inner, "object"));
+                Ok(result)
+            }
         }
+    };
+}
 
-        let iterator = JsonFirstLineIterator::new(inner);
+impl_json_value_reader_for_tuple!(1 => 0 T0);
+impl_json_value_reader_for_tuple!(2 => 0 T0, 1 T1);
+impl_json_value_reader_for_tuple!(3 => 0 T0, 1 T1, 2 T2);
+impl_json_value_reader_for_tuple!(4 => 0 T0, 1 T1, 2 T2, 3 T3);
+impl_json_value_reader_for_tuple!(5 => 0 T0, 1 T1, 2 T2, 3 T3, 4 T4);
+impl_json_value_reader_for_tuple!(6 => 0 T0, 1 T1, 2 T2, 3 T3, 4 T4, 5 T5);
+impl_json_value_reader_for_tuple!(7 => 0 T0, 1 T1, 2 T2, 3 T3, 4 T4, 5 T5, 6 T6);
+impl_json_value_reader_for_tuple!(8 => 0 T0, 1 T1, 2 T2, 3 T3, 4 T4, 5 T5, 6 T6, 7 T7);
 
-        let mut result = HashMap::new();
+// Mirrors the map writers, which render each entry as a `"key":value` pair with the key written
+// through `Display`; the key is read back through `FromStr`, so `HashMap<u32, V>` is
+// `{"1":...}`. Entry order is not part of the contract on either side.
+fn read_map<'s, K: FromStr, V: JsonValueReader<'s>>(
+    value: &JsonValueRef<'s>,
+    mut insert: impl FnMut(K, V),
+) -> Result<(), JsonParseError> {
+    let inner = json_object_slice(value, "object")?;
 
-        while let Some(item) = iterator.get_next() {
-            let (key, item_value) = item?;
+    let iterator = JsonFirstLineIterator::new(inner);
 
-            let key = key.as_str()?.to_string();
+    while let Some(item) = iterator.get_next() {
+        let (key, item_value) = item?;
 
-            let item_value = JsonValueRef::new(item_value.data.clone(), inner);
+        let key = key.as_str()?;
 
-            result.insert(key, V::from_json_value(&item_value)?);
-        }
+        let parsed_key = K::from_str(key.as_str()).map_err(|_| {
+            JsonParseError::new(format!(
+                "JSON object key '{}' can not be read as {}",
+                key.as_str(),
+                std::any::type_name::<K>()
+            ))
+            .with_path_key(key.as_str())
+        })?;
+
+        let item_value = JsonValueRef::new(item_value.data.clone(), inner);
 
+        let item_value =
+            V::from_json_value(&item_value).map_err(|err| err.with_path_key(key.as_str()))?;
+
+        insert(parsed_key, item_value);
+    }
+
+    Ok(())
+}
+
+impl<'s, K, V, S> JsonValueReader<'s> for HashMap<K, V, S>
+where
+    K: FromStr + Eq + Hash,
+    V: JsonValueReader<'s>,
+    S: BuildHasher + Default,
+{
+    fn from_json_value(value: &JsonValueRef<'s>) -> Result<Self, JsonParseError> {
+        let mut result = HashMap::default();
+        read_map(value, |key, value| {
+            result.insert(key, value);
+        })?;
         Ok(result)
     }
 }
 
+impl<'s, K: FromStr + Ord, V: JsonValueReader<'s>> JsonValueReader<'s> for BTreeMap<K, V> {
+    fn from_json_value(value: &JsonValueRef<'s>) -> Result<Self, JsonParseError> {
+        let mut result = BTreeMap::new();
+        read_map(value, |key, value| {
+            result.insert(key, value);
+        })?;
+        Ok(result)
+    }
+}
+
+// Smart pointers are transparent on the wire: a `Box<T>` reads exactly what a `T` reads, an
+// absent key included.
+macro_rules! impl_json_value_reader_for_pointer {
+    ($($p:ident),* $(,)?) => {
+        $(
+            impl<'s, T: JsonValueReader<'s>> JsonValueReader<'s> for $p<T> {
+                fn from_json_value(value: &JsonValueRef<'s>) -> Result<Self, JsonParseError> {
+                    Ok($p::new(T::from_json_value(value)?))
+                }
+
+                fn from_absent_json_value(field_name: &str) -> Result<Self, JsonParseError> {
+                    Ok($p::new(T::from_absent_json_value(field_name)?))
+                }
+            }
+
+            impl<'s> JsonValueReader<'s> for $p<str> {
+                fn from_json_value(value: &JsonValueRef<'s>) -> Result<Self, JsonParseError> {
+                    let result: String = JsonValueReader::from_json_value(value)?;
+                    Ok(result.into())
+                }
+            }
+        )*
+    };
+}
+
+impl_json_value_reader_for_pointer!(Box, Rc, Arc);
+
+// Borrows from the document when the string is escape-free - the common case - and allocates only
+// for one carrying an escape, which has no de-escaped form inside the source bytes.
+impl<'s> JsonValueReader<'s> for Cow<'s, str> {
+    fn from_json_value(value: &JsonValueRef<'s>) -> Result<Self, JsonParseError> {
+        let raw = raw_slice(value);
+
+        if raw.len() >= 2
+            && raw[0] == crate::consts::DOUBLE_QUOTE
+            && raw[raw.len() - 1] == crate::consts::DOUBLE_QUOTE
+            && !raw.contains(&b'\\')
+        {
+            if let Ok(result) = std::str::from_utf8(&raw[1..raw.len() - 1]) {
+                return Ok(Cow::Borrowed(result));
+            }
+        }
+
+        let result: String = JsonValueReader::from_json_value(value)?;
+        Ok(Cow::Owned(result))
+    }
+}
+
 #[cfg(test)]
 mod symmetry_tests {
     use super::*;
@@ -949,6 +1199,127 @@ mod symmetry_tests {
         );
     }
 
+    #[test]
+    fn tuples_and_fixed_arrays_round_trip() {
+        let value = ("a".to_string(), 1u32);
+        let json = write_value(value.clone());
+        assert_eq!(json, r#"{"v":["a",1]}"#);
+        assert_eq!(read_value::<(String, u32)>(&json).unwrap(), value);
+
+        let value = [1.5f64, -2.0];
+        let json = write_value(value);
+        assert_eq!(json, r#"{"v":[1.5,-2]}"#);
+        assert_eq!(read_value::<[f64; 2]>(&json).unwrap(), value);
+
+        // elements that are themselves arrays keep their own brackets
+        let value = (vec![1u8, 2], [[3u8], [4u8]], (true,));
+        let json = write_value(value.clone());
+        assert_eq!(json, r#"{"v":[[1,2],[[3],[4]],[true]]}"#);
+        assert_eq!(
+            read_value::<(Vec<u8>, [[u8; 1]; 2], (bool,))>(&json).unwrap(),
+            value
+        );
+
+        // the length is part of the type
+        assert!(read_value::<(String, u32)>(r#"{"v":["a"]}"#).is_err());
+        assert!(read_value::<(String, u32)>(r#"{"v":["a",1,2]}"#).is_err());
+        assert!(read_value::<[u8; 2]>(r#"{"v":[1,2,3]}"#).is_err());
+
+        let err = read_value::<(String, u32)>(r#"{"v":["a","b"]}"#).unwrap_err();
+        assert_eq!(err.path().unwrap(), "$[1]");
+    }
+
+    #[test]
+    fn sets_and_deques_round_trip() {
+        let value: VecDeque<i32> = vec![3, 1, 2].into();
+        let json = write_value(value.clone());
+        assert_eq!(json, r#"{"v":[3,1,2]}"#);
+        assert_eq!(read_value::<VecDeque<i32>>(&json).unwrap(), value);
+
+        let value: BTreeSet<String> = ["b", "a"].iter().map(|s| s.to_string()).collect();
+        let json = write_value(value.clone());
+        assert_eq!(json, r#"{"v":["a","b"]}"#);
+        assert_eq!(read_value::<BTreeSet<String>>(&json).unwrap(), value);
+
+        let value: HashSet<u64> = [1, 2, u64::MAX].into_iter().collect();
+        let json = write_value(value.clone());
+        assert_eq!(read_value::<HashSet<u64>>(&json).unwrap(), value);
+
+        // duplicates collapse, as they would on insert
+        let value = read_value::<HashSet<u64>>(r#"{"v":[1,1,2]}"#).unwrap();
+        assert_eq!(value.len(), 2);
+    }
+
+    #[test]
+    fn maps_with_non_string_keys_round_trip() {
+        let mut value: BTreeMap<u32, Vec<String>> = BTreeMap::new();
+        value.insert(10, vec!["x".to_string()]);
+        value.insert(2, vec![]);
+
+        let json = write_value(value.clone());
+        assert_eq!(json, r#"{"v":{"2":[],"10":["x"]}}"#);
+        assert_eq!(
+            read_value::<BTreeMap<u32, Vec<String>>>(&json).unwrap(),
+            value
+        );
+
+        let mut value: HashMap<i64, bool> = HashMap::new();
+        value.insert(-1, true);
+        value.insert(i64::MAX, false);
+
+        let json = write_value(value.clone());
+        assert_eq!(read_value::<HashMap<i64, bool>>(&json).unwrap(), value);
+
+        let mut value: BTreeMap<String, u8> = BTreeMap::new();
+        value.insert(r#"he said "hi""#.to_string(), 1);
+
+        let json = write_value(value.clone());
+        assert_eq!(read_value::<BTreeMap<String, u8>>(&json).unwrap(), value);
+
+        // a key that does not parse is an error at that key
+        let err = read_value::<BTreeMap<u32, u8>>(r#"{"v":{"1":1,"x":2}}"#).unwrap_err();
+        assert_eq!(err.path().unwrap(), "$.x");
+        assert!(err.to_string().contains("can not be read as u32"));
+    }
+
+    #[test]
+    fn smart_pointers_and_shared_strings_round_trip() {
+        let value = Box::new(vec![1u8, 2]);
+        let json = write_value(value.clone());
+        assert_eq!(json, r#"{"v":[1,2]}"#);
+        assert_eq!(read_value::<Box<Vec<u8>>>(&json).unwrap(), value);
+
+        let value = Rc::new(7u16);
+        let json = write_value(value.clone());
+        assert_eq!(read_value::<Rc<u16>>(&json).unwrap(), value);
+
+        // an absent key reads like the pointee does
+        assert_eq!(
+            Arc::<Option<u8>>::from_absent_json_value("v").unwrap(),
+            Arc::new(None)
+        );
+
+        let value: Arc<str> = "a \"quoted\" text".into();
+        let json = write_value(value.clone());
+        assert_eq!(read_value::<Arc<str>>(&json).unwrap(), value);
+        assert_eq!(&*read_value::<Box<str>>(&json).unwrap(), &*value);
+        assert_eq!(&*read_value::<Rc<str>>(&json).unwrap(), &*value);
+
+        // `Cow<str>` borrows unless the string carries an escape
+        let json = write_value(Cow::Borrowed("plain"));
+        assert!(matches!(
+            read_value::<Cow<str>>(&json).unwrap(),
+            Cow::Borrowed("plain")
+        ));
+
+        let json = write_value(Cow::Borrowed("a\nb"));
+        let value = read_value::<Cow<str>>(&json).unwrap();
+        assert!(matches!(value, Cow::Owned(_)));
+        assert_eq!(value, "a\nb");
+
+        assert!(read_value::<Cow<str>>(r#"{"v":1}"#).is_err());
+    }
+
     // ----- errors -----------------------------------------------------------------------------
 
     /// Every mismatch is a `JsonParseError` naming both sides. None of these panic - the whole
@@ -1079,4 +1450,39 @@ mod symmetry_tests {
         assert_eq!(parse_generic::<f64>(&value).unwrap(), 7f64);
         assert_eq!(parse_generic::<Option<u8>>(&value).unwrap(), Some(7u8));
     }
+
+    #[test]
+    fn nested_errors_carry_the_path() {
+        let json = r#"{"v":{"orders":[{"qty":[1]},{"qty":[2,"3"]}]}}"#;
+
+        let err = read_value::<HashMap<String, Vec<HashMap<String, Vec<u64>>>>>(json).unwrap_err();
+
+        assert_eq!(err.path().unwrap(), "$.orders[1].qty[1]");
+        assert!(err
+            .to_string()
+            .starts_with("$.orders[1].qty[1]: JSON value of type 'string' can not be read as u64"));
+
+        // a failure in the value itself carries no path
+        let err = read_value::<Vec<u64>>(r#"{"v":"x"}"#).unwrap_err();
+        assert!(err.path().is_none());
+    }
+
+    #[test]
+    fn owned_values_read_from_a_standalone_slice() {
+        fn read_owned<T: JsonValueReaderOwned>(src: Vec<u8>) -> T {
+            // `src` is dropped when this returns; the value must not borrow from it
+            T::from_json_slice(&src).unwrap()
+        }
+
+        let map: HashMap<String, Vec<u64>> = read_owned(b" {\"a\":[1,2]}\n".to_vec());
+        assert_eq!(map["a"], vec![1, 2]);
+
+        let value: Option<String> = read_owned(b"null".to_vec());
+        assert_eq!(value, None);
+
+        let value: String = read_json_value(b"\"x\"").unwrap();
+        assert_eq!(value, "x");
+
+        assert!(u64::from_json_slice(b"1 2").is_err());
+    }
 }
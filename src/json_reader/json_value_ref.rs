@@ -1,8 +1,10 @@
 use rust_extensions::{date_time::DateTimeAsMicroseconds, StrOrString};
 
+use crate::json_date_time_format::{JsonDateTime, JsonDateTimeFormat};
+
 use super::{
-    json_value::UnwrappedJsonValue, JsonArrayIterator, JsonFirstLineIterator, JsonParseError,
-    JsonValue,
+    json_value::UnwrappedJsonValue, JsonArrayItems, JsonArrayIterator, JsonFirstLineIterator,
+    JsonNumber, JsonObjectEntries, JsonParseError, JsonValue, JsonValueReader,
 };
 
 #[derive(Clone, Debug)]
@@ -62,6 +64,19 @@ impl<'s> JsonValueRef<'s> {
         self.data.unwrap_as_object(&self.json_slice)
     }
 
+    /// The object's entries as a standard [`Iterator`]; see [`JsonObjectEntries`].
+    pub fn object_entries(&self) -> Result<JsonObjectEntries<'s>, JsonParseError> {
+        let slice = &self.json_slice[self.data.start..self.data.end];
+
+        if crate::json_utils::is_object(slice) {
+            return Ok(JsonObjectEntries::new(slice));
+        }
+
+        Err(JsonParseError::new(
+            "Json value is not an object".to_string(),
+        ))
+    }
+
     pub fn unwrap_as_bool(&'s self) -> Option<bool> {
         self.data.unwrap_as_bool(&self.json_slice)
     }
@@ -98,10 +113,33 @@ impl<'s> JsonValueRef<'s> {
         self.data.unwrap_as_array(&self.json_slice)
     }
 
+    /// The array's elements as a standard [`Iterator`]; see [`JsonArrayItems`].
+    pub fn array_items(&self) -> Result<JsonArrayItems<'s>, JsonParseError> {
+        let slice = &self.json_slice[self.data.start..self.data.end];
+
+        if crate::json_utils::is_array(slice) {
+            return Ok(JsonArrayItems::new(slice));
+        }
+
+        Err(JsonParseError::new(
+            "Json value is not an array".to_string(),
+        ))
+    }
+
     pub fn as_str(&'s self) -> Option<StrOrString<'s>> {
         self.data.as_str(&self.json_slice)
     }
 
+    /// See [`JsonValue::unescape_into`].
+    pub fn unescape_into(&self, dest: &mut String) -> bool {
+        self.data.unescape_into(&self.json_slice, dest)
+    }
+
+    /// See [`JsonValue::unescape_into_vec`].
+    pub fn unescape_into_vec(&self, dest: &mut Vec<u8>) -> bool {
+        self.data.unescape_into_vec(&self.json_slice, dest)
+    }
+
     pub fn as_unescaped_str(&'s self) -> Option<&'s str> {
         self.data.as_unescaped_str(&self.json_slice)
     }
@@ -134,6 +172,20 @@ impl<'s> JsonValueRef<'s> {
         self.unwrap_value()?.try_into()
     }
 
+    /// The value as a [`JsonNumber`] - its digits as written, converted on demand with no
+    /// precision lost on the way in. `Err` for anything but a number.
+    pub fn try_get_json_number(&self) -> Result<JsonNumber<'s>, JsonParseError> {
+        JsonNumber::from_json_value(self)
+    }
+
+    /// [`Self::try_get_date_time`] pinned to the format `F` instead of guessing - a number is
+    /// read in `F`'s unit whatever its magnitude, and anything not in `F` is an `Err`.
+    pub fn try_get_date_time_as<F: JsonDateTimeFormat>(
+        &self,
+    ) -> Result<DateTimeAsMicroseconds, JsonParseError> {
+        JsonDateTime::<F>::from_json_value(self).map(JsonDateTime::into_inner)
+    }
+
     /// `Option`-returning variant of [`Self::try_get_date_time`]: a JSON `null` yields
     /// `Ok(None)`, a parseable value yields `Ok(Some(_))`, otherwise an `Err`.
     pub fn try_get_date_time_opt(
@@ -0,0 +1,311 @@
+use std::ops::{Deref, DerefMut};
+
+use rust_extensions::StrOrString;
+
+use super::json_value_reader::{preview, raw_slice};
+use super::{
+    JsonArrayIterator, JsonParseError, JsonValueReader, JsonValueReaderOwned, JsonValueRef,
+};
+
+/// The opt-in coercing counterpart of [`JsonValueReader`], for upstreams that do not keep to
+/// their own types.
+///
+/// On top of everything the strict reader accepts:
+///
+/// * numbers sent as strings - `"12"`, `"1.5"` - read as the number;
+/// * booleans sent as `1` / `0`, or as the strings `"true"` / `"false"` / `"1"` / `"0"`;
+/// * numbers and booleans sent where a string is expected read as their text - `12` as `"12"`;
+/// * an empty string reads as `None` for an `Option<T>`, the same as `null`.
+///
+/// The strict reader is untouched: coercion only happens where a field asks for it, through
+/// [`Lenient<T>`] or `#[json(lenient)]` on a derived field. Writing never coerces - a lenient
+/// field is written in its canonical form, which both readers take back.
+pub trait LenientJsonValueReader<'s>: JsonValueReader<'s> {
+    fn from_json_value_lenient(value: &JsonValueRef<'s>) -> Result<Self, JsonParseError>;
+}
+
+/// A `T` read through [`LenientJsonValueReader`] and written as a plain `T`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Lenient<T>(pub T);
+
+impl<T> Lenient<T> {
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> Deref for Lenient<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for Lenient<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<T> From<T> for Lenient<T> {
+    fn from(value: T) -> Self {
+        Self(value)
+    }
+}
+
+impl<'s, T: LenientJsonValueReader<'s>> JsonValueReader<'s> for Lenient<T> {
+    fn from_json_value(value: &JsonValueRef<'s>) -> Result<Self, JsonParseError> {
+        Ok(Self(T::from_json_value_lenient(value)?))
+    }
+
+    fn from_absent_json_value(field_name: &str) -> Result<Self, JsonParseError> {
+        Ok(Self(T::from_absent_json_value(field_name)?))
+    }
+}
+
+// A number in a string is read by handing the string's content to the strict reader, so a
+// stringified number obeys exactly the rules of an unquoted one - range checks included.
+fn read_stringified<'s, T>(value: &JsonValueRef<'s>) -> Result<T, JsonParseError>
+where
+    T: JsonValueReader<'s> + JsonValueReaderOwned,
+{
+    let raw = raw_slice(value);
+
+    if raw.is_empty() || !crate::json_utils::is_string(raw) {
+        return T::from_json_value(value);
+    }
+
+    let text: StrOrString<'s> = JsonValueReader::from_json_value(value)?;
+
+    T::from_json_slice(text.as_str().as_bytes()).map_err(|_| {
+        JsonParseError::new(format!(
+            "JSON string {} can not be read as {}",
+            preview(raw),
+            std::any::type_name::<T>()
+        ))
+    })
+}
+
+macro_rules! impl_lenient_json_value_reader_for_number {
+    ($($t:ty),* $(,)?) => {
+        $(
+            impl<'s> LenientJsonValueReader<'s> for $t {
+                fn from_json_value_lenient(
+                    value: &JsonValueRef<'s>,
+                ) -> Result<Self, JsonParseError> {
+                    read_stringified(value)
+                }
+            }
+        )*
+    };
+}
+
+impl_lenient_json_value_reader_for_number!(
+    u8, i8, u16, i16, u32, i32, u64, i64, u128, i128, usize, isize, f32, f64
+);
+
+#[cfg(feature = "decimal")]
+impl_lenient_json_value_reader_for_number!(rust_decimal::Decimal);
+
+impl<'s> LenientJsonValueReader<'s> for bool {
+    fn from_json_value_lenient(value: &JsonValueRef<'s>) -> Result<Self, JsonParseError> {
+        let raw = raw_slice(value);
+
+        let text = if !raw.is_empty() && crate::json_utils::is_string(raw) {
+            let text: StrOrString<'s> = JsonValueReader::from_json_value(value)?;
+            Some(text)
+        } else {
+            None
+        };
+
+        let token = match &text {
+            Some(text) => text.as_str().as_bytes(),
+            None => raw,
+        };
+
+        if token == b"1" || token.eq_ignore_ascii_case(b"true") {
+            return Ok(true);
+        }
+
+        if token == b"0" || token.eq_ignore_ascii_case(b"false") {
+            return Ok(false);
+        }
+
+        match text {
+            Some(_) => Err(JsonParseError::new(format!(
+                "JSON string {} can not be read as bool",
+                preview(raw)
+            ))),
+            None => bool::from_json_value(value),
+        }
+    }
+}
+
+impl<'s> LenientJsonValueReader<'s> for StrOrString<'s> {
+    fn from_json_value_lenient(value: &JsonValueRef<'s>) -> Result<Self, JsonParseError> {
+        let raw = raw_slice(value);
+
+        let is_number = !matches!(
+            crate::json_utils::is_number(raw),
+            crate::json_utils::NumberType::NaN
+        );
+
+        if !raw.is_empty() && (is_number || crate::json_utils::as_bool_value(raw).is_some()) {
+            if let Ok(text) = std::str::from_utf8(raw) {
+                return Ok(text.into());
+            }
+        }
+
+        StrOrString::from_json_value(value)
+    }
+}
+
+impl<'s> LenientJsonValueReader<'s> for String {
+    fn from_json_value_lenient(value: &JsonValueRef<'s>) -> Result<Self, JsonParseError> {
+        let result = StrOrString::from_json_value_lenient(value)?;
+        Ok(result.to_string())
+    }
+}
+
+// `null`, an empty string and an absent key are all `None`.
+impl<'s, T: LenientJsonValueReader<'s>> LenientJsonValueReader<'s> for Option<T> {
+    fn from_json_value_lenient(value: &JsonValueRef<'s>) -> Result<Self, JsonParseError> {
+        let raw = raw_slice(value);
+
+        if raw.is_empty() || crate::json_utils::is_null(raw) || raw == b"\"\"" {
+            return Ok(None);
+        }
+
+        Ok(Some(T::from_json_value_lenient(value)?))
+    }
+}
+
+impl<'s, T: LenientJsonValueReader<'s>> LenientJsonValueReader<'s> for Vec<T> {
+    fn from_json_value_lenient(value: &JsonValueRef<'s>) -> Result<Self, JsonParseError> {
+        let inner = super::json_array_slice(value, "array")?;
+
+        let iterator = JsonArrayIterator::new(inner)?;
+
+        let mut result = Vec::new();
+
+        while let Some(item) = iterator.get_next() {
+            let item = item?;
+            let index = result.len();
+
+            let item = JsonValueRef::new(item.data.clone(), inner);
+
+            result
+                .push(T::from_json_value_lenient(&item).map_err(|err| err.with_path_index(index))?);
+        }
+
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::json_reader::read_json_value;
+
+    fn read<'s, T: JsonValueReader<'s>>(json: &'s str) -> Result<T, JsonParseError> {
+        read_json_value(json.as_bytes())
+    }
+
+    #[test]
+    fn numbers_read_from_strings() {
+        assert_eq!(read::<Lenient<u32>>(r#""12""#).unwrap().0, 12);
+        assert_eq!(read::<Lenient<u32>>("12").unwrap().0, 12);
+        assert_eq!(read::<Lenient<f64>>(r#""1.5""#).unwrap().0, 1.5);
+        assert_eq!(read::<Lenient<i64>>(r#"" -3 ""#).unwrap().0, -3);
+
+        // the strict rules still apply to the digits
+        assert!(read::<Lenient<u8>>(r#""256""#).is_err());
+        assert!(read::<Lenient<u32>>(r#""12a""#).is_err());
+        assert!(read::<Lenient<u32>>(r#""""#).is_err());
+        assert!(read::<Lenient<u32>>(r#""\"12\"""#).is_err());
+        assert!(read::<Lenient<u32>>("true").is_err());
+
+        // and the strict reader is unchanged
+        assert!(read::<u32>(r#""12""#).is_err());
+    }
+
+    #[test]
+    fn booleans_read_from_numbers_and_strings() {
+        for (json, expected) in [
+            ("true", true),
+            ("false", false),
+            ("1", true),
+            ("0", false),
+            (r#""true""#, true),
+            (r#""FALSE""#, false),
+            (r#""1""#, true),
+            (r#""0""#, false),
+        ] {
+            assert_eq!(read::<Lenient<bool>>(json).unwrap().0, expected, "{}", json);
+        }
+
+        assert!(read::<Lenient<bool>>("2").is_err());
+        assert!(read::<Lenient<bool>>(r#""yes""#).is_err());
+        assert!(read::<bool>("1").is_err());
+    }
+
+    #[test]
+    fn empty_string_is_none() {
+        assert_eq!(read::<Lenient<Option<u32>>>(r#""""#).unwrap().0, None);
+        assert_eq!(read::<Lenient<Option<u32>>>("null").unwrap().0, None);
+        assert_eq!(read::<Lenient<Option<u32>>>(r#""7""#).unwrap().0, Some(7));
+        assert_eq!(
+            Lenient::<Option<u32>>::from_absent_json_value("qty")
+                .unwrap()
+                .0,
+            None
+        );
+
+        assert!(read::<Option<u32>>(r#""""#).is_err());
+    }
+
+    #[test]
+    fn strings_read_from_numbers_and_booleans() {
+        for (json, expected) in [
+            (r#""abc""#, "abc"),
+            (r#""a\"b""#, "a\"b"),
+            ("12", "12"),
+            ("-1.5e3", "-1.5e3"),
+            ("true", "true"),
+        ] {
+            assert_eq!(
+                read::<Lenient<String>>(json).unwrap().0,
+                expected,
+                "{}",
+                json
+            );
+            assert_eq!(
+                read::<Lenient<StrOrString>>(json).unwrap().as_str(),
+                expected,
+                "{}",
+                json
+            );
+        }
+
+        assert!(read::<Lenient<String>>("null").is_err());
+        assert!(read::<Lenient<String>>("[1]").is_err());
+        assert!(read::<String>("12").is_err());
+
+        assert_eq!(read::<Lenient<Option<String>>>(r#""""#).unwrap().0, None);
+        assert_eq!(read::<Lenient<Option<String>>>("null").unwrap().0, None);
+        assert_eq!(
+            read::<Lenient<Option<String>>>(r#""x""#).unwrap().0,
+            Some("x".to_string())
+        );
+    }
+
+    #[test]
+    fn vec_elements_are_lenient_and_carry_their_index() {
+        let value = read::<Lenient<Vec<u64>>>(r#"["1",2,"3"]"#).unwrap();
+        assert_eq!(value.into_inner(), vec![1, 2, 3]);
+
+        let err = read::<Lenient<Vec<u64>>>(r#"["1","x"]"#).unwrap_err();
+        assert_eq!(err.path().unwrap(), "$[1]");
+    }
+}
@@ -0,0 +1,102 @@
+use std::cell::Cell;
+use std::path::Path;
+
+use rust_extensions::array_of_bytes_iterator::{ArrayOfBytesIterator, NextValue};
+
+/// An [`ArrayOfBytesIterator`] over a memory-mapped file.
+///
+/// The file is read as a single slice, but its pages are loaded when they are touched and can be
+/// evicted again, so a file larger than RAM reads like any other payload - every reader works on
+/// it, `JsonParallelReader` included via [`Self::as_slice`].
+pub struct MmapIterator {
+    mmap: memmap2::Mmap,
+    pos: Cell<usize>,
+}
+
+impl MmapIterator {
+    pub fn new(mmap: memmap2::Mmap) -> Self {
+        Self {
+            mmap,
+            pos: Cell::new(0),
+        }
+    }
+
+    /// Maps the file at `path`.
+    ///
+    /// # Safety
+    ///
+    /// The file must not be modified or truncated while it is mapped - see
+    /// [`memmap2::Mmap::map`].
+    pub unsafe fn open(path: impl AsRef<Path>) -> std::io::Result<Self> {
+        let file = std::fs::File::open(path)?;
+        let mmap = memmap2::Mmap::map(&file)?;
+        Ok(Self::new(mmap))
+    }
+
+    pub fn as_slice(&self) -> &[u8] {
+        &self.mmap
+    }
+}
+
+impl ArrayOfBytesIterator for MmapIterator {
+    fn peek_value(&self) -> Option<NextValue> {
+        let pos = self.pos.get();
+        let value = *self.mmap.get(pos)?;
+        Some(NextValue { pos, value })
+    }
+
+    fn get_next(&self) -> Option<NextValue> {
+        let result = self.peek_value()?;
+        self.pos.set(result.pos + 1);
+        Some(result)
+    }
+
+    fn get_src_slice(&self) -> &[u8] {
+        &self.mmap
+    }
+
+    fn get_pos(&self) -> usize {
+        self.pos.get()
+    }
+
+    fn get_slice_to_current_pos(&self, from_pos: usize) -> &[u8] {
+        &self.mmap[from_pos..self.pos.get()]
+    }
+
+    fn get_slice_to_end(&self, from_pos: usize) -> &[u8] {
+        &self.mmap[from_pos..]
+    }
+
+    fn advance(&self, amount: usize) -> Option<&[u8]> {
+        let pos = self.pos.get();
+        let result = self.mmap.get(pos..pos.checked_add(amount)?)?;
+        self.pos.set(pos + amount);
+        Some(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MmapIterator;
+    use crate::json_reader::JsonLIterator;
+
+    #[test]
+    fn json_l_reads_from_a_mapped_file() {
+        let path = std::env::temp_dir().join(format!("my-json-mmap-{}.jsonl", std::process::id()));
+        std::fs::write(&path, "{\"a\":1}\n[2]\n3\n").unwrap();
+
+        let mut iterator = JsonLIterator::new(unsafe { MmapIterator::open(&path) }.unwrap());
+
+        let mut records = Vec::new();
+        while let Some(record) = iterator.get_next() {
+            records.push(record.unwrap().to_vec());
+        }
+
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(
+            records,
+            vec![b"{\"a\":1}".to_vec(), b"[2]".to_vec(), b"3".to_vec()]
+        );
+    }
+}
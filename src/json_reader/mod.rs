@@ -3,23 +3,44 @@ pub mod array_iterator_inner;
 mod json_first_line_reader;
 mod json_parse_error;
 
-pub use json_parse_error::JsonParseError;
+pub use json_parse_error::{JsonParseError, JsonPathSegment};
 
 pub use json_first_line_reader::*;
 pub(crate) mod json_value;
 pub use json_value::{AsJsonSlice, JsonValue, UnwrappedJsonValue};
 mod array_parser_async;
 pub use array_parser_async::*;
+mod json_array_at_path;
+pub use json_array_at_path::JsonArrayAtPathIterator;
+mod json_array_at_path_async;
+pub use json_array_at_path_async::JsonArrayAtPathIteratorAsync;
 
+mod json_l_record;
+pub use json_l_record::{JsonLCheckpoint, JsonLRecord, JsonLRecordError};
 mod json_l_iterator;
 pub use json_l_iterator::*;
 mod json_l_iterator_async;
 pub use json_l_iterator_async::*;
+mod json_seq_iterator;
+pub use json_seq_iterator::JsonSeqIterator;
+mod json_seq_iterator_async;
+pub use json_seq_iterator_async::JsonSeqIteratorAsync;
+mod json_concat_iterator;
+pub use json_concat_iterator::JsonConcatIterator;
+mod json_concat_iterator_async;
+pub use json_concat_iterator_async::JsonConcatIteratorAsync;
 pub mod bytes_of_array_reader;
 mod json_value_ref;
 pub use json_value_ref::*;
 mod json_value_reader;
-pub use json_value_reader::JsonValueReader;
+pub use json_value_reader::{
+    json_array_slice, json_object_slice, read_json_value, JsonValueReader, JsonValueReaderOwned,
+};
+pub(crate) use json_value_reader::{preview, raw_slice};
+mod json_number;
+pub use json_number::JsonNumber;
+mod lenient_json_value_reader;
+pub use lenient_json_value_reader::{Lenient, LenientJsonValueReader};
 
 mod json_field_name;
 pub use json_field_name::*;
@@ -31,3 +52,26 @@ mod json_key_value;
 pub use json_key_value::*;
 mod array_iterator;
 pub use array_iterator::*;
+mod json_array_items;
+pub use json_array_items::JsonArrayItems;
+mod json_object_entries;
+pub use json_object_entries::JsonObjectEntries;
+mod json_parallel_reader;
+pub use json_parallel_reader::JsonParallelReader;
+mod read_iterator;
+pub use read_iterator::ReadIterator;
+#[cfg(feature = "tokio")]
+mod async_read_iterator;
+#[cfg(feature = "tokio")]
+pub use async_read_iterator::AsyncReadIterator;
+#[cfg(feature = "tokio")]
+mod json_async_stream;
+#[cfg(feature = "tokio")]
+pub use json_async_stream::JsonAsyncStream;
+#[cfg(feature = "mmap")]
+mod mmap_iterator;
+#[cfg(feature = "mmap")]
+pub use mmap_iterator::MmapIterator;
+
+#[cfg(feature = "derive")]
+pub use my_json_derive::JsonValueReader;
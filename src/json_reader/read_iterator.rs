@@ -0,0 +1,375 @@
+use std::cell::{Cell, RefCell, UnsafeCell};
+use std::io::Read;
+use std::mem::MaybeUninit;
+
+use rust_extensions::array_of_bytes_iterator::{ArrayOfBytesIterator, NextValue};
+
+const DEFAULT_CHUNK_SIZE: usize = 64 * 1024;
+
+/// An [`ArrayOfBytesIterator`] over any [`std::io::Read`] - a file, a socket, a decompressor -
+/// that reads the source as it goes instead of loading it up front.
+///
+/// The bytes read so far are kept in a window, and every position - `get_pos`, the ones in
+/// [`NextValue`] and in the values the readers return - is a position within it. The window only
+/// shrinks on `release_consumed`, which drops the bytes before the current position; the readers
+/// that own one have their own `release_consumed` to call between records. Called after every
+/// record, memory use is bounded by the largest record, not the payload.
+///
+/// `get_slice_to_end` and `get_src_slice` end where reading has got to. A failed read ends the
+/// source as if the payload ended there, so the readers report it as truncated JSON;
+/// [`Self::take_error`] has the error itself.
+pub struct ReadIterator<TRead: Read> {
+    src: RefCell<TRead>,
+    // Slices of the window are handed out for as long as `&self` is borrowed, so under a shared
+    // borrow its bytes are never moved, changed or freed: it only grows into spare capacity, and
+    // when it has to move to a bigger allocation the old one is kept in `retired`. Both are
+    // tidied up in `release_to`, which takes `&mut self`.
+    window: UnsafeCell<Vec<u8>>,
+    retired: RefCell<Vec<Vec<u8>>>,
+    pos: Cell<usize>,
+    window_start: usize,
+    chunk_size: usize,
+    eof: Cell<bool>,
+    error: RefCell<Option<std::io::Error>>,
+}
+
+impl<TRead: Read> ReadIterator<TRead> {
+    pub fn new(src: TRead) -> Self {
+        Self {
+            src: RefCell::new(src),
+            window: UnsafeCell::new(Vec::new()),
+            retired: RefCell::new(Vec::new()),
+            pos: Cell::new(0),
+            window_start: 0,
+            chunk_size: DEFAULT_CHUNK_SIZE,
+            eof: Cell::new(false),
+            error: RefCell::new(None),
+        }
+    }
+
+    /// A source already seeked to `offset` in the payload - to carry on a read from a checkpoint
+    /// with [`JsonLIterator::resume_reader`](super::JsonLIterator::resume_reader) without reading
+    /// what comes before it.
+    pub fn starting_at(src: TRead, offset: usize) -> Self {
+        let mut result = Self::new(src);
+        result.window_start = offset;
+        result
+    }
+
+    /// How much is read from the source at a time. Defaults to 64 KiB.
+    pub fn with_chunk_size(mut self, chunk_size: usize) -> Self {
+        self.chunk_size = chunk_size.max(1);
+        self
+    }
+
+    /// The offset in the payload of the window's first byte: a position plus this is an offset
+    /// in the payload.
+    pub fn get_window_start(&self) -> usize {
+        self.window_start
+    }
+
+    /// The error that ended the source early, if one did.
+    pub fn take_error(&mut self) -> Option<std::io::Error> {
+        self.error.get_mut().take()
+    }
+
+    /// Drops the bytes before the current position. Positions taken before this are no longer
+    /// valid.
+    pub fn release_consumed(&mut self) {
+        let pos = self.pos.get();
+        self.release_to(pos);
+    }
+
+    /// Drops the bytes before `pos`, which must not be past the current position. Positions
+    /// taken before this are no longer valid; `pos` becomes 0.
+    pub fn release_to(&mut self, pos: usize) {
+        let pos = pos.min(self.pos.get());
+
+        self.window.get_mut().drain(..pos);
+        self.retired.get_mut().clear();
+        self.pos.set(self.pos.get() - pos);
+        self.window_start += pos;
+    }
+
+    fn window(&self) -> &[u8] {
+        // SAFETY: nothing changes the bytes already in the window while `&self` is borrowed -
+        // see `fill`.
+        unsafe { (*self.window.get()).as_slice() }
+    }
+
+    // `false` once the source has run out before `pos`.
+    fn load_to(&self, pos: usize) -> bool {
+        while self.window().len() <= pos {
+            if self.eof.get() || !self.fill() {
+                return false;
+            }
+        }
+
+        true
+    }
+
+    fn fill(&self) -> bool {
+        // SAFETY: the bytes already in the window may be borrowed, so they are neither written
+        // nor moved. The `Vec` itself is only ever borrowed here and in `release_to`.
+        let window = unsafe { &mut *self.window.get() };
+
+        if window.capacity() - window.len() < self.chunk_size {
+            let mut grown =
+                Vec::with_capacity((window.len() * 2).max(window.len() + self.chunk_size));
+            grown.extend_from_slice(window);
+
+            let old = std::mem::replace(window, grown);
+            self.retired.borrow_mut().push(old);
+        }
+
+        let len = window.len();
+
+        // Only the spare capacity is written: the bytes the window holds stay untouched.
+        let spare = &mut window.spare_capacity_mut()[..self.chunk_size];
+        spare.fill(MaybeUninit::new(0));
+
+        // SAFETY: every byte of `spare` has just been initialised.
+        let spare = unsafe { &mut *(spare as *mut [MaybeUninit<u8>] as *mut [u8]) };
+
+        loop {
+            match self.src.borrow_mut().read(spare) {
+                Ok(0) => {
+                    self.eof.set(true);
+                    return false;
+                }
+                Ok(read) => {
+                    // SAFETY: `read` bytes after `len` are initialised.
+                    unsafe { window.set_len(len + read) };
+                    return true;
+                }
+                Err(err) if err.kind() == std::io::ErrorKind::Interrupted => {}
+                Err(err) => {
+                    self.eof.set(true);
+                    *self.error.borrow_mut() = Some(err);
+                    return false;
+                }
+            }
+        }
+    }
+}
+
+impl<TRead: Read> ArrayOfBytesIterator for ReadIterator<TRead> {
+    fn peek_value(&self) -> Option<NextValue> {
+        let pos = self.pos.get();
+
+        if !self.load_to(pos) {
+            return None;
+        }
+
+        Some(NextValue {
+            pos,
+            value: self.window()[pos],
+        })
+    }
+
+    fn get_next(&self) -> Option<NextValue> {
+        let result = self.peek_value()?;
+        self.pos.set(result.pos + 1);
+        Some(result)
+    }
+
+    fn get_src_slice(&self) -> &[u8] {
+        self.window()
+    }
+
+    fn get_pos(&self) -> usize {
+        self.pos.get()
+    }
+
+    fn get_slice_to_current_pos(&self, from_pos: usize) -> &[u8] {
+        &self.window()[from_pos..self.pos.get()]
+    }
+
+    fn get_slice_to_end(&self, from_pos: usize) -> &[u8] {
+        &self.window()[from_pos..]
+    }
+
+    fn advance(&self, amount: usize) -> Option<&[u8]> {
+        let pos = self.pos.get();
+
+        if amount > 0 && !self.load_to(pos + amount - 1) {
+            return None;
+        }
+
+        self.pos.set(pos + amount);
+        Some(&self.window()[pos..pos + amount])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rust_extensions::array_of_bytes_iterator::ArrayOfBytesIterator;
+
+    use super::ReadIterator;
+    use crate::json_reader::array_iterator_inner::JsonArrayIteratorInner;
+    use crate::json_reader::{JsonFirstLineReaderInner, JsonLCheckpoint, JsonLIterator};
+
+    // Hands out at most three bytes a read, the way a socket hands out what has arrived.
+    struct Trickle<'s>(&'s [u8]);
+
+    impl<'s> std::io::Read for Trickle<'s> {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            let len = self.0.len().min(buf.len()).min(3);
+            buf[..len].copy_from_slice(&self.0[..len]);
+            self.0 = &self.0[len..];
+            Ok(len)
+        }
+    }
+
+    #[test]
+    fn json_l_is_read_in_a_bounded_window() {
+        let mut payload = String::new();
+        for i in 0..200 {
+            payload.push_str(&format!(
+                "{{\"id\":{},\"pad\":\"{}\"}}\n",
+                i,
+                "x".repeat(i % 13)
+            ));
+        }
+
+        for skip_bad_records in [false, true] {
+            let src = ReadIterator::new(Trickle(payload.as_bytes())).with_chunk_size(16);
+            let mut iterator = JsonLIterator::new(src);
+
+            if skip_bad_records {
+                iterator = iterator.with_skip_bad_records();
+            }
+
+            let mut count = 0;
+            let mut largest_window = 0;
+
+            while let Some(record) = iterator.get_next_record() {
+                let record = record.unwrap();
+                let expected = payload.lines().nth(count).unwrap();
+
+                assert_eq!(record.data, expected.as_bytes());
+                assert_eq!(record.line, count + 1);
+                assert_eq!(
+                    &payload[record.offset..record.offset + expected.len()],
+                    expected
+                );
+
+                count += 1;
+                iterator.release_consumed();
+                largest_window = largest_window.max(iterator.get_src_slice().len());
+            }
+
+            assert_eq!(count, 200);
+            assert!(largest_window < 128, "{}", largest_window);
+        }
+    }
+
+    #[test]
+    fn array_and_object_readers_work_over_a_reader() {
+        let json = br#"[{"a":1}, [2, 3], "four"]"#;
+
+        let mut iterator = JsonArrayIteratorInner::new(ReadIterator::new(Trickle(json))).unwrap();
+        let mut items = Vec::new();
+
+        while let Some(item) = iterator.get_next() {
+            let item = item.unwrap();
+            items.push(iterator.get_src_slice()[item.start..item.end].to_vec());
+            iterator.release_consumed();
+        }
+
+        assert_eq!(
+            items,
+            vec![
+                br#"{"a":1}"#.to_vec(),
+                b"[2, 3]".to_vec(),
+                br#""four""#.to_vec()
+            ]
+        );
+
+        let json = br#"{"a": 1, "b": [true]}"#;
+        let mut reader = JsonFirstLineReaderInner::new(ReadIterator::new(Trickle(json)));
+        let mut keys = Vec::new();
+
+        while let Some(item) = reader.get_next() {
+            let item = item.unwrap();
+            keys.push(item.name.as_raw_str(&reader).unwrap().to_string());
+            reader.release_consumed();
+        }
+
+        assert_eq!(keys, vec!["\"a\"", "\"b\""]);
+    }
+
+    #[test]
+    fn json_l_resumes_over_a_reader_in_a_bounded_window() {
+        let mut payload = String::new();
+        for i in 0..20_000 {
+            payload.push_str(&format!("{{\"id\":{}}}\n", i));
+        }
+
+        let mut iterator = JsonLIterator::new(ReadIterator::new(payload.as_bytes()));
+        for _ in 0..15_000 {
+            iterator.get_next_record().unwrap().unwrap();
+            iterator.release_consumed();
+        }
+        let checkpoint = iterator.checkpoint();
+
+        let from_start = JsonLIterator::resume_reader(
+            ReadIterator::new(Trickle(payload.as_bytes())).with_chunk_size(16),
+            checkpoint,
+        )
+        .unwrap();
+
+        let seeked = JsonLIterator::resume_reader(
+            ReadIterator::starting_at(
+                Trickle(&payload.as_bytes()[checkpoint.offset..]),
+                checkpoint.offset,
+            ),
+            checkpoint,
+        )
+        .unwrap();
+
+        for mut resumed in [from_start, seeked] {
+            assert!(resumed.get_src_slice().len() < 128);
+
+            let record = resumed.get_next_record().unwrap().unwrap();
+            assert_eq!(record.data, b"{\"id\":15000}");
+            assert_eq!(&payload[checkpoint.offset..record.offset], "\n");
+            assert_eq!(record.line, 15_001);
+
+            let end = record.offset + record.data.len();
+            assert_eq!(resumed.checkpoint().offset, end);
+        }
+
+        for bad in [checkpoint.offset + 3, payload.len() + 1] {
+            let checkpoint = JsonLCheckpoint {
+                offset: bad,
+                line: 1,
+            };
+            let result =
+                JsonLIterator::resume_reader(ReadIterator::new(payload.as_bytes()), checkpoint);
+            assert!(result.is_err(), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn a_failed_read_ends_the_source() {
+        struct Failing;
+
+        impl std::io::Read for Failing {
+            fn read(&mut self, _: &mut [u8]) -> std::io::Result<usize> {
+                Err(std::io::Error::new(
+                    std::io::ErrorKind::ConnectionReset,
+                    "reset",
+                ))
+            }
+        }
+
+        let mut src = ReadIterator::new(Failing);
+        assert!(src.peek_value().is_none());
+        assert!(src.advance(1).is_none());
+        assert_eq!(
+            src.take_error().unwrap().kind(),
+            std::io::ErrorKind::ConnectionReset
+        );
+    }
+}
@@ -1,3 +1,5 @@
+use std::fmt::Write;
+
 use rust_extensions::StrOrString;
 
 const DOUBLE_QUOTE: char = '"';
@@ -64,6 +66,79 @@ pub fn write_escaped_json_string_value(src: &str, out: &mut String) {
     }
 }
 
+/// Which characters, on top of the ones JSON requires, a string writer turns into `\uXXXX`
+/// escapes. The default escapes only what JSON requires and writes everything else raw.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct JsonEscapeMode {
+    /// Escape every non-ASCII character (as a UTF-16 surrogate pair above U+FFFF), so the output
+    /// survives ASCII-only transports.
+    pub ascii_only: bool,
+    /// Escape `<`, `>`, `&`, `'` and U+2028 / U+2029, so the output can be embedded inside a
+    /// `<script>` tag (no `</script>` or `<!--` can appear) and evaluated as JavaScript.
+    pub html_safe: bool,
+}
+
+impl JsonEscapeMode {
+    pub const STANDARD: Self = Self {
+        ascii_only: false,
+        html_safe: false,
+    };
+
+    pub const ASCII_ONLY: Self = Self {
+        ascii_only: true,
+        html_safe: false,
+    };
+
+    pub const HTML_SAFE: Self = Self {
+        ascii_only: false,
+        html_safe: true,
+    };
+
+    pub const ASCII_HTML_SAFE: Self = Self {
+        ascii_only: true,
+        html_safe: true,
+    };
+
+    fn must_escape_as_unicode(&self, c: char) -> bool {
+        if self.ascii_only && !c.is_ascii() {
+            return true;
+        }
+
+        self.html_safe && matches!(c, '<' | '>' | '&' | '\'' | '\u{2028}' | '\u{2029}')
+    }
+}
+
+fn write_unicode_escape(c: char, out: &mut String) {
+    let mut utf16 = [0u16; 2];
+    for unit in c.encode_utf16(&mut utf16) {
+        let _ = write!(out, "\\u{:04x}", unit);
+    }
+}
+
+/// [`write_escaped_json_string_value`] with the extra escaping of `mode` on top.
+pub fn write_escaped_json_string_value_with_mode(
+    src: &str,
+    mode: JsonEscapeMode,
+    out: &mut String,
+) {
+    if mode == JsonEscapeMode::STANDARD {
+        write_escaped_json_string_value(src, out);
+        return;
+    }
+
+    let mut from = 0;
+
+    for (index, c) in src.char_indices() {
+        if mode.must_escape_as_unicode(c) {
+            write_escaped_json_string_value(&src[from..index], out);
+            write_unicode_escape(c, out);
+            from = index + c.len_utf8();
+        }
+    }
+
+    write_escaped_json_string_value(&src[from..], out);
+}
+
 pub fn escape_json_string_value<'s>(src: &'s str) -> StrOrString<'s> {
     if !has_to_escape(src.as_bytes()) {
         return src.into();
@@ -116,7 +191,7 @@ pub fn escape_json_string_value<'s>(src: &'s str) -> StrOrString<'s> {
 /// Reads exactly 4 hex digits from `chars` and returns their value, or `None` if fewer than 4
 /// remain or any is not a hex digit (consuming what it read - the caller clones the iterator
 /// first when it needs a non-destructive look-ahead).
-fn parse_unicode_escape(chars: &mut std::iter::Peekable<std::str::Chars>) -> Option<u32> {
+fn parse_unicode_escape(chars: &mut std::str::Chars) -> Option<u32> {
     let mut code: u32 = 0;
     for _ in 0..4 {
         let digit = chars.next()?.to_digit(16)?;
@@ -125,83 +200,163 @@ fn parse_unicode_escape(chars: &mut std::iter::Peekable<std::str::Chars>) -> Opt
     Some(code)
 }
 
-pub fn de_escape_json_string_value<'s>(src: &'s str) -> StrOrString<'s> {
-    if !has_to_escape(src.as_bytes()) {
-        return src.into();
-    }
+/// One step of de-escaping: either a run of source text that needs no change, or a single
+/// character decoded from an escape sequence.
+enum UnescapedPiece<'s> {
+    Str(&'s str),
+    Char(char),
+}
 
-    let mut result = String::with_capacity(src.len());
-    let mut chars = src.chars().peekable();
-
-    while let Some(c) = chars.next() {
-        if c == '\\' {
-            match chars.next() {
-                Some('"') => result.push('"'),
-                Some('\\') => result.push('\\'),
-                Some('/') => result.push('/'),
-                Some('b') => result.push(BACKSPACE), // backspace
-                Some('f') => result.push(FORM_FEED), // form feed
-                Some('n') => result.push(NEWLINE),   // newline
-                Some('r') => result.push(CARRIAGE_RETURN), // carriage return
-                Some('t') => result.push(TAB),       // tab
-                Some('u') => {
-                    // Handle \uXXXX unicode escape, including surrogate pairs
-                    // (a high surrogate \uD800-\uDBFF followed by a low surrogate \uDC00-\uDFFF).
-                    match parse_unicode_escape(&mut chars) {
-                        Some(code) if (0xD800..=0xDBFF).contains(&code) => {
-                            // High surrogate: try to combine with a following low surrogate.
-                            let mut lookahead = chars.clone();
-                            let combined =
-                                if lookahead.next() == Some('\\') && lookahead.next() == Some('u') {
-                                    match parse_unicode_escape(&mut lookahead) {
-                                        Some(low) if (0xDC00..=0xDFFF).contains(&low) => Some(
-                                            0x10000 + ((code - 0xD800) << 10) + (low - 0xDC00),
-                                        ),
-                                        _ => None,
+// Walks an escaped JSON string body piece by piece, borrowing everything that is not an escape
+// straight from the source. Every de-escaping entry point - allocating, buffer-filling and
+// comparing - goes through this one decoder, so they all agree on the edge cases (surrogate
+// pairs, broken `\u` escapes, unknown escapes).
+struct UnescapedPieces<'s> {
+    rest: &'s str,
+}
+
+impl<'s> Iterator for UnescapedPieces<'s> {
+    type Item = UnescapedPiece<'s>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.rest.is_empty() {
+            return None;
+        }
+
+        let rest = self.rest;
+
+        match rest.find('\\') {
+            Some(0) => {}
+            Some(index) => {
+                self.rest = &rest[index..];
+                return Some(UnescapedPiece::Str(&rest[..index]));
+            }
+            None => {
+                self.rest = "";
+                return Some(UnescapedPiece::Str(rest));
+            }
+        }
+
+        let mut chars = rest[1..].chars();
+
+        let piece = match chars.next() {
+            Some('"') => UnescapedPiece::Char('"'),
+            Some('\\') => UnescapedPiece::Char('\\'),
+            Some('/') => UnescapedPiece::Char('/'),
+            Some('b') => UnescapedPiece::Char(BACKSPACE),
+            Some('f') => UnescapedPiece::Char(FORM_FEED),
+            Some('n') => UnescapedPiece::Char(NEWLINE),
+            Some('r') => UnescapedPiece::Char(CARRIAGE_RETURN),
+            Some('t') => UnescapedPiece::Char(TAB),
+            Some('u') => {
+                // Handle \uXXXX unicode escape, including surrogate pairs
+                // (a high surrogate \uD800-\uDBFF followed by a low surrogate \uDC00-\uDFFF).
+                match parse_unicode_escape(&mut chars) {
+                    Some(code) if (0xD800..=0xDBFF).contains(&code) => {
+                        // High surrogate: try to combine with a following low surrogate.
+                        let mut lookahead = chars.clone();
+                        let combined =
+                            if lookahead.next() == Some('\\') && lookahead.next() == Some('u') {
+                                match parse_unicode_escape(&mut lookahead) {
+                                    Some(low) if (0xDC00..=0xDFFF).contains(&low) => {
+                                        Some(0x10000 + ((code - 0xD800) << 10) + (low - 0xDC00))
                                     }
-                                } else {
-                                    None
-                                };
-
-                            match combined {
-                                Some(cp) => {
-                                    // consume the low surrogate escape we peeked
-                                    chars = lookahead;
-                                    result.push(char::from_u32(cp).unwrap_or('\u{FFFD}'));
+                                    _ => None,
                                 }
-                                // unpaired high surrogate -> replacement character
-                                None => result.push('\u{FFFD}'),
+                            } else {
+                                None
+                            };
+
+                        match combined {
+                            Some(cp) => {
+                                // consume the low surrogate escape we peeked
+                                chars = lookahead;
+                                UnescapedPiece::Char(char::from_u32(cp).unwrap_or('\u{FFFD}'))
                             }
-                        }
-                        // lone low surrogate -> replacement character
-                        Some(code) if (0xDC00..=0xDFFF).contains(&code) => {
-                            result.push('\u{FFFD}')
-                        }
-                        Some(code) => {
-                            result.push(char::from_u32(code).unwrap_or('\u{FFFD}'));
-                        }
-                        None => {
-                            // Invalid / truncated \uXXXX escape - keep the marker literally.
-                            result.push_str("\\u");
+                            // unpaired high surrogate -> replacement character
+                            None => UnescapedPiece::Char('\u{FFFD}'),
                         }
                     }
+                    // lone low surrogate -> replacement character
+                    Some(code) if (0xDC00..=0xDFFF).contains(&code) => {
+                        UnescapedPiece::Char('\u{FFFD}')
+                    }
+                    Some(code) => UnescapedPiece::Char(char::from_u32(code).unwrap_or('\u{FFFD}')),
+                    // Invalid / truncated \uXXXX escape - keep the marker literally.
+                    None => UnescapedPiece::Str(&rest[..2]),
                 }
-                Some(other) => {
-                    // Unknown escape sequence, push backslash and the character
-                    result.push('\\');
-                    result.push(other);
-                }
-                None => {
-                    // Backslash at end of string, push it
-                    result.push('\\');
+            }
+            // Unknown escape sequence, keep the backslash and the character
+            Some(other) => UnescapedPiece::Str(&rest[..1 + other.len_utf8()]),
+            // Backslash at end of string, keep it
+            None => UnescapedPiece::Str(rest),
+        };
+
+        self.rest = chars.as_str();
+        Some(piece)
+    }
+}
+
+fn unescaped_pieces(src: &str) -> UnescapedPieces<'_> {
+    UnescapedPieces { rest: src }
+}
+
+pub fn de_escape_json_string_value<'s>(src: &'s str) -> StrOrString<'s> {
+    if !has_to_escape(src.as_bytes()) {
+        return src.into();
+    }
+
+    let mut result = String::with_capacity(src.len());
+    de_escape_json_string_value_into(src, &mut result);
+    result.into()
+}
+
+/// Appends the de-escaped `src` to `out` - [`de_escape_json_string_value`] without the
+/// allocation, for loops that reuse one buffer across many values.
+pub fn de_escape_json_string_value_into(src: &str, out: &mut String) {
+    for piece in unescaped_pieces(src) {
+        match piece {
+            UnescapedPiece::Str(value) => out.push_str(value),
+            UnescapedPiece::Char(c) => out.push(c),
+        }
+    }
+}
+
+/// [`de_escape_json_string_value_into`] appending the UTF-8 bytes to a `Vec<u8>`.
+pub fn de_escape_json_string_value_into_vec(src: &str, out: &mut Vec<u8>) {
+    for piece in unescaped_pieces(src) {
+        match piece {
+            UnescapedPiece::Str(value) => out.extend_from_slice(value.as_bytes()),
+            UnescapedPiece::Char(c) => {
+                let mut buffer = [0u8; 4];
+                out.extend_from_slice(c.encode_utf8(&mut buffer).as_bytes());
+            }
+        }
+    }
+}
+
+/// Checks whether the escaped JSON string body `escaped` de-escapes to exactly `expected`,
+/// decoding as it compares - nothing is allocated, and it stops at the first difference.
+pub fn json_string_value_eq(escaped: &str, expected: &str) -> bool {
+    let mut expected = expected;
+
+    for piece in unescaped_pieces(escaped) {
+        match piece {
+            UnescapedPiece::Str(value) => match expected.strip_prefix(value) {
+                Some(rest) => expected = rest,
+                None => return false,
+            },
+            UnescapedPiece::Char(c) => {
+                let mut chars = expected.chars();
+                if chars.next() != Some(c) {
+                    return false;
                 }
+                expected = chars.as_str();
             }
-        } else {
-            result.push(c);
         }
     }
 
-    result.into()
+    expected.is_empty()
 }
 
 #[cfg(test)]
@@ -340,4 +495,92 @@ mod test {
         let de_escaped = super::de_escape_json_string_value(src);
         assert_eq!("\n\t\r\"\\/\u{08}\u{0C}", de_escaped.as_str());
     }
+
+    #[test]
+    pub fn test_ascii_only_escapes_non_ascii_with_surrogate_pairs() {
+        let mut out = String::new();
+        super::write_escaped_json_string_value_with_mode(
+            "café \"😀\"\n",
+            super::JsonEscapeMode::ASCII_ONLY,
+            &mut out,
+        );
+
+        assert_eq!(out, "caf\\u00e9 \\\"\\ud83d\\ude00\\\"\\n");
+        assert!(out.is_ascii());
+
+        let de_escaped = super::de_escape_json_string_value(out.as_str());
+        assert_eq!(de_escaped.as_str(), "café \"😀\"\n");
+    }
+
+    #[test]
+    pub fn test_html_safe_escapes_script_breakers() {
+        let src = "</script><!-- a & b 'c' \u{2028}\u{2029} é";
+
+        let mut out = String::new();
+        super::write_escaped_json_string_value_with_mode(
+            src,
+            super::JsonEscapeMode::HTML_SAFE,
+            &mut out,
+        );
+
+        assert_eq!(
+            out,
+            "\\u003c/script\\u003e\\u003c!-- a \\u0026 b \\u0027c\\u0027 \\u2028\\u2029 é"
+        );
+        assert_eq!(super::de_escape_json_string_value(out.as_str()).as_str(), src);
+    }
+
+    #[test]
+    pub fn test_standard_mode_matches_plain_escape() {
+        let src = "a<b> \"ü\" \t";
+
+        let mut plain = String::new();
+        super::write_escaped_json_string_value(src, &mut plain);
+
+        let mut with_mode = String::new();
+        super::write_escaped_json_string_value_with_mode(
+            src,
+            super::JsonEscapeMode::default(),
+            &mut with_mode,
+        );
+
+        assert_eq!(plain, with_mode);
+    }
+
+    #[test]
+    pub fn test_de_escape_into_reuses_the_buffer() {
+        let mut buffer = String::new();
+
+        for (src, expected) in [
+            ("plain", "plain"),
+            ("a\\nb", "a\nb"),
+            ("\\uD83D\\uDE00!", "\u{1F600}!"),
+            ("x\\uD8", "x\\u"),
+            ("tail\\", "tail\\"),
+            ("\\q", "\\q"),
+        ] {
+            buffer.clear();
+            super::de_escape_json_string_value_into(src, &mut buffer);
+            assert_eq!(buffer, expected);
+            assert_eq!(super::de_escape_json_string_value(src).as_str(), expected);
+
+            let mut bytes = Vec::new();
+            super::de_escape_json_string_value_into_vec(src, &mut bytes);
+            assert_eq!(bytes, expected.as_bytes());
+        }
+    }
+
+    #[test]
+    pub fn test_json_string_value_eq() {
+        assert!(super::json_string_value_eq("name", "name"));
+        assert!(super::json_string_value_eq("a\\\"b", "a\"b"));
+        assert!(super::json_string_value_eq("\\u0041BC", "ABC"));
+        assert!(super::json_string_value_eq("\\uD83D\\uDE00", "\u{1F600}"));
+        assert!(super::json_string_value_eq("", ""));
+
+        assert!(!super::json_string_value_eq("name", "nam"));
+        assert!(!super::json_string_value_eq("nam", "name"));
+        assert!(!super::json_string_value_eq("a\\nb", "a\\nb"));
+        assert!(!super::json_string_value_eq("\\u0041", "B"));
+    }
 }
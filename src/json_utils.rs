@@ -182,12 +182,17 @@ pub fn is_string(src: &[u8]) -> bool {
 }
 
 pub fn try_get_string_value<'s>(src: &'s [u8]) -> Option<StrOrString<'s>> {
+    let inner = try_get_escaped_string_body(src)?;
+    Some(crate::json_string_value::de_escape_json_string_value(inner))
+}
+
+/// The text between the quotes of a quoted string value, escapes still in place.
+pub fn try_get_escaped_string_body(src: &[u8]) -> Option<&str> {
     // Requires both surrounding quotes; a lone quote (len < 2) is not a well formed string.
     if src.len() >= 2 && is_string(src) {
         // Untrusted input can carry invalid UTF-8 inside the quotes - return `None` instead of
         // panicking (the previous `.unwrap()` was a DoS on hostile bytes).
-        let inner = std::str::from_utf8(src[1..src.len() - 1].as_ref()).ok()?;
-        return Some(crate::json_string_value::de_escape_json_string_value(inner));
+        return std::str::from_utf8(src[1..src.len() - 1].as_ref()).ok();
     }
 
     None
@@ -0,0 +1,198 @@
+use std::io::Write;
+
+use super::*;
+
+/// Streams a JSON array straight into an [`std::io::Write`] instead of holding it in memory until
+/// `build()` like [`JsonArrayWriter`] does.
+///
+/// The `[` goes out in [`Self::new`], every element is written through as soon as it is added, and
+/// [`Self::finish`] writes the closing `]`. Memory use is bounded by the largest single element,
+/// not by the array, which is what makes exports of millions of rows possible.
+///
+/// Every `write` call is exactly **one** element. An array-valued element (`T::IS_ARRAY`, e.g. a
+/// `Vec<T>`) is wrapped in its own `[` `]`, the same way `JsonObjectWriter::write` and the `Vec<Vec<T>>`
+/// writer treat it - it never splices into this array.
+///
+/// Dropping the writer without calling [`Self::finish`] leaves the output unterminated.
+pub struct JsonArrayStreamWriter<TWrite: Write> {
+    dest: TWrite,
+    buffer: String,
+    first_element: bool,
+    options: JsonWriterOptions,
+}
+
+impl<TWrite: Write> JsonArrayStreamWriter<TWrite> {
+    pub fn new(mut dest: TWrite) -> std::io::Result<Self> {
+        dest.write_all(b"[")?;
+
+        Ok(Self {
+            dest,
+            buffer: String::new(),
+            first_element: true,
+            options: JsonWriterOptions::default(),
+        })
+    }
+
+    /// Options for every value written from here on - see [`JsonWriterOptions`].
+    pub fn with_options(mut self, options: JsonWriterOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    fn add_delimiter(&mut self) {
+        if self.first_element {
+            self.first_element = false;
+        } else {
+            self.buffer.push(',');
+        }
+    }
+
+    // The scratch buffer is reused between elements, so a steady stream of similar rows settles
+    // on one allocation.
+    fn flush_element(&mut self) -> std::io::Result<()> {
+        let result = self.dest.write_all(self.buffer.as_bytes());
+        self.buffer.clear();
+        result
+    }
+
+    pub fn write<TJsonValue: JsonValueWriter>(&mut self, value: TJsonValue) -> std::io::Result<()> {
+        self.write_ref(&value)
+    }
+
+    pub fn write_ref<TJsonValue: JsonValueWriter>(
+        &mut self,
+        value: &TJsonValue,
+    ) -> std::io::Result<()> {
+        self.add_delimiter();
+
+        if TJsonValue::IS_ARRAY {
+            self.buffer.push('[');
+        }
+
+        value.write_with_options(&mut self.buffer, &self.options);
+
+        if TJsonValue::IS_ARRAY {
+            self.buffer.push(']');
+        }
+
+        self.flush_element()
+    }
+
+    pub fn write_null_element(&mut self) -> std::io::Result<()> {
+        self.add_delimiter();
+        self.buffer.push_str("null");
+        self.flush_element()
+    }
+
+    /// Writes every item of `values` as a separate element, flushing each one as it is produced.
+    pub fn write_iter<TJsonValue: JsonValueWriter>(
+        &mut self,
+        values: impl Iterator<Item = TJsonValue>,
+    ) -> std::io::Result<()> {
+        for itm in values {
+            self.write(itm)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn write_json_object(
+        &mut self,
+        write_object: impl Fn(JsonObjectWriter) -> JsonObjectWriter,
+    ) -> std::io::Result<()> {
+        self.add_delimiter();
+
+        let raw = std::mem::take(&mut self.buffer);
+        self.buffer =
+            write_object(JsonObjectWriter::from_string(raw).with_options(self.options)).build();
+
+        self.flush_element()
+    }
+
+    pub fn write_json_array(
+        &mut self,
+        write_array: impl Fn(JsonArrayWriter) -> JsonArrayWriter,
+    ) -> std::io::Result<()> {
+        self.add_delimiter();
+
+        let raw = std::mem::take(&mut self.buffer);
+        self.buffer =
+            write_array(JsonArrayWriter::from_string(raw).with_options(self.options)).build();
+
+        self.flush_element()
+    }
+
+    pub fn has_written(&self) -> bool {
+        !self.first_element
+    }
+
+    /// Writes the closing `]`, flushes the destination and hands it back.
+    pub fn finish(mut self) -> std::io::Result<TWrite> {
+        self.dest.write_all(b"]")?;
+        self.dest.flush()?;
+        Ok(self.dest)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::JsonArrayStreamWriter;
+
+    #[test]
+    fn test_empty_array() {
+        let writer = JsonArrayStreamWriter::new(Vec::new()).unwrap();
+        let result = writer.finish().unwrap();
+
+        assert_eq!(result, b"[]");
+    }
+
+    #[test]
+    fn test_elements_are_written_through_before_finish() {
+        let mut writer = JsonArrayStreamWriter::new(Vec::new()).unwrap();
+
+        writer.write(1).unwrap();
+        assert_eq!(writer.dest, b"[1");
+
+        writer.write("two").unwrap();
+        writer.write_null_element().unwrap();
+        assert_eq!(writer.dest, br#"[1,"two",null"#);
+
+        let result = writer.finish().unwrap();
+        assert_eq!(result, br#"[1,"two",null]"#);
+    }
+
+    #[test]
+    fn test_matches_in_memory_array_writer() {
+        let mut writer = JsonArrayStreamWriter::new(Vec::new()).unwrap();
+
+        writer
+            .write_json_object(|o| o.write("id", 1).write("name", "Alice"))
+            .unwrap();
+        writer
+            .write_json_object(|o| o.write("id", 2).write("name", "Bob"))
+            .unwrap();
+        writer.write_json_array(|a| a.write(1).write(2)).unwrap();
+
+        let streamed = String::from_utf8(writer.finish().unwrap()).unwrap();
+
+        let in_memory = super::JsonArrayWriter::new()
+            .write_json_object(|o| o.write("id", 1).write("name", "Alice"))
+            .write_json_object(|o| o.write("id", 2).write("name", "Bob"))
+            .write(super::JsonArrayWriter::new().write(1).write(2))
+            .build();
+
+        assert_eq!(streamed, in_memory);
+    }
+
+    #[test]
+    fn test_array_valued_element_stays_nested() {
+        let mut writer = JsonArrayStreamWriter::new(Vec::new()).unwrap();
+
+        writer.write(vec![1, 2]).unwrap();
+        writer.write(Vec::<i32>::new()).unwrap();
+        writer.write_iter([3, 4].into_iter()).unwrap();
+
+        let result = writer.finish().unwrap();
+        assert_eq!(result, b"[[1,2],[],3,4]");
+    }
+}
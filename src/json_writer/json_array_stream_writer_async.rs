@@ -0,0 +1,144 @@
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+
+use super::*;
+
+/// Async twin of [`JsonArrayStreamWriter`] for a [`tokio::io::AsyncWrite`] destination.
+///
+/// Elements are serialized into a reused scratch buffer exactly like the sync writer does and the
+/// buffer is awaited out after each `write`, so element formatting stays synchronous and only the
+/// I/O is async.
+pub struct JsonArrayStreamWriterAsync<TWrite: AsyncWrite + Unpin> {
+    dest: TWrite,
+    buffer: String,
+    first_element: bool,
+    options: JsonWriterOptions,
+}
+
+impl<TWrite: AsyncWrite + Unpin> JsonArrayStreamWriterAsync<TWrite> {
+    pub async fn new(mut dest: TWrite) -> std::io::Result<Self> {
+        dest.write_all(b"[").await?;
+
+        Ok(Self {
+            dest,
+            buffer: String::new(),
+            first_element: true,
+            options: JsonWriterOptions::default(),
+        })
+    }
+
+    /// Options for every value written from here on - see [`JsonWriterOptions`].
+    pub fn with_options(mut self, options: JsonWriterOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    fn add_delimiter(&mut self) {
+        if self.first_element {
+            self.first_element = false;
+        } else {
+            self.buffer.push(',');
+        }
+    }
+
+    async fn flush_element(&mut self) -> std::io::Result<()> {
+        let result = self.dest.write_all(self.buffer.as_bytes()).await;
+        self.buffer.clear();
+        result
+    }
+
+    pub async fn write<TJsonValue: JsonValueWriter>(
+        &mut self,
+        value: TJsonValue,
+    ) -> std::io::Result<()> {
+        self.write_ref(&value).await
+    }
+
+    pub async fn write_ref<TJsonValue: JsonValueWriter>(
+        &mut self,
+        value: &TJsonValue,
+    ) -> std::io::Result<()> {
+        self.add_delimiter();
+
+        if TJsonValue::IS_ARRAY {
+            self.buffer.push('[');
+        }
+
+        value.write_with_options(&mut self.buffer, &self.options);
+
+        if TJsonValue::IS_ARRAY {
+            self.buffer.push(']');
+        }
+
+        self.flush_element().await
+    }
+
+    pub async fn write_null_element(&mut self) -> std::io::Result<()> {
+        self.add_delimiter();
+        self.buffer.push_str("null");
+        self.flush_element().await
+    }
+
+    pub async fn write_json_object(
+        &mut self,
+        write_object: impl Fn(JsonObjectWriter) -> JsonObjectWriter,
+    ) -> std::io::Result<()> {
+        self.add_delimiter();
+
+        let raw = std::mem::take(&mut self.buffer);
+        self.buffer =
+            write_object(JsonObjectWriter::from_string(raw).with_options(self.options)).build();
+
+        self.flush_element().await
+    }
+
+    pub async fn write_json_array(
+        &mut self,
+        write_array: impl Fn(JsonArrayWriter) -> JsonArrayWriter,
+    ) -> std::io::Result<()> {
+        self.add_delimiter();
+
+        let raw = std::mem::take(&mut self.buffer);
+        self.buffer =
+            write_array(JsonArrayWriter::from_string(raw).with_options(self.options)).build();
+
+        self.flush_element().await
+    }
+
+    pub fn has_written(&self) -> bool {
+        !self.first_element
+    }
+
+    /// Writes the closing `]`, flushes the destination and hands it back.
+    pub async fn finish(mut self) -> std::io::Result<TWrite> {
+        self.dest.write_all(b"]").await?;
+        self.dest.flush().await?;
+        Ok(self.dest)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::JsonArrayStreamWriterAsync;
+
+    #[tokio::test]
+    async fn test_matches_sync_stream_writer() {
+        let mut writer = JsonArrayStreamWriterAsync::new(Vec::new()).await.unwrap();
+        writer.write(1).await.unwrap();
+        writer.write(vec!["a", "b"]).await.unwrap();
+        writer
+            .write_json_object(|o| o.write("id", 1))
+            .await
+            .unwrap();
+        writer.write_null_element().await.unwrap();
+        let streamed = writer.finish().await.unwrap();
+
+        let mut sync_writer = super::JsonArrayStreamWriter::new(Vec::new()).unwrap();
+        sync_writer.write(1).unwrap();
+        sync_writer.write(vec!["a", "b"]).unwrap();
+        sync_writer.write_json_object(|o| o.write("id", 1)).unwrap();
+        sync_writer.write_null_element().unwrap();
+
+        assert_eq!(streamed, sync_writer.finish().unwrap());
+        assert_eq!(streamed, br#"[1,["a","b"],{"id":1},null]"#);
+    }
+}
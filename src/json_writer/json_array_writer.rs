@@ -3,6 +3,7 @@ use super::*;
 pub struct JsonArrayWriter {
     raw: Option<String>,
     first_element: bool,
+    options: JsonWriterOptions,
 }
 
 impl JsonArrayWriter {
@@ -13,6 +14,7 @@ impl JsonArrayWriter {
         Self {
             raw: Some(raw),
             first_element: true,
+            options: JsonWriterOptions::default(),
         }
     }
 
@@ -21,9 +23,27 @@ impl JsonArrayWriter {
         Self {
             first_element: true,
             raw: Some(raw),
+            options: JsonWriterOptions::default(),
         }
     }
 
+    /// Options for every value written from here on, nested objects included - see
+    /// [`JsonWriterOptions`].
+    pub fn with_options(mut self, options: JsonWriterOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    pub fn with_number_format(mut self, number_format: JsonNumberFormat) -> Self {
+        self.options.number_format = number_format;
+        self
+    }
+
+    pub fn with_escape_mode(mut self, escape_mode: JsonEscapeMode) -> Self {
+        self.options.escape_mode = escape_mode;
+        self
+    }
+
     fn add_delimiter(&mut self) {
         if self.first_element {
             self.first_element = false;
@@ -47,14 +67,14 @@ impl JsonArrayWriter {
     pub fn write(mut self, value: impl JsonValueWriter) -> Self {
         self.add_delimiter();
         let raw = self.raw.as_mut().unwrap();
-        value.write(raw);
+        value.write_with_options(raw, &self.options);
         self
     }
 
     pub fn write_ref(mut self, value: &impl JsonValueWriter) -> Self {
         self.add_delimiter();
         let raw = self.raw.as_mut().unwrap();
-        value.write(raw);
+        value.write_with_options(raw, &self.options);
         self
     }
 
@@ -66,7 +86,7 @@ impl JsonArrayWriter {
         let raw = self.raw.as_mut().unwrap();
 
         for itm in values {
-            itm.write(raw);
+            itm.write_with_options(raw, &self.options);
         }
 
         self
@@ -80,7 +100,7 @@ impl JsonArrayWriter {
         let raw = self.raw.as_mut().unwrap();
 
         for itm in values {
-            itm.write(raw);
+            itm.write_with_options(raw, &self.options);
         }
 
         self
@@ -94,7 +114,8 @@ impl JsonArrayWriter {
 
         let raw = self.raw.take().unwrap();
 
-        let json_object_writer = write_object(JsonObjectWriter::from_string(raw));
+        let json_object_writer =
+            write_object(JsonObjectWriter::from_string(raw).with_options(self.options));
 
         let raw = json_object_writer.build();
         self.raw = Some(raw);
@@ -1,4 +1,14 @@
+use super::JsonWriterOptions;
+
 pub trait JsonValueWriter {
     const IS_ARRAY: bool;
     fn write(&self, dest: &mut String);
+
+    /// Same as [`Self::write`], honouring the options of the writer the value goes into. Only
+    /// numbers, strings and containers of them override it; everything else writes exactly as
+    /// [`Self::write`] does.
+    fn write_with_options(&self, dest: &mut String, options: &JsonWriterOptions) {
+        let _ = options;
+        self.write(dest);
+    }
 }
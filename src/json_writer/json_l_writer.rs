@@ -0,0 +1,401 @@
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use super::*;
+
+const DEFAULT_BATCH_SIZE: usize = 1000;
+
+/// When a rotating [`JsonLWriter`] moves on to the next file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JsonLRotateAt {
+    /// Before a record that would take the file past this many bytes. A file always gets at least
+    /// one record, so a single record larger than the limit still goes out, on its own.
+    Bytes(usize),
+    /// Once the file holds this many records.
+    Records(usize),
+}
+
+type OpenFile<TWrite> = Box<dyn FnMut(usize) -> std::io::Result<TWrite> + Send>;
+
+/// Writes JSON-L - one JSON value per line - into an [`std::io::Write`].
+///
+/// A record is always exactly one line: a line break inside a string is written as `\n`, and one
+/// between tokens - from a pretty-printed [`RawJsonObject`], say - as a space. Records are
+/// collected and written out [`Self::with_batch_size`] at a time, and [`Self::rotating`] spreads
+/// the output over numbered files.
+///
+/// Dropping the writer without calling [`Self::finish`] loses the records of the last batch.
+pub struct JsonLWriter<TWrite: Write> {
+    dest: TWrite,
+    open_file: Option<(OpenFile<TWrite>, JsonLRotateAt)>,
+    file_number: usize,
+    bytes_in_file: usize,
+    records_in_file: usize,
+    buffer: String,
+    buffered_records: usize,
+    batch_size: usize,
+    record: String,
+    options: JsonWriterOptions,
+}
+
+impl<TWrite: Write> JsonLWriter<TWrite> {
+    pub fn new(dest: TWrite) -> Self {
+        Self {
+            dest,
+            open_file: None,
+            file_number: 1,
+            bytes_in_file: 0,
+            records_in_file: 0,
+            buffer: String::new(),
+            buffered_records: 0,
+            batch_size: DEFAULT_BATCH_SIZE,
+            record: String::new(),
+            options: JsonWriterOptions::default(),
+        }
+    }
+
+    /// Writes into the destinations `open_file` returns, passing it the file number: 1 straight
+    /// away, then the next one each time `rotate_at` is reached. The previous destination is
+    /// flushed and dropped before the next one is opened.
+    pub fn rotating(
+        rotate_at: JsonLRotateAt,
+        mut open_file: impl FnMut(usize) -> std::io::Result<TWrite> + Send + 'static,
+    ) -> std::io::Result<Self> {
+        let dest = open_file(1)?;
+
+        let mut result = Self::new(dest);
+        result.open_file = Some((Box::new(open_file), rotate_at));
+        Ok(result)
+    }
+
+    /// How many records are collected before they are written out together. `0` and `1` both
+    /// write every record as it comes. Defaults to 1000.
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size;
+        self
+    }
+
+    /// Options for every value written from here on - see [`JsonWriterOptions`].
+    pub fn with_options(mut self, options: JsonWriterOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// The number of the file being written to, counting from 1.
+    pub fn get_file_number(&self) -> usize {
+        self.file_number
+    }
+
+    pub fn write<TJsonValue: JsonValueWriter>(&mut self, value: TJsonValue) -> std::io::Result<()> {
+        self.write_ref(&value)
+    }
+
+    /// An array-valued record (`T::IS_ARRAY`, e.g. a `Vec<T>`) is written as one JSON array, the
+    /// same way `JsonArrayStreamWriter` writes it as one element.
+    pub fn write_ref<TJsonValue: JsonValueWriter>(
+        &mut self,
+        value: &TJsonValue,
+    ) -> std::io::Result<()> {
+        if TJsonValue::IS_ARRAY {
+            self.record.push('[');
+        }
+
+        value.write_with_options(&mut self.record, &self.options);
+
+        if TJsonValue::IS_ARRAY {
+            self.record.push(']');
+        }
+
+        self.add_record()
+    }
+
+    /// Writes every item of `values` as a separate record.
+    pub fn write_iter<TJsonValue: JsonValueWriter>(
+        &mut self,
+        values: impl Iterator<Item = TJsonValue>,
+    ) -> std::io::Result<()> {
+        for itm in values {
+            self.write(itm)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn write_json_object(
+        &mut self,
+        write_object: impl Fn(JsonObjectWriter) -> JsonObjectWriter,
+    ) -> std::io::Result<()> {
+        let raw = std::mem::take(&mut self.record);
+        self.record =
+            write_object(JsonObjectWriter::from_string(raw).with_options(self.options)).build();
+
+        self.add_record()
+    }
+
+    // The record is rendered into its own scratch buffer first, so its size is known before the
+    // rotation check, and it is put on one line before it joins the batch.
+    fn add_record(&mut self) -> std::io::Result<()> {
+        keep_on_one_line(&mut self.record);
+        let len = self.record.len() + 1;
+
+        if self.must_rotate(len) {
+            self.rotate()?;
+        }
+
+        self.buffer.push_str(&self.record);
+        self.buffer.push('\n');
+        self.record.clear();
+
+        self.buffered_records += 1;
+        self.records_in_file += 1;
+        self.bytes_in_file += len;
+
+        if self.buffered_records >= self.batch_size {
+            self.write_batch()?;
+        }
+
+        Ok(())
+    }
+
+    fn must_rotate(&self, record_len: usize) -> bool {
+        if self.records_in_file == 0 {
+            return false;
+        }
+
+        match &self.open_file {
+            Some((_, JsonLRotateAt::Bytes(max))) => self.bytes_in_file + record_len > *max,
+            Some((_, JsonLRotateAt::Records(max))) => self.records_in_file >= *max,
+            None => false,
+        }
+    }
+
+    fn rotate(&mut self) -> std::io::Result<()> {
+        self.flush()?;
+
+        if let Some((open_file, _)) = &mut self.open_file {
+            self.dest = open_file(self.file_number + 1)?;
+            self.file_number += 1;
+            self.bytes_in_file = 0;
+            self.records_in_file = 0;
+        }
+
+        Ok(())
+    }
+
+    fn write_batch(&mut self) -> std::io::Result<()> {
+        let result = self.dest.write_all(self.buffer.as_bytes());
+        self.buffer.clear();
+        self.buffered_records = 0;
+        result
+    }
+
+    /// Writes out the records collected so far and flushes the destination.
+    pub fn flush(&mut self) -> std::io::Result<()> {
+        self.write_batch()?;
+        self.dest.flush()
+    }
+
+    /// Writes out the last batch, flushes the destination and hands it back.
+    pub fn finish(mut self) -> std::io::Result<TWrite> {
+        self.flush()?;
+        Ok(self.dest)
+    }
+}
+
+impl JsonLWriter<BufWriter<File>> {
+    /// Rotates over files named after `path` - see [`numbered_file_path`].
+    pub fn create_numbered_files(
+        path: impl AsRef<Path>,
+        rotate_at: JsonLRotateAt,
+    ) -> std::io::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+
+        Self::rotating(rotate_at, move |file_number| {
+            let file = File::create(numbered_file_path(&path, file_number))?;
+            Ok(BufWriter::new(file))
+        })
+    }
+}
+
+/// `export.jsonl` with file number 3 is `export.00003.jsonl`; the padding keeps the files in
+/// order when listed by name.
+pub fn numbered_file_path(path: impl AsRef<Path>, file_number: usize) -> PathBuf {
+    let path = path.as_ref();
+
+    let mut file_name = path.file_stem().unwrap_or_default().to_os_string();
+    file_name.push(format!(".{:05}", file_number));
+
+    if let Some(extension) = path.extension() {
+        file_name.push(".");
+        file_name.push(extension);
+    }
+
+    path.with_file_name(file_name)
+}
+
+// Writers escape the line breaks of the strings they write, so only a value passed through raw
+// can have one. Inside a string it becomes an escape; between tokens it is white space, and a
+// space means the same.
+fn keep_on_one_line(record: &mut String) {
+    if !record.contains(['\n', '\r']) {
+        return;
+    }
+
+    let mut result = String::with_capacity(record.len() + 8);
+    let mut in_string = false;
+    let mut escaped = false;
+
+    for c in record.chars() {
+        match c {
+            '\n' | '\r' if in_string => {
+                result.push_str(if c == '\n' { "\\n" } else { "\\r" });
+                escaped = false;
+                continue;
+            }
+            '\n' | '\r' => {
+                result.push(' ');
+                continue;
+            }
+            '"' if !escaped => in_string = !in_string,
+            _ => {}
+        }
+
+        escaped = in_string && c == '\\' && !escaped;
+        result.push(c);
+    }
+
+    *record = result;
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use super::*;
+
+    #[test]
+    fn test_one_record_per_line() {
+        let mut writer = JsonLWriter::new(Vec::new());
+
+        writer
+            .write_json_object(|o| o.write("id", 1).write("text", "a\nb"))
+            .unwrap();
+        writer.write(vec![1, 2]).unwrap();
+        writer
+            .write(RawJsonObject::AsStr("{\n  \"raw\": \"x\ny\\\"\n\"\r\n}"))
+            .unwrap();
+        writer.write("plain").unwrap();
+
+        let result = String::from_utf8(writer.finish().unwrap()).unwrap();
+
+        assert_eq!(
+            result,
+            "{\"id\":1,\"text\":\"a\\nb\"}\n[1,2]\n{   \"raw\": \"x\\ny\\\"\\n\"  }\n\"plain\"\n"
+        );
+
+        let mut records = crate::json_reader::JsonLIterator::new(
+            rust_extensions::array_of_bytes_iterator::SliceIterator::new(result.as_bytes()),
+        );
+
+        for line in 1..=4 {
+            assert_eq!(records.get_next_record().unwrap().unwrap().line, line);
+        }
+        assert!(records.get_next_record().is_none());
+    }
+
+    #[test]
+    fn test_records_are_written_in_batches() {
+        let mut writer = JsonLWriter::new(Vec::new()).with_batch_size(2);
+
+        writer.write(1).unwrap();
+        assert!(writer.dest.is_empty());
+
+        writer.write(2).unwrap();
+        assert_eq!(writer.dest, b"1\n2\n");
+
+        writer.write(3).unwrap();
+        assert_eq!(writer.dest, b"1\n2\n");
+
+        assert_eq!(writer.finish().unwrap(), b"1\n2\n3\n");
+    }
+
+    fn collect_files(
+        rotate_at: JsonLRotateAt,
+    ) -> (JsonLWriter<SharedFile>, Arc<Mutex<Vec<Vec<u8>>>>) {
+        let files = Arc::new(Mutex::new(Vec::new()));
+        let opened = files.clone();
+
+        let writer = JsonLWriter::rotating(rotate_at, move |file_number| {
+            let mut files = opened.lock().unwrap();
+            assert_eq!(files.len() + 1, file_number);
+            files.push(Vec::new());
+            Ok(SharedFile {
+                files: opened.clone(),
+                index: file_number - 1,
+            })
+        })
+        .unwrap();
+
+        (writer, files)
+    }
+
+    struct SharedFile {
+        files: Arc<Mutex<Vec<Vec<u8>>>>,
+        index: usize,
+    }
+
+    impl Write for SharedFile {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.files.lock().unwrap()[self.index].extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_rotates_by_records_and_by_bytes() {
+        let (mut writer, files) = collect_files(JsonLRotateAt::Records(2));
+        writer.write_iter(1..=5).unwrap();
+        assert_eq!(writer.get_file_number(), 3);
+        writer.finish().unwrap();
+
+        assert_eq!(
+            *files.lock().unwrap(),
+            vec![b"1\n2\n".to_vec(), b"3\n4\n".to_vec(), b"5\n".to_vec()]
+        );
+
+        let (mut writer, files) = collect_files(JsonLRotateAt::Bytes(6));
+        writer
+            .write_iter([1, 22, 333, 4444444].into_iter())
+            .unwrap();
+        writer.write(5).unwrap();
+        writer.finish().unwrap();
+
+        // a record bigger than the limit still goes out, on its own
+        assert_eq!(
+            *files.lock().unwrap(),
+            vec![
+                b"1\n22\n".to_vec(),
+                b"333\n".to_vec(),
+                b"4444444\n".to_vec(),
+                b"5\n".to_vec()
+            ]
+        );
+    }
+
+    #[test]
+    fn test_numbered_file_path() {
+        assert_eq!(
+            numbered_file_path("out/export.jsonl", 3),
+            PathBuf::from("out/export.00003.jsonl")
+        );
+        assert_eq!(
+            numbered_file_path("export", 12),
+            PathBuf::from("export.00012")
+        );
+    }
+}
@@ -0,0 +1,385 @@
+use std::fmt::Write;
+
+use super::JsonValueWriter;
+
+/// Largest integer a JavaScript `Number` (an IEEE-754 double) holds exactly: `2^53 - 1`.
+pub const MAX_SAFE_INTEGER: u64 = (1 << 53) - 1;
+
+/// How a float is rendered on the wire.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum JsonFloatFormat {
+    /// Rust's `Display` - what the plain `f32` / `f64` writers have always produced. Round-trips,
+    /// but never switches to exponent form, so `1e300` comes out as 301 digits.
+    #[default]
+    Display,
+    /// The shortest string that parses back to the same value, written with `ryu`. A whole value
+    /// keeps its `.0` (`3.0`, not `3`), so a reader that tells integers from doubles apart
+    /// (`UnwrappedJsonValue::Number` vs `Double`) still sees a double.
+    Shortest,
+    /// Exactly this many digits after the decimal point, rounded - e.g. a price written with the
+    /// instrument's `Digits`.
+    Fixed(usize),
+    /// Plain decimal while the value's decimal exponent `e` (as in `d.ddd * 10^e`) satisfies
+    /// `min_exponent <= e < max_exponent`, exponent form (`1.5e-7`) outside that range.
+    /// `Scientific { min_exponent: -6, max_exponent: 21 }` matches JavaScript's `Number#toString`.
+    Scientific {
+        min_exponent: i32,
+        max_exponent: i32,
+    },
+}
+
+/// Number formatting options for [`super::JsonObjectWriter`] / [`super::JsonArrayWriter`], set
+/// through their `with_number_format`. The options reach every number the writer renders -
+/// including ones inside a `Vec`, a `HashMap` or a nested `write_json_object` / `write_json_array`.
+///
+/// The default writes exactly what the writers write without options.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct JsonNumberFormat {
+    pub float: JsonFloatFormat,
+    /// Write integers outside `-(2^53 - 1)..=2^53 - 1` as JSON strings (`"9007199254740993"`),
+    /// so a JavaScript client does not silently round them when it parses the document.
+    pub big_integers_as_strings: bool,
+}
+
+impl JsonNumberFormat {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_float_format(mut self, float: JsonFloatFormat) -> Self {
+        self.float = float;
+        self
+    }
+
+    pub fn with_big_integers_as_strings(mut self) -> Self {
+        self.big_integers_as_strings = true;
+        self
+    }
+}
+
+/// A float type [`JsonFloatFormat`] can render.
+pub trait JsonFloatValue: Copy {
+    fn write_with_float_format(&self, dest: &mut String, format: JsonFloatFormat);
+}
+
+// Exponent form is produced with `{:e}` (itself the shortest round-trip digits), and the exponent
+// is read back from that text rather than computed with `log10`, which is off by one right at
+// powers of ten.
+fn write_scientific(
+    dest: &mut String,
+    min_exponent: i32,
+    max_exponent: i32,
+    write_exp: impl Fn(&mut String),
+    write_plain: impl Fn(&mut String),
+) {
+    let start = dest.len();
+    write_exp(dest);
+
+    let exponent = dest[start..]
+        .rsplit_once('e')
+        .and_then(|(_, exp)| exp.parse::<i32>().ok())
+        .unwrap_or(0);
+
+    if exponent >= min_exponent && exponent < max_exponent {
+        dest.truncate(start);
+        write_plain(dest);
+    }
+}
+
+macro_rules! impl_json_float_value {
+    ($($t:ty),* $(,)?) => {
+        $(
+            impl JsonFloatValue for $t {
+                fn write_with_float_format(&self, dest: &mut String, format: JsonFloatFormat) {
+                    // `NaN` / `Infinity` have no JSON form in any format
+                    if !self.is_finite() {
+                        dest.push_str("null");
+                        return;
+                    }
+
+                    match format {
+                        JsonFloatFormat::Display => {
+                            let _ = write!(dest, "{}", self);
+                        }
+                        JsonFloatFormat::Shortest => {
+                            let mut buffer = ryu::Buffer::new();
+                            dest.push_str(buffer.format_finite(*self));
+                        }
+                        JsonFloatFormat::Fixed(digits) => {
+                            let _ = write!(dest, "{:.*}", digits, self);
+                        }
+                        JsonFloatFormat::Scientific {
+                            min_exponent,
+                            max_exponent,
+                        } => write_scientific(
+                            dest,
+                            min_exponent,
+                            max_exponent,
+                            |dest| {
+                                let _ = write!(dest, "{:e}", self);
+                            },
+                            |dest| {
+                                let _ = write!(dest, "{}", self);
+                            },
+                        ),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_json_float_value!(f32, f64);
+
+// A `Decimal` is exact, so `Shortest` is simply its normalized form (trailing zeros dropped).
+#[cfg(feature = "decimal")]
+impl JsonFloatValue for rust_decimal::Decimal {
+    fn write_with_float_format(&self, dest: &mut String, format: JsonFloatFormat) {
+        match format {
+            JsonFloatFormat::Display => {
+                let _ = write!(dest, "{}", self);
+            }
+            JsonFloatFormat::Shortest => {
+                let _ = write!(dest, "{}", self.normalize());
+            }
+            JsonFloatFormat::Fixed(digits) => {
+                let _ = write!(dest, "{:.*}", digits, self);
+            }
+            JsonFloatFormat::Scientific {
+                min_exponent,
+                max_exponent,
+            } => write_scientific(
+                dest,
+                min_exponent,
+                max_exponent,
+                |dest| {
+                    let _ = write!(dest, "{:e}", self);
+                },
+                |dest| {
+                    let _ = write!(dest, "{}", self.normalize());
+                },
+            ),
+        }
+    }
+}
+
+/// A float written with its own [`JsonFloatFormat`], whatever the writer's options are:
+///
+/// ```ignore
+/// JsonObjectWriter::new()
+///     .write("bid", JsonFloat::fixed(bid, digits))
+///     .write("volume", JsonFloat::shortest(volume))
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct JsonFloat<T: JsonFloatValue> {
+    pub value: T,
+    pub format: JsonFloatFormat,
+}
+
+impl<T: JsonFloatValue> JsonFloat<T> {
+    pub fn new(value: T, format: JsonFloatFormat) -> Self {
+        Self { value, format }
+    }
+
+    pub fn shortest(value: T) -> Self {
+        Self::new(value, JsonFloatFormat::Shortest)
+    }
+
+    pub fn fixed(value: T, digits: usize) -> Self {
+        Self::new(value, JsonFloatFormat::Fixed(digits))
+    }
+
+    pub fn scientific(value: T, min_exponent: i32, max_exponent: i32) -> Self {
+        Self::new(
+            value,
+            JsonFloatFormat::Scientific {
+                min_exponent,
+                max_exponent,
+            },
+        )
+    }
+}
+
+impl<T: JsonFloatValue> JsonValueWriter for JsonFloat<T> {
+    const IS_ARRAY: bool = false;
+    fn write(&self, dest: &mut String) {
+        self.value.write_with_float_format(dest, self.format);
+    }
+}
+
+impl<T: JsonFloatValue> JsonValueWriter for Option<JsonFloat<T>> {
+    const IS_ARRAY: bool = false;
+    fn write(&self, dest: &mut String) {
+        match self {
+            Some(v) => v.write(dest),
+            None => dest.push_str("null"),
+        }
+    }
+}
+
+/// An integer that is written as a JSON string once it leaves JavaScript's safe range
+/// (`±(2^53 - 1)`), and as a plain number otherwise - the per-field form of
+/// [`JsonNumberFormat::big_integers_as_strings`].
+#[derive(Debug, Clone, Copy)]
+pub struct JsonSafeInteger<T>(pub T);
+
+pub(crate) fn write_integer<T: Copy + std::fmt::Display>(
+    dest: &mut String,
+    value: T,
+    big_integers_as_strings: bool,
+) where
+    i128: TryFrom<T>,
+{
+    let is_big = big_integers_as_strings
+        && i128::try_from(value).map_or(true, |v| v.unsigned_abs() > MAX_SAFE_INTEGER as u128);
+
+    if is_big {
+        let _ = write!(dest, "\"{}\"", value);
+    } else {
+        let _ = write!(dest, "{}", value);
+    }
+}
+
+macro_rules! impl_json_safe_integer {
+    ($($t:ty),* $(,)?) => {
+        $(
+            impl JsonValueWriter for JsonSafeInteger<$t> {
+                const IS_ARRAY: bool = false;
+                fn write(&self, dest: &mut String) {
+                    write_integer(dest, self.0, true);
+                }
+            }
+
+            impl JsonValueWriter for Option<JsonSafeInteger<$t>> {
+                const IS_ARRAY: bool = false;
+                fn write(&self, dest: &mut String) {
+                    match self {
+                        Some(v) => v.write(dest),
+                        None => dest.push_str("null"),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_json_safe_integer!(u8, i8, u16, i16, u32, i32, u64, i64, u128, i128, usize, isize);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::json_writer::{JsonArrayWriter, JsonObjectWriter};
+
+    #[test]
+    fn test_shortest_round_trips() {
+        let result = JsonObjectWriter::new()
+            .write("a", JsonFloat::shortest(0.1f64))
+            .write("b", JsonFloat::shortest(3.0f64))
+            .write("c", JsonFloat::shortest(1e300f64))
+            .write("d", JsonFloat::shortest(0.1f32))
+            .write("e", JsonFloat::shortest(f64::NAN))
+            .build();
+
+        assert_eq!(result, r#"{"a":0.1,"b":3.0,"c":1e300,"d":0.1,"e":null}"#);
+    }
+
+    #[test]
+    fn test_fixed_precision_per_field() {
+        let result = JsonObjectWriter::new()
+            .write("AUDCHF", JsonFloat::fixed(0.5712f64, 5))
+            .write("AUDJPY", JsonFloat::fixed(97.1235f64, 3))
+            .write("none", Option::<JsonFloat<f64>>::None)
+            .build();
+
+        assert_eq!(result, r#"{"AUDCHF":0.57120,"AUDJPY":97.124,"none":null}"#);
+    }
+
+    #[test]
+    fn test_scientific_thresholds() {
+        let js_like = |v: f64| JsonFloat::scientific(v, -6, 21);
+
+        let result = JsonArrayWriter::new()
+            .write(js_like(0.000001))
+            .write(js_like(0.0000001))
+            .write(js_like(123.5))
+            .write(js_like(1e21))
+            .write(js_like(-2.5e25))
+            .write(js_like(0.0))
+            .build();
+
+        assert_eq!(result, "[0.000001,1e-7,123.5,1e21,-2.5e25,0]");
+    }
+
+    #[test]
+    fn test_safe_integer_wrapper() {
+        let result = JsonObjectWriter::new()
+            .write("small", JsonSafeInteger(MAX_SAFE_INTEGER))
+            .write("big", JsonSafeInteger(MAX_SAFE_INTEGER + 2))
+            .write("neg", JsonSafeInteger(-(MAX_SAFE_INTEGER as i64) - 1))
+            .write("huge", JsonSafeInteger(u128::MAX))
+            .build();
+
+        assert_eq!(
+            result,
+            r#"{"small":9007199254740991,"big":"9007199254740993","neg":"-9007199254740992","huge":"340282366920938463463374607431768211455"}"#
+        );
+    }
+
+    #[test]
+    fn test_writer_options_reach_nested_values() {
+        let format = JsonNumberFormat::new()
+            .with_float_format(JsonFloatFormat::Fixed(2))
+            .with_big_integers_as_strings();
+
+        let result = JsonObjectWriter::new()
+            .with_number_format(format)
+            .write("price", 1.5f64)
+            .write("maybe", Some(2f32))
+            .write("id", u64::MAX)
+            .write("count", 3)
+            .write("list", vec![0.125f64, 1.0])
+            .write_json_object("inner", |o| o.write("v", 1.0f64))
+            .write_json_array("arr", |a| a.write(2.0f64).write(i64::MIN))
+            .write("explicit", JsonFloat::shortest(1.0f64))
+            .build();
+
+        assert_eq!(
+            result,
+            r#"{"price":1.50,"maybe":2.00,"id":"18446744073709551615","count":3,"list":[0.12,1.00],"inner":{"v":1.00},"arr":[2.00,"-9223372036854775808"],"explicit":1.0}"#
+        );
+    }
+
+    #[test]
+    fn test_default_options_change_nothing() {
+        let plain = JsonObjectWriter::new()
+            .write("a", 4.5f64)
+            .write("b", 0.0f64)
+            .write("c", u64::MAX)
+            .build();
+
+        let with_defaults = JsonObjectWriter::new()
+            .with_number_format(JsonNumberFormat::default())
+            .write("a", 4.5f64)
+            .write("b", 0.0f64)
+            .write("c", u64::MAX)
+            .build();
+
+        assert_eq!(plain, with_defaults);
+    }
+
+    #[cfg(feature = "decimal")]
+    #[test]
+    fn test_decimal_formats() {
+        use std::str::FromStr;
+
+        let price = rust_decimal::Decimal::from_str("1.2500").unwrap();
+
+        let result = JsonObjectWriter::new()
+            .write("display", price)
+            .write("shortest", JsonFloat::shortest(price))
+            .write("fixed", JsonFloat::fixed(price, 1))
+            .build();
+
+        assert_eq!(result, r#"{"display":1.2500,"shortest":1.25,"fixed":1.2}"#);
+    }
+}
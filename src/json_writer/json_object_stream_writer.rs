@@ -0,0 +1,215 @@
+use std::io::Write;
+
+use super::*;
+
+/// Streams a JSON object straight into an [`std::io::Write`] - the object counterpart of
+/// [`JsonArrayStreamWriter`]. The `{` goes out in [`Self::new`], each `"key":value` pair is
+/// written through as soon as it is added, and [`Self::finish`] writes the closing `}`.
+///
+/// Values follow the `JsonObjectWriter::write` contract exactly, `IS_ARRAY` wrapping included.
+///
+/// Dropping the writer without calling [`Self::finish`] leaves the output unterminated.
+pub struct JsonObjectStreamWriter<TWrite: Write> {
+    dest: TWrite,
+    buffer: String,
+    first_element: bool,
+    options: JsonWriterOptions,
+}
+
+impl<TWrite: Write> JsonObjectStreamWriter<TWrite> {
+    pub fn new(mut dest: TWrite) -> std::io::Result<Self> {
+        dest.write_all(b"{")?;
+
+        Ok(Self {
+            dest,
+            buffer: String::new(),
+            first_element: true,
+            options: JsonWriterOptions::default(),
+        })
+    }
+
+    /// Options for every value written from here on - see [`JsonWriterOptions`].
+    pub fn with_options(mut self, options: JsonWriterOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    fn add_delimiter(&mut self) {
+        if self.first_element {
+            self.first_element = false;
+        } else {
+            self.buffer.push(',');
+        }
+    }
+
+    fn write_key(&mut self, key: &str) {
+        self.buffer.push('"');
+        crate::json_string_value::write_escaped_json_string_value_with_mode(
+            key,
+            self.options.escape_mode,
+            &mut self.buffer,
+        );
+        self.buffer.push_str("\":");
+    }
+
+    fn flush_element(&mut self) -> std::io::Result<()> {
+        let result = self.dest.write_all(self.buffer.as_bytes());
+        self.buffer.clear();
+        result
+    }
+
+    pub fn write<TJsonValue: JsonValueWriter>(
+        &mut self,
+        key: &str,
+        value: TJsonValue,
+    ) -> std::io::Result<()> {
+        self.write_ref(key, &value)
+    }
+
+    pub fn write_ref<TJsonValue: JsonValueWriter>(
+        &mut self,
+        key: &str,
+        value: &TJsonValue,
+    ) -> std::io::Result<()> {
+        self.add_delimiter();
+        self.write_key(key);
+
+        if TJsonValue::IS_ARRAY {
+            self.buffer.push('[');
+        }
+
+        value.write_with_options(&mut self.buffer, &self.options);
+
+        if TJsonValue::IS_ARRAY {
+            self.buffer.push(']');
+        }
+
+        self.flush_element()
+    }
+
+    pub fn write_if_some<TJsonValue: JsonValueWriter>(
+        &mut self,
+        key: &str,
+        value: Option<TJsonValue>,
+    ) -> std::io::Result<()> {
+        let Some(value) = value else {
+            return Ok(());
+        };
+
+        self.write_ref(key, &value)
+    }
+
+    pub fn write_json_object(
+        &mut self,
+        key: &str,
+        write_object: impl Fn(JsonObjectWriter) -> JsonObjectWriter,
+    ) -> std::io::Result<()> {
+        self.add_delimiter();
+        self.write_key(key);
+
+        let raw = std::mem::take(&mut self.buffer);
+        self.buffer =
+            write_object(JsonObjectWriter::from_string(raw).with_options(self.options)).build();
+
+        self.flush_element()
+    }
+
+    pub fn write_json_array(
+        &mut self,
+        key: &str,
+        write_array: impl Fn(JsonArrayWriter) -> JsonArrayWriter,
+    ) -> std::io::Result<()> {
+        self.add_delimiter();
+        self.write_key(key);
+
+        let raw = std::mem::take(&mut self.buffer);
+        self.buffer =
+            write_array(JsonArrayWriter::from_string(raw).with_options(self.options)).build();
+
+        self.flush_element()
+    }
+
+    /// Opens an array under `key` and streams its elements through the returned writer, which
+    /// borrows this one's destination. Call [`JsonArrayStreamWriter::finish`] on it to close the
+    /// array before writing the next key - so an unbounded array can sit inside an object
+    /// (`{"meta":..,"data":[ ... ]}`) without either of them being buffered.
+    pub fn start_json_array(
+        &mut self,
+        key: &str,
+    ) -> std::io::Result<JsonArrayStreamWriter<&mut TWrite>> {
+        self.add_delimiter();
+        self.write_key(key);
+        self.flush_element()?;
+
+        let options = self.options;
+        Ok(JsonArrayStreamWriter::new(&mut self.dest)?.with_options(options))
+    }
+
+    pub fn has_written(&self) -> bool {
+        !self.first_element
+    }
+
+    /// Writes the closing `}`, flushes the destination and hands it back.
+    pub fn finish(mut self) -> std::io::Result<TWrite> {
+        self.dest.write_all(b"}")?;
+        self.dest.flush()?;
+        Ok(self.dest)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::JsonObjectStreamWriter;
+
+    #[test]
+    fn test_empty_object() {
+        let writer = JsonObjectStreamWriter::new(Vec::new()).unwrap();
+        assert_eq!(writer.finish().unwrap(), b"{}");
+    }
+
+    #[test]
+    fn test_matches_in_memory_object_writer() {
+        let mut writer = JsonObjectStreamWriter::new(Vec::new()).unwrap();
+
+        writer.write("name", "Alice").unwrap();
+        writer.write("he said \"hi\"", 1).unwrap();
+        writer.write("nums", vec![1, 2, 3]).unwrap();
+        writer.write_if_some("skipped", Option::<u8>::None).unwrap();
+        writer
+            .write_json_object("profile", |o| o.write("age", 30))
+            .unwrap();
+        writer.write_json_array("tags", |a| a.write("a")).unwrap();
+
+        let streamed = String::from_utf8(writer.finish().unwrap()).unwrap();
+
+        let in_memory = super::JsonObjectWriter::new()
+            .write("name", "Alice")
+            .write("he said \"hi\"", 1)
+            .write("nums", vec![1, 2, 3])
+            .write_if_some("skipped", Option::<u8>::None)
+            .write_json_object("profile", |o| o.write("age", 30))
+            .write_json_array("tags", |a| a.write("a"))
+            .build();
+
+        assert_eq!(streamed, in_memory);
+    }
+
+    #[test]
+    fn test_streamed_array_inside_streamed_object() {
+        let mut writer = JsonObjectStreamWriter::new(Vec::new()).unwrap();
+        writer.write("total", 2).unwrap();
+
+        let mut data = writer.start_json_array("data").unwrap();
+        data.write_json_object(|o| o.write("id", 1)).unwrap();
+        data.write_json_object(|o| o.write("id", 2)).unwrap();
+
+        data.finish().unwrap();
+        writer.write("done", true).unwrap();
+
+        let result = String::from_utf8(writer.finish().unwrap()).unwrap();
+        assert_eq!(
+            result,
+            r#"{"total":2,"data":[{"id":1},{"id":2}],"done":true}"#
+        );
+    }
+}
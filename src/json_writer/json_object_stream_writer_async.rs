@@ -0,0 +1,179 @@
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+
+use super::*;
+
+/// Async twin of [`JsonObjectStreamWriter`] for a [`tokio::io::AsyncWrite`] destination.
+pub struct JsonObjectStreamWriterAsync<TWrite: AsyncWrite + Unpin> {
+    dest: TWrite,
+    buffer: String,
+    first_element: bool,
+    options: JsonWriterOptions,
+}
+
+impl<TWrite: AsyncWrite + Unpin> JsonObjectStreamWriterAsync<TWrite> {
+    pub async fn new(mut dest: TWrite) -> std::io::Result<Self> {
+        dest.write_all(b"{").await?;
+
+        Ok(Self {
+            dest,
+            buffer: String::new(),
+            first_element: true,
+            options: JsonWriterOptions::default(),
+        })
+    }
+
+    /// Options for every value written from here on - see [`JsonWriterOptions`].
+    pub fn with_options(mut self, options: JsonWriterOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    fn add_delimiter(&mut self) {
+        if self.first_element {
+            self.first_element = false;
+        } else {
+            self.buffer.push(',');
+        }
+    }
+
+    fn write_key(&mut self, key: &str) {
+        self.buffer.push('"');
+        crate::json_string_value::write_escaped_json_string_value_with_mode(
+            key,
+            self.options.escape_mode,
+            &mut self.buffer,
+        );
+        self.buffer.push_str("\":");
+    }
+
+    async fn flush_element(&mut self) -> std::io::Result<()> {
+        let result = self.dest.write_all(self.buffer.as_bytes()).await;
+        self.buffer.clear();
+        result
+    }
+
+    pub async fn write<TJsonValue: JsonValueWriter>(
+        &mut self,
+        key: &str,
+        value: TJsonValue,
+    ) -> std::io::Result<()> {
+        self.write_ref(key, &value).await
+    }
+
+    pub async fn write_ref<TJsonValue: JsonValueWriter>(
+        &mut self,
+        key: &str,
+        value: &TJsonValue,
+    ) -> std::io::Result<()> {
+        self.add_delimiter();
+        self.write_key(key);
+
+        if TJsonValue::IS_ARRAY {
+            self.buffer.push('[');
+        }
+
+        value.write_with_options(&mut self.buffer, &self.options);
+
+        if TJsonValue::IS_ARRAY {
+            self.buffer.push(']');
+        }
+
+        self.flush_element().await
+    }
+
+    pub async fn write_if_some<TJsonValue: JsonValueWriter>(
+        &mut self,
+        key: &str,
+        value: Option<TJsonValue>,
+    ) -> std::io::Result<()> {
+        let Some(value) = value else {
+            return Ok(());
+        };
+
+        self.write_ref(key, &value).await
+    }
+
+    pub async fn write_json_object(
+        &mut self,
+        key: &str,
+        write_object: impl Fn(JsonObjectWriter) -> JsonObjectWriter,
+    ) -> std::io::Result<()> {
+        self.add_delimiter();
+        self.write_key(key);
+
+        let raw = std::mem::take(&mut self.buffer);
+        self.buffer =
+            write_object(JsonObjectWriter::from_string(raw).with_options(self.options)).build();
+
+        self.flush_element().await
+    }
+
+    pub async fn write_json_array(
+        &mut self,
+        key: &str,
+        write_array: impl Fn(JsonArrayWriter) -> JsonArrayWriter,
+    ) -> std::io::Result<()> {
+        self.add_delimiter();
+        self.write_key(key);
+
+        let raw = std::mem::take(&mut self.buffer);
+        self.buffer =
+            write_array(JsonArrayWriter::from_string(raw).with_options(self.options)).build();
+
+        self.flush_element().await
+    }
+
+    /// Opens an array under `key`; see [`JsonObjectStreamWriter::start_json_array`].
+    pub async fn start_json_array(
+        &mut self,
+        key: &str,
+    ) -> std::io::Result<JsonArrayStreamWriterAsync<&mut TWrite>> {
+        self.add_delimiter();
+        self.write_key(key);
+        self.flush_element().await?;
+
+        let options = self.options;
+        Ok(JsonArrayStreamWriterAsync::new(&mut self.dest)
+            .await?
+            .with_options(options))
+    }
+
+    pub fn has_written(&self) -> bool {
+        !self.first_element
+    }
+
+    /// Writes the closing `}`, flushes the destination and hands it back.
+    pub async fn finish(mut self) -> std::io::Result<TWrite> {
+        self.dest.write_all(b"}").await?;
+        self.dest.flush().await?;
+        Ok(self.dest)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::JsonObjectStreamWriterAsync;
+
+    #[tokio::test]
+    async fn test_streamed_array_inside_streamed_object() {
+        let mut writer = JsonObjectStreamWriterAsync::new(Vec::new()).await.unwrap();
+        writer.write("total", 2).await.unwrap();
+
+        let mut data = writer.start_json_array("data").await.unwrap();
+        data.write_json_object(|o| o.write("id", 1)).await.unwrap();
+        data.write_json_object(|o| o.write("id", 2)).await.unwrap();
+        data.finish().await.unwrap();
+
+        writer
+            .write_if_some("skipped", Option::<u8>::None)
+            .await
+            .unwrap();
+        writer.write("done", true).await.unwrap();
+
+        let result = String::from_utf8(writer.finish().await.unwrap()).unwrap();
+        assert_eq!(
+            result,
+            r#"{"total":2,"data":[{"id":1},{"id":2}],"done":true}"#
+        );
+    }
+}
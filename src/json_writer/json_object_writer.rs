@@ -3,6 +3,7 @@ use super::*;
 pub struct JsonObjectWriter {
     first_element: bool,
     raw: Option<String>,
+    options: JsonWriterOptions,
 }
 
 impl JsonObjectWriter {
@@ -13,6 +14,7 @@ impl JsonObjectWriter {
         Self {
             first_element: true,
             raw: Some(raw),
+            options: JsonWriterOptions::default(),
         }
     }
 
@@ -21,9 +23,27 @@ impl JsonObjectWriter {
         Self {
             first_element: true,
             raw: Some(raw),
+            options: JsonWriterOptions::default(),
         }
     }
 
+    /// Options for every value written from here on, nested objects and arrays included - see
+    /// [`JsonWriterOptions`].
+    pub fn with_options(mut self, options: JsonWriterOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    pub fn with_number_format(mut self, number_format: JsonNumberFormat) -> Self {
+        self.options.number_format = number_format;
+        self
+    }
+
+    pub fn with_escape_mode(mut self, escape_mode: JsonEscapeMode) -> Self {
+        self.options.escape_mode = escape_mode;
+        self
+    }
+
     pub fn has_written(&self) -> bool {
         !self.first_element
     }
@@ -40,7 +60,11 @@ impl JsonObjectWriter {
         let raw = self.raw.as_mut().unwrap();
         raw.push('"');
 
-        crate::json_string_value::write_escaped_json_string_value(key, raw);
+        crate::json_string_value::write_escaped_json_string_value_with_mode(
+            key,
+            self.options.escape_mode,
+            raw,
+        );
         raw.push_str("\":");
     }
 
@@ -54,7 +78,7 @@ impl JsonObjectWriter {
 
         let raw = self.raw.take().unwrap();
 
-        let mut json_object_writer = Self::from_string(raw);
+        let mut json_object_writer = Self::from_string(raw).with_options(self.options);
 
         json_object_writer = write_object(json_object_writer);
 
@@ -74,7 +98,8 @@ impl JsonObjectWriter {
 
         let raw = self.raw.take().unwrap();
 
-        let json_array_writer = write_array(JsonArrayWriter::from_string(raw));
+        let json_array_writer =
+            write_array(JsonArrayWriter::from_string(raw).with_options(self.options));
 
         let raw = json_array_writer.build();
         self.raw = Some(raw);
@@ -111,7 +136,7 @@ impl JsonObjectWriter {
             if no > 0 {
                 raw.push(',');
             }
-            itm.write(&mut raw);
+            itm.write_with_options(&mut raw, &self.options);
             no += 1;
         }
         raw.push(']');
@@ -141,7 +166,7 @@ impl JsonObjectWriter {
             raw.push('[');
         }
 
-        value.write(raw);
+        value.write_with_options(raw, &self.options);
 
         if TJsonValue::IS_ARRAY {
             raw.push(']');
@@ -160,7 +185,7 @@ impl JsonObjectWriter {
             raw.push('[');
         }
 
-        value.write(raw);
+        value.write_with_options(raw, &self.options);
 
         if TJsonValue::IS_ARRAY {
             raw.push(']');
@@ -187,7 +212,7 @@ impl JsonObjectWriter {
             raw.push('[');
         }
 
-        value.write(raw);
+        value.write_with_options(raw, &self.options);
 
         if TJsonValue::IS_ARRAY {
             raw.push(']');
@@ -233,6 +258,33 @@ impl JsonObjectWriter {
         self.write_ref(key, value)
     }
 
+    /// Splices the fields of an object-valued `value` into this object, as if each had been
+    /// written here directly. Anything that does not write an object (e.g. a `None`, which
+    /// writes `null`) contributes nothing.
+    pub fn write_flattened<TJsonValue: JsonValueWriter>(mut self, value: &TJsonValue) -> Self {
+        if TJsonValue::IS_ARRAY {
+            return self;
+        }
+
+        let mut buffer = String::new();
+        value.write_with_options(&mut buffer, &self.options);
+
+        let fields = buffer
+            .trim()
+            .strip_prefix('{')
+            .and_then(|body| body.strip_suffix('}'))
+            .map(|body| body.trim());
+
+        if let Some(fields) = fields {
+            if !fields.is_empty() {
+                self.add_delimiter();
+                self.raw.as_mut().unwrap().push_str(fields);
+            }
+        }
+
+        self
+    }
+
     pub fn build(mut self) -> String {
         let mut raw = self.raw.take().unwrap();
         raw.push('}');
@@ -327,4 +379,21 @@ mod tests {
 
         assert_eq!("{\"key1\":\"value1\"}", result);
     }
+
+    #[test]
+    fn test_write_flattened() {
+        let inner = super::JsonObjectWriter::new()
+            .write("b", 2)
+            .write("c", "x");
+
+        let result = super::JsonObjectWriter::new()
+            .write("a", 1)
+            .write_flattened(&inner)
+            .write_flattened(&super::JsonObjectWriter::new())
+            .write_flattened(&Option::<i32>::None)
+            .write("d", true)
+            .build();
+
+        assert_eq!(r#"{"a":1,"b":2,"c":"x","d":true}"#, result);
+    }
 }
@@ -0,0 +1,121 @@
+use std::io::Write;
+
+use super::*;
+use crate::consts::RECORD_SEPARATOR;
+
+/// Writes an RFC 7464 JSON text sequence (`application/json-seq`) into an [`std::io::Write`]:
+/// every value is written as the `0x1E` record separator, the value and a line break.
+///
+/// Every record is written through as soon as it is added. Unlike JSON-L, a value may span lines,
+/// so it is written exactly as rendered.
+pub struct JsonSeqWriter<TWrite: Write> {
+    dest: TWrite,
+    buffer: String,
+    options: JsonWriterOptions,
+}
+
+impl<TWrite: Write> JsonSeqWriter<TWrite> {
+    pub fn new(dest: TWrite) -> Self {
+        Self {
+            dest,
+            buffer: String::new(),
+            options: JsonWriterOptions::default(),
+        }
+    }
+
+    /// Options for every value written from here on - see [`JsonWriterOptions`].
+    pub fn with_options(mut self, options: JsonWriterOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    pub fn write<TJsonValue: JsonValueWriter>(&mut self, value: TJsonValue) -> std::io::Result<()> {
+        self.write_ref(&value)
+    }
+
+    /// An array-valued record (`T::IS_ARRAY`, e.g. a `Vec<T>`) is written as one JSON array.
+    pub fn write_ref<TJsonValue: JsonValueWriter>(
+        &mut self,
+        value: &TJsonValue,
+    ) -> std::io::Result<()> {
+        self.buffer.push(RECORD_SEPARATOR as char);
+
+        if TJsonValue::IS_ARRAY {
+            self.buffer.push('[');
+        }
+
+        value.write_with_options(&mut self.buffer, &self.options);
+
+        if TJsonValue::IS_ARRAY {
+            self.buffer.push(']');
+        }
+
+        self.flush_record()
+    }
+
+    /// Writes every item of `values` as a separate record.
+    pub fn write_iter<TJsonValue: JsonValueWriter>(
+        &mut self,
+        values: impl Iterator<Item = TJsonValue>,
+    ) -> std::io::Result<()> {
+        for itm in values {
+            self.write(itm)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn write_json_object(
+        &mut self,
+        write_object: impl Fn(JsonObjectWriter) -> JsonObjectWriter,
+    ) -> std::io::Result<()> {
+        let mut raw = std::mem::take(&mut self.buffer);
+        raw.push(RECORD_SEPARATOR as char);
+
+        self.buffer =
+            write_object(JsonObjectWriter::from_string(raw).with_options(self.options)).build();
+
+        self.flush_record()
+    }
+
+    // The line break also ends a trailing number, which a reader would otherwise have to drop as
+    // possibly truncated.
+    fn flush_record(&mut self) -> std::io::Result<()> {
+        self.buffer.push('\n');
+        let result = self.dest.write_all(self.buffer.as_bytes());
+        self.buffer.clear();
+        result
+    }
+
+    /// Flushes the destination and hands it back.
+    pub fn finish(mut self) -> std::io::Result<TWrite> {
+        self.dest.flush()?;
+        Ok(self.dest)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rust_extensions::array_of_bytes_iterator::SliceIterator;
+
+    use super::JsonSeqWriter;
+    use crate::json_reader::JsonSeqIterator;
+
+    #[test]
+    fn test_written_sequence_reads_back() {
+        let mut writer = JsonSeqWriter::new(Vec::new());
+
+        writer.write_json_object(|o| o.write("id", 1)).unwrap();
+        writer.write(vec![1, 2]).unwrap();
+        writer.write(42).unwrap();
+
+        let result = writer.finish().unwrap();
+        assert_eq!(result, b"\x1E{\"id\":1}\n\x1E[1,2]\n\x1E42\n");
+
+        let mut iterator = JsonSeqIterator::new(SliceIterator::new(&result));
+        assert_eq!(iterator.get_next().unwrap().unwrap(), b"{\"id\":1}");
+        assert_eq!(iterator.get_next().unwrap().unwrap(), b"[1,2]");
+        assert_eq!(iterator.get_next().unwrap().unwrap(), b"42");
+        assert!(iterator.get_next().is_none());
+    }
+}
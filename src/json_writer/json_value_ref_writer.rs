@@ -0,0 +1,870 @@
+use crate::json_reader::{JsonNumber, JsonParseError, JsonValue, JsonValueRef, UnwrappedJsonValue};
+
+use super::{JsonValueWriter, JsonWriterOptions, RawJsonObject};
+
+// A value read from one document can be written straight into another. The source bytes go out
+// as they are - whitespace and all - when they are strictly valid JSON and the writer's options
+// would not change them. Anything else is re-rendered value by value: a token only the lenient
+// parser accepts (a single-quoted or bare string, a `+1`) comes out as proper JSON, and the
+// writer's escape mode and number format reach every nested string and number.
+//
+// `RawJsonObject::from(&value)` copies the bytes unchecked; `CheckedJsonValue` checks them and
+// can minify.
+impl<'s> JsonValueWriter for JsonValueRef<'s> {
+    const IS_ARRAY: bool = false;
+    fn write(&self, dest: &mut String) {
+        self.write_with_options(dest, &JsonWriterOptions::default())
+    }
+
+    fn write_with_options(&self, dest: &mut String, options: &JsonWriterOptions) {
+        let slice = self.as_slice();
+
+        if *options == JsonWriterOptions::default() && is_strict_json(slice) {
+            dest.push_str(String::from_utf8_lossy(slice).as_ref());
+            return;
+        }
+
+        write_rendered(slice, dest, |dest| {
+            write_unwrapped(&self.unwrap_value()?, dest, options, 0)
+        });
+    }
+}
+
+impl<'s> JsonValueWriter for &'s JsonValueRef<'s> {
+    const IS_ARRAY: bool = false;
+    fn write(&self, dest: &mut String) {
+        JsonValueRef::write(self, dest)
+    }
+
+    fn write_with_options(&self, dest: &mut String, options: &JsonWriterOptions) {
+        JsonValueRef::write_with_options(self, dest, options)
+    }
+}
+
+// Scalars are re-rendered from their parsed value - a number keeps the digits it was read with,
+// however long or precise - and arrays and objects go out as the source bytes under the same rule
+// as a `JsonValueRef`. `String` holds the raw token: a strictly valid double-quoted one goes out
+// as is with the default options, anything else is de-escaped and written as a JSON string.
+impl<'s> JsonValueWriter for UnwrappedJsonValue<'s> {
+    const IS_ARRAY: bool = false;
+    fn write(&self, dest: &mut String) {
+        self.write_with_options(dest, &JsonWriterOptions::default())
+    }
+
+    fn write_with_options(&self, dest: &mut String, options: &JsonWriterOptions) {
+        match self {
+            UnwrappedJsonValue::Array(value) => {
+                write_container(value.as_slice(), dest, options);
+            }
+            UnwrappedJsonValue::Object(value) => {
+                write_container(value.as_slice(), dest, options);
+            }
+            // Only an array or an object can fail to render.
+            _ => {
+                let _ = write_unwrapped(self, dest, options, 0);
+            }
+        }
+    }
+}
+
+// The raw copy, for a value that is known to be well formed.
+impl<'s> From<&JsonValueRef<'s>> for RawJsonObject<'s> {
+    fn from(value: &JsonValueRef<'s>) -> Self {
+        match std::str::from_utf8(value.as_slice()) {
+            Ok(value) => RawJsonObject::AsStr(value),
+            Err(_) => {
+                RawJsonObject::AsString(String::from_utf8_lossy(value.as_slice()).into_owned())
+            }
+        }
+    }
+}
+
+fn is_strict_json(slice: &[u8]) -> bool {
+    CheckedJsonValue::from_slice(slice, CheckedJsonValueMode::Verbatim).is_ok()
+}
+
+fn write_container(slice: &[u8], dest: &mut String, options: &JsonWriterOptions) {
+    if *options == JsonWriterOptions::default() && is_strict_json(slice) {
+        dest.push_str(String::from_utf8_lossy(slice).as_ref());
+        return;
+    }
+
+    write_rendered(slice, dest, |dest| write_items(slice, dest, options, 0));
+}
+
+// Renders into a buffer of its own, so a value that turns out to be malformed half way through
+// leaves nothing half written. There is no error to hand back from a writer: such a value - or
+// one nested too deep - goes out as a JSON string holding its source text, so the document stays
+// valid and nothing is dropped.
+fn write_rendered(
+    src: &[u8],
+    dest: &mut String,
+    render: impl FnOnce(&mut String) -> Result<(), JsonParseError>,
+) {
+    let mut rendered = String::new();
+
+    match render(&mut rendered) {
+        Ok(()) => dest.push_str(&rendered),
+        Err(_) => String::from_utf8_lossy(src).as_ref().write(dest),
+    }
+}
+
+fn write_unwrapped(
+    value: &UnwrappedJsonValue,
+    dest: &mut String,
+    options: &JsonWriterOptions,
+    depth: usize,
+) -> Result<(), JsonParseError> {
+    match value {
+        UnwrappedJsonValue::Null => dest.push_str("null"),
+        UnwrappedJsonValue::Boolean(value) => value.write(dest),
+        UnwrappedJsonValue::String(raw) => write_string_token(raw, dest, options),
+        UnwrappedJsonValue::Number(value) => write_number(value, dest, options),
+        UnwrappedJsonValue::Double(value) => write_number(value, dest, options),
+        UnwrappedJsonValue::Array(value) => write_items(value.as_slice(), dest, options, depth)?,
+        UnwrappedJsonValue::Object(value) => write_items(value.as_slice(), dest, options, depth)?,
+    }
+
+    Ok(())
+}
+
+// The elements of an array or the entries of an object, each one re-rendered.
+fn write_items(
+    slice: &[u8],
+    dest: &mut String,
+    options: &JsonWriterOptions,
+    depth: usize,
+) -> Result<(), JsonParseError> {
+    let mut renderer = LenientRenderer {
+        src: slice,
+        pos: 0,
+        options,
+    };
+
+    renderer.skip_white_spaces();
+    renderer.render_value(dest, depth)?;
+    renderer.skip_white_spaces();
+
+    if renderer.pos != renderer.src.len() {
+        return Err(renderer.error("unexpected data after the end of the value"));
+    }
+
+    Ok(())
+}
+
+// Walks an array or an object the way the lenient parser reads it - single-quoted and bare
+// strings, bare keys, a trailing comma - and writes it back as strict JSON. Each scalar token is
+// classified by `JsonValue::unwrap_value`, exactly as it would be when read.
+struct LenientRenderer<'s, 'o> {
+    src: &'s [u8],
+    pos: usize,
+    options: &'o JsonWriterOptions,
+}
+
+impl<'s, 'o> LenientRenderer<'s, 'o> {
+    fn error(&self, msg: &str) -> JsonParseError {
+        JsonParseError::new(format!("Invalid JSON at position {}: {}", self.pos, msg))
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.src.get(self.pos).copied()
+    }
+
+    fn skip_white_spaces(&mut self) {
+        while let Some(b' ' | b'\t' | b'\n' | b'\r') = self.peek() {
+            self.pos += 1;
+        }
+    }
+
+    fn render_value(&mut self, dest: &mut String, depth: usize) -> Result<(), JsonParseError> {
+        if depth >= MAX_DEPTH {
+            return Err(self.error("nesting is too deep"));
+        }
+
+        match self.peek() {
+            Some(b'{') => self.render_object(dest, depth),
+            Some(b'[') => self.render_array(dest, depth),
+            Some(_) => {
+                let token = self.read_token()?;
+                let value = JsonValue::new(0, token.len()).unwrap_value(&token)?;
+                write_unwrapped(&value, dest, self.options, depth)
+            }
+            None => Err(self.error("unexpected end of the value")),
+        }
+    }
+
+    fn render_object(&mut self, dest: &mut String, depth: usize) -> Result<(), JsonParseError> {
+        self.pos += 1;
+        dest.push('{');
+
+        let mut first = true;
+
+        loop {
+            self.skip_white_spaces();
+
+            if self.peek() == Some(b'}') {
+                self.pos += 1;
+                dest.push('}');
+                return Ok(());
+            }
+
+            if !first {
+                dest.push(',');
+            }
+            first = false;
+
+            let key = self.read_token()?;
+            write_string_token(as_utf8(key)?, dest, self.options);
+            self.skip_white_spaces();
+
+            if self.peek() != Some(b':') {
+                return Err(self.error("expected ':'"));
+            }
+            self.pos += 1;
+            dest.push(':');
+            self.skip_white_spaces();
+
+            self.render_value(dest, depth + 1)?;
+            self.skip_white_spaces();
+
+            match self.peek() {
+                Some(b',') => self.pos += 1,
+                Some(b'}') => {}
+                _ => return Err(self.error("expected ',' or '}'")),
+            }
+        }
+    }
+
+    fn render_array(&mut self, dest: &mut String, depth: usize) -> Result<(), JsonParseError> {
+        self.pos += 1;
+        dest.push('[');
+
+        let mut first = true;
+
+        loop {
+            self.skip_white_spaces();
+
+            if self.peek() == Some(b']') {
+                self.pos += 1;
+                dest.push(']');
+                return Ok(());
+            }
+
+            if !first {
+                dest.push(',');
+            }
+            first = false;
+
+            self.render_value(dest, depth + 1)?;
+            self.skip_white_spaces();
+
+            match self.peek() {
+                Some(b',') => self.pos += 1,
+                Some(b']') => {}
+                _ => return Err(self.error("expected ',' or ']'")),
+            }
+        }
+    }
+
+    // A quoted string with its quotes, or a bare word up to the next separator.
+    fn read_token(&mut self) -> Result<&'s [u8], JsonParseError> {
+        let start = self.pos;
+
+        if let Some(quote @ (b'"' | b'\'')) = self.peek() {
+            self.pos += 1;
+
+            loop {
+                match self.peek() {
+                    Some(b'\\') => self.pos += 2,
+                    Some(b) if b == quote => {
+                        self.pos += 1;
+                        return Ok(&self.src[start..self.pos]);
+                    }
+                    Some(_) => self.pos += 1,
+                    None => return Err(self.error("unterminated string")),
+                }
+            }
+        }
+
+        while let Some(b) = self.peek() {
+            if matches!(
+                b,
+                b',' | b':' | b'{' | b'}' | b'[' | b']' | b' ' | b'\t' | b'\n' | b'\r'
+            ) {
+                break;
+            }
+            self.pos += 1;
+        }
+
+        if self.pos == start {
+            return Err(self.error("unexpected character"));
+        }
+
+        Ok(&self.src[start..self.pos])
+    }
+}
+
+fn as_utf8(slice: &[u8]) -> Result<&str, JsonParseError> {
+    std::str::from_utf8(slice)
+        .map_err(|err| JsonParseError::new(format!("JSON value is not valid UTF-8. Err: {}", err)))
+}
+
+// A string token as the parser found it: double- or single-quoted, or a bare word.
+fn write_string_token(raw: &str, dest: &mut String, options: &JsonWriterOptions) {
+    if *options == JsonWriterOptions::default()
+        && raw.starts_with('"')
+        && is_strict_json(raw.as_bytes())
+    {
+        dest.push_str(raw);
+        return;
+    }
+
+    let value = match crate::json_utils::try_get_escaped_string_body(raw.as_bytes()) {
+        Some(body) => crate::json_string_value::de_escape_json_string_value(body),
+        None => raw.into(),
+    };
+
+    value.as_str().write_with_options(dest, options);
+}
+
+// The digits as they were read when they are a JSON number. A number only the lenient parser
+// accepts (`+1`, `.5`, `1.`, `007`) is given the JSON spelling of the same digits rather than
+// being rounded through an `f64`, and anything else goes out as a string.
+fn write_number(value: &JsonNumber, dest: &mut String, options: &JsonWriterOptions) {
+    if is_strict_json(value.as_str().as_bytes()) {
+        value.write_with_options(dest, options);
+        return;
+    }
+
+    let normalized = normalize_number(value.as_str());
+
+    match JsonNumber::new(&normalized) {
+        Some(number) if is_strict_json(normalized.as_bytes()) => {
+            number.write_with_options(dest, options)
+        }
+        _ => value.as_str().write_with_options(dest, options),
+    }
+}
+
+fn normalize_number(raw: &str) -> String {
+    let (sign, unsigned) = match raw.as_bytes().first() {
+        Some(b'-') => ("-", &raw[1..]),
+        Some(b'+') => ("", &raw[1..]),
+        _ => ("", raw),
+    };
+
+    let (mantissa, exponent) = match unsigned.find(['e', 'E']) {
+        Some(index) => unsigned.split_at(index),
+        None => (unsigned, ""),
+    };
+
+    let (int, frac) = match mantissa.find('.') {
+        Some(index) => (&mantissa[..index], &mantissa[index + 1..]),
+        None => (mantissa, ""),
+    };
+
+    let int = int.trim_start_matches('0');
+
+    let mut result = String::with_capacity(raw.len() + 2);
+    result.push_str(sign);
+    result.push_str(if int.is_empty() { "0" } else { int });
+
+    if !frac.is_empty() {
+        result.push('.');
+        result.push_str(frac);
+    }
+
+    result.push_str(exponent);
+    result
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CheckedJsonValueMode {
+    /// The source bytes exactly as they were, whitespace included.
+    Verbatim,
+    /// Whitespace between tokens dropped; string contents are left untouched.
+    Minified,
+}
+
+/// A read value that has been checked to be exactly one complete, strictly valid JSON value
+/// (RFC 8259 - no single quotes, bare words, trailing commas or trailing garbage) before it is
+/// written anywhere, so a malformed fragment can never corrupt the document it is forwarded into.
+///
+/// ```ignore
+/// let data = j_path::get_value(incoming, "data")?.unwrap();
+/// let json = JsonObjectWriter::new()
+///     .write("forwarded", CheckedJsonValue::minified(&data)?)
+///     .build();
+/// ```
+pub struct CheckedJsonValue<'s> {
+    value: &'s str,
+    mode: CheckedJsonValueMode,
+}
+
+impl<'s> CheckedJsonValue<'s> {
+    pub fn new(
+        value: &JsonValueRef<'s>,
+        mode: CheckedJsonValueMode,
+    ) -> Result<Self, JsonParseError> {
+        Self::from_slice(value.as_slice(), mode)
+    }
+
+    pub fn verbatim(value: &JsonValueRef<'s>) -> Result<Self, JsonParseError> {
+        Self::new(value, CheckedJsonValueMode::Verbatim)
+    }
+
+    pub fn minified(value: &JsonValueRef<'s>) -> Result<Self, JsonParseError> {
+        Self::new(value, CheckedJsonValueMode::Minified)
+    }
+
+    /// Checks raw JSON text that did not come from the parser.
+    pub fn from_slice(value: &'s [u8], mode: CheckedJsonValueMode) -> Result<Self, JsonParseError> {
+        let value = match std::str::from_utf8(value) {
+            Ok(value) => value,
+            Err(err) => {
+                return Err(JsonParseError::new(format!(
+                    "JSON value is not valid UTF-8. Err: {}",
+                    err
+                )))
+            }
+        };
+
+        let mut validator = StrictValidator {
+            src: value.as_bytes(),
+            pos: 0,
+        };
+
+        validator.skip_white_spaces();
+        validator.check_value(0)?;
+        validator.skip_white_spaces();
+
+        if validator.pos != validator.src.len() {
+            return Err(validator.error("unexpected data after the end of the value"));
+        }
+
+        let value = value.trim_matches(|c| matches!(c, ' ' | '\t' | '\n' | '\r'));
+        Ok(Self { value, mode })
+    }
+
+    pub fn as_str(&self) -> &'s str {
+        self.value
+    }
+}
+
+impl<'s> JsonValueWriter for CheckedJsonValue<'s> {
+    const IS_ARRAY: bool = false;
+    fn write(&self, dest: &mut String) {
+        match self.mode {
+            CheckedJsonValueMode::Verbatim => dest.push_str(self.value),
+            CheckedJsonValueMode::Minified => write_minified(self.value, dest),
+        }
+    }
+}
+
+// Only runs on text the validator accepted, so tracking "inside a string" and "after a
+// backslash" is enough to tell insignificant whitespace from string content.
+fn write_minified(src: &str, dest: &mut String) {
+    let mut in_string = false;
+    let mut escaped = false;
+    let mut run_start = 0;
+
+    for (index, b) in src.bytes().enumerate() {
+        if in_string {
+            if escaped {
+                escaped = false;
+            } else if b == b'\\' {
+                escaped = true;
+            } else if b == b'"' {
+                in_string = false;
+            }
+            continue;
+        }
+
+        match b {
+            b'"' => in_string = true,
+            b' ' | b'\t' | b'\n' | b'\r' => {
+                dest.push_str(&src[run_start..index]);
+                run_start = index + 1;
+            }
+            _ => {}
+        }
+    }
+
+    dest.push_str(&src[run_start..]);
+}
+
+// Deep enough for any real document, shallow enough that hostile nesting can not overflow the
+// stack of the (recursive) validator.
+const MAX_DEPTH: usize = 512;
+
+struct StrictValidator<'s> {
+    src: &'s [u8],
+    pos: usize,
+}
+
+impl<'s> StrictValidator<'s> {
+    fn error(&self, msg: &str) -> JsonParseError {
+        JsonParseError::new(format!("Invalid JSON at position {}: {}", self.pos, msg))
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.src.get(self.pos).copied()
+    }
+
+    fn skip_white_spaces(&mut self) {
+        while let Some(b' ' | b'\t' | b'\n' | b'\r') = self.peek() {
+            self.pos += 1;
+        }
+    }
+
+    fn expect_literal(&mut self, literal: &[u8]) -> Result<(), JsonParseError> {
+        if self.src[self.pos..].starts_with(literal) {
+            self.pos += literal.len();
+            Ok(())
+        } else {
+            Err(self.error("unknown literal"))
+        }
+    }
+
+    fn check_value(&mut self, depth: usize) -> Result<(), JsonParseError> {
+        if depth > MAX_DEPTH {
+            return Err(self.error("nesting is too deep"));
+        }
+
+        match self.peek() {
+            Some(b'{') => self.check_object(depth),
+            Some(b'[') => self.check_array(depth),
+            Some(b'"') => self.check_string(),
+            Some(b't') => self.expect_literal(b"true"),
+            Some(b'f') => self.expect_literal(b"false"),
+            Some(b'n') => self.expect_literal(b"null"),
+            Some(b'-' | b'0'..=b'9') => self.check_number(),
+            Some(_) => Err(self.error("unexpected character")),
+            None => Err(self.error("unexpected end of the value")),
+        }
+    }
+
+    fn check_object(&mut self, depth: usize) -> Result<(), JsonParseError> {
+        self.pos += 1;
+        self.skip_white_spaces();
+
+        if self.peek() == Some(b'}') {
+            self.pos += 1;
+            return Ok(());
+        }
+
+        loop {
+            if self.peek() != Some(b'"') {
+                return Err(self.error("expected a quoted key"));
+            }
+            self.check_string()?;
+            self.skip_white_spaces();
+
+            if self.peek() != Some(b':') {
+                return Err(self.error("expected ':'"));
+            }
+            self.pos += 1;
+            self.skip_white_spaces();
+
+            self.check_value(depth + 1)?;
+            self.skip_white_spaces();
+
+            match self.peek() {
+                Some(b',') => {
+                    self.pos += 1;
+                    self.skip_white_spaces();
+                }
+                Some(b'}') => {
+                    self.pos += 1;
+                    return Ok(());
+                }
+                _ => return Err(self.error("expected ',' or '}'")),
+            }
+        }
+    }
+
+    fn check_array(&mut self, depth: usize) -> Result<(), JsonParseError> {
+        self.pos += 1;
+        self.skip_white_spaces();
+
+        if self.peek() == Some(b']') {
+            self.pos += 1;
+            return Ok(());
+        }
+
+        loop {
+            self.check_value(depth + 1)?;
+            self.skip_white_spaces();
+
+            match self.peek() {
+                Some(b',') => {
+                    self.pos += 1;
+                    self.skip_white_spaces();
+                }
+                Some(b']') => {
+                    self.pos += 1;
+                    return Ok(());
+                }
+                _ => return Err(self.error("expected ',' or ']'")),
+            }
+        }
+    }
+
+    fn check_string(&mut self) -> Result<(), JsonParseError> {
+        self.pos += 1;
+
+        loop {
+            match self.peek() {
+                Some(b'"') => {
+                    self.pos += 1;
+                    return Ok(());
+                }
+                Some(b'\\') => {
+                    self.pos += 1;
+                    match self.peek() {
+                        Some(b'"' | b'\\' | b'/' | b'b' | b'f' | b'n' | b'r' | b't') => {
+                            self.pos += 1;
+                        }
+                        Some(b'u') => {
+                            self.pos += 1;
+                            for _ in 0..4 {
+                                match self.peek() {
+                                    Some(b) if b.is_ascii_hexdigit() => self.pos += 1,
+                                    _ => return Err(self.error("invalid \\u escape")),
+                                }
+                            }
+                        }
+                        _ => return Err(self.error("invalid escape sequence")),
+                    }
+                }
+                Some(b) if b < 0x20 => return Err(self.error("unescaped control character")),
+                Some(_) => self.pos += 1,
+                None => return Err(self.error("unterminated string")),
+            }
+        }
+    }
+
+    fn check_number(&mut self) -> Result<(), JsonParseError> {
+        if self.peek() == Some(b'-') {
+            self.pos += 1;
+        }
+
+        match self.peek() {
+            Some(b'0') => self.pos += 1,
+            Some(b'1'..=b'9') => self.skip_digits(),
+            _ => return Err(self.error("invalid number")),
+        }
+
+        if self.peek() == Some(b'.') {
+            self.pos += 1;
+            self.expect_digits()?;
+        }
+
+        if let Some(b'e' | b'E') = self.peek() {
+            self.pos += 1;
+            if let Some(b'+' | b'-') = self.peek() {
+                self.pos += 1;
+            }
+            self.expect_digits()?;
+        }
+
+        Ok(())
+    }
+
+    fn skip_digits(&mut self) {
+        while let Some(b'0'..=b'9') = self.peek() {
+            self.pos += 1;
+        }
+    }
+
+    fn expect_digits(&mut self) -> Result<(), JsonParseError> {
+        let start = self.pos;
+        self.skip_digits();
+
+        if self.pos == start {
+            return Err(self.error("invalid number"));
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::json_writer::JsonObjectWriter;
+
+    #[test]
+    fn test_value_ref_is_written_verbatim() {
+        let src = br#"{"data": { "a" : [1, 2 ], "s":"x y" }, "n": 1.50}"#;
+
+        let data = crate::j_path::get_value(src, "data").unwrap().unwrap();
+        let n = crate::j_path::get_value(src, "n").unwrap().unwrap();
+
+        let result = JsonObjectWriter::new()
+            .write("data", data.clone())
+            .write("n", &n)
+            .write("n_parsed", n.unwrap_value().unwrap())
+            .build();
+
+        assert_eq!(
+            result,
+            r#"{"data":{ "a" : [1, 2 ], "s":"x y" },"n":1.50,"n_parsed":1.50}"#
+        );
+    }
+
+    #[test]
+    fn test_checked_value_minified() {
+        let src = br#"{"data": { "a" : [1, 2 ], "s":"x y \" }" }}"#;
+        let data = crate::j_path::get_value(src, "data").unwrap().unwrap();
+
+        let result = JsonObjectWriter::new()
+            .write("m", CheckedJsonValue::minified(&data).unwrap())
+            .write("v", CheckedJsonValue::verbatim(&data).unwrap())
+            .build();
+
+        assert_eq!(
+            result,
+            r#"{"m":{"a":[1,2],"s":"x y \" }"},"v":{ "a" : [1, 2 ], "s":"x y \" }" }}"#
+        );
+    }
+
+    #[test]
+    fn test_unwrapped_values_are_written_as_json() {
+        let src = br#"{"s":"a\"b","t":true,"z":null,"arr":[1, 2],"obj":{"k":'v'}}"#;
+
+        let mut result = JsonObjectWriter::new();
+        for key in ["s", "t", "z", "arr", "obj"] {
+            let value = crate::j_path::get_value(src, key).unwrap().unwrap();
+            result = result.write(key, value.unwrap_value().unwrap());
+        }
+
+        assert_eq!(
+            result.build(),
+            r#"{"s":"a\"b","t":true,"z":null,"arr":[1, 2],"obj":{"k":"v"}}"#
+        );
+
+        for digits in ["0.12345678901234567890123", "1e400", "-1E-05"] {
+            let double = UnwrappedJsonValue::Double(JsonNumber::new(digits).unwrap());
+            let mut dest = String::new();
+            double.write(&mut dest);
+            assert_eq!(dest, digits);
+        }
+
+        let single_quoted = UnwrappedJsonValue::String("'it\"s'");
+        let mut dest = String::new();
+        single_quoted.write(&mut dest);
+        assert_eq!(dest, r#""it\"s""#);
+    }
+
+    #[test]
+    fn test_lenient_value_is_re_rendered_as_json() {
+        let src = br#"{"o":{'k':bare, n:+1, "d":.5, "a":['x', 1.50, ], "s":"\u00e9"}}"#;
+        let o = crate::j_path::get_value(src, "o").unwrap().unwrap();
+
+        let result = JsonObjectWriter::new().write("o", &o).build();
+
+        assert_eq!(
+            result,
+            r#"{"o":{"k":"bare","n":1,"d":0.5,"a":["x",1.50],"s":"\u00e9"}}"#
+        );
+        assert!(
+            CheckedJsonValue::from_slice(result.as_bytes(), CheckedJsonValueMode::Verbatim).is_ok()
+        );
+    }
+
+    #[test]
+    fn test_numbers_keep_their_digits_when_re_rendered() {
+        let src = br#"{"o":{"p":0.12345678901234567890123,"big":1e400,"s":'x',"l":[+1e400, -.1234567890123456789012345, 007, 1.]}}"#;
+        let o = crate::j_path::get_value(src, "o").unwrap().unwrap();
+
+        let result = JsonObjectWriter::new().write("o", &o).build();
+
+        assert_eq!(
+            result,
+            r#"{"o":{"p":0.12345678901234567890123,"big":1e400,"s":"x","l":[1e400,-0.1234567890123456789012345,7,1]}}"#
+        );
+        assert!(
+            CheckedJsonValue::from_slice(result.as_bytes(), CheckedJsonValueMode::Verbatim).is_ok()
+        );
+    }
+
+    #[test]
+    fn test_writer_options_reach_nested_values() {
+        use crate::json_writer::{JsonEscapeMode, JsonNumberFormat};
+
+        let src = br#"{"o":{"s":"\u00e9\u0041", "a":[12345678901234567890, "<b>"]}}"#;
+        let o = crate::j_path::get_value(src, "o").unwrap().unwrap();
+
+        let options = JsonWriterOptions::new()
+            .with_escape_mode(JsonEscapeMode::ASCII_ONLY)
+            .with_number_format(JsonNumberFormat::new().with_big_integers_as_strings());
+
+        let mut dest = String::new();
+        o.write_with_options(&mut dest, &options);
+
+        assert_eq!(
+            dest,
+            r#"{"s":"\u00e9A","a":["12345678901234567890","<b>"]}"#
+        );
+    }
+
+    #[test]
+    fn test_raw_copy_is_an_explicit_opt_in() {
+        let src = br#"{"o":{'k':bare}}"#;
+        let o = crate::j_path::get_value(src, "o").unwrap().unwrap();
+
+        let result = JsonObjectWriter::new()
+            .write("raw", RawJsonObject::from(&o))
+            .build();
+
+        assert_eq!(result, r#"{"raw":{'k':bare}}"#);
+    }
+
+    #[test]
+    fn test_incomplete_or_lenient_json_is_rejected() {
+        for bad in [
+            "",
+            "{",
+            r#"{"a":1,}"#,
+            "[1,2",
+            "'single'",
+            "{a:1}",
+            "01",
+            "1.",
+            r#""tab	inside""#,
+            r#""\x""#,
+            "true false",
+            "nul",
+        ] {
+            assert!(
+                CheckedJsonValue::from_slice(bad.as_bytes(), CheckedJsonValueMode::Verbatim)
+                    .is_err(),
+                "{} must be rejected",
+                bad
+            );
+        }
+
+        for good in [
+            " { \"a\" : [ -0.5e+3 , true, null, \"\\u00e9\" ] } ",
+            "0",
+            "[]",
+            "\"\"",
+        ] {
+            assert!(
+                CheckedJsonValue::from_slice(good.as_bytes(), CheckedJsonValueMode::Verbatim)
+                    .is_ok(),
+                "{} must be accepted",
+                good
+            );
+        }
+    }
+
+    #[test]
+    fn test_hostile_nesting_is_an_error_not_a_stack_overflow() {
+        let deep = "[".repeat(100_000);
+        assert!(
+            CheckedJsonValue::from_slice(deep.as_bytes(), CheckedJsonValueMode::Minified).is_err()
+        );
+    }
+}
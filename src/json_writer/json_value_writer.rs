@@ -1,9 +1,14 @@
-use std::collections::HashMap;
-use std::fmt::Write;
+use std::borrow::Cow;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
+use std::fmt::{Display, Write};
+use std::rc::Rc;
+use std::sync::Arc;
 
 use rust_extensions::{date_time::DateTimeAsMicroseconds, StrOrString};
 
-use super::JsonValueWriter;
+use crate::json_reader::{JsonNumber, Lenient};
+
+use super::{JsonEscapeMode, JsonFloatValue, JsonValueWriter, JsonWriterOptions};
 
 // Integer writers: format straight into the destination buffer via `write!` instead of allocating
 // an intermediate `String` through `to_string()` on every value.
@@ -15,6 +20,10 @@ macro_rules! impl_json_value_writer_for_integer {
                 fn write(&self, dest: &mut String) {
                     let _ = write!(dest, "{}", self);
                 }
+
+                fn write_with_options(&self, dest: &mut String, options: &JsonWriterOptions) {
+                    super::write_integer(dest, *self, options.number_format.big_integers_as_strings);
+                }
             }
 
             impl JsonValueWriter for Option<$t> {
@@ -27,6 +36,13 @@ macro_rules! impl_json_value_writer_for_integer {
                         None => dest.push_str("null"),
                     }
                 }
+
+                fn write_with_options(&self, dest: &mut String, options: &JsonWriterOptions) {
+                    match self {
+                        Some(v) => v.write_with_options(dest, options),
+                        None => dest.push_str("null"),
+                    }
+                }
             }
         )*
     };
@@ -54,6 +70,10 @@ macro_rules! impl_json_value_writer_for_float {
                         dest.push_str("null");
                     }
                 }
+
+                fn write_with_options(&self, dest: &mut String, options: &JsonWriterOptions) {
+                    self.write_with_float_format(dest, options.number_format.float);
+                }
             }
 
             impl JsonValueWriter for Option<$t> {
@@ -67,6 +87,13 @@ macro_rules! impl_json_value_writer_for_float {
                         _ => dest.push_str("null"),
                     }
                 }
+
+                fn write_with_options(&self, dest: &mut String, options: &JsonWriterOptions) {
+                    match self {
+                        Some(v) => v.write_with_float_format(dest, options.number_format.float),
+                        None => dest.push_str("null"),
+                    }
+                }
             }
         )*
     };
@@ -80,6 +107,10 @@ impl JsonValueWriter for rust_decimal::Decimal {
     fn write(&self, dest: &mut String) {
         let _ = write!(dest, "{}", self);
     }
+
+    fn write_with_options(&self, dest: &mut String, options: &JsonWriterOptions) {
+        self.write_with_float_format(dest, options.number_format.float);
+    }
 }
 
 // Mirrors `Option<DateTimeAsMicroseconds>`: without this a `Option<Decimal>` field has no wire
@@ -95,6 +126,13 @@ impl JsonValueWriter for Option<rust_decimal::Decimal> {
             None => dest.push_str("null"),
         }
     }
+
+    fn write_with_options(&self, dest: &mut String, options: &JsonWriterOptions) {
+        match self {
+            Some(v) => v.write_with_float_format(dest, options.number_format.float),
+            None => dest.push_str("null"),
+        }
+    }
 }
 
 impl JsonValueWriter for bool {
@@ -147,74 +185,148 @@ impl JsonValueWriter for Option<DateTimeAsMicroseconds> {
     }
 }
 
-impl JsonValueWriter for String {
-    const IS_ARRAY: bool = false;
-    fn write(&self, dest: &mut String) {
-        dest.push('"');
-        crate::json_string_value::write_escaped_json_string_value(self, dest);
-        dest.push('"');
+fn write_wrapped<T: JsonValueWriter>(
+    value: &T,
+    dest: &mut String,
+    options: Option<&JsonWriterOptions>,
+) {
+    if T::IS_ARRAY {
+        dest.push('[');
     }
-}
 
-impl<'s> JsonValueWriter for StrOrString<'s> {
-    const IS_ARRAY: bool = false;
-    fn write(&self, dest: &mut String) {
-        dest.push('"');
-        crate::json_string_value::write_escaped_json_string_value(self.as_str(), dest);
-        dest.push('"');
+    match options {
+        Some(options) => value.write_with_options(dest, options),
+        None => value.write(dest),
     }
-}
 
-impl<'s> JsonValueWriter for &'s StrOrString<'s> {
-    const IS_ARRAY: bool = false;
-    fn write(&self, dest: &mut String) {
-        dest.push('"');
-        crate::json_string_value::write_escaped_json_string_value(self.as_str(), dest);
-        dest.push('"');
+    if T::IS_ARRAY {
+        dest.push(']');
     }
 }
 
-impl JsonValueWriter for Option<String> {
+// Every string writer honours the writer's `JsonEscapeMode` through `write_with_options`; the
+// plain `write` path keeps the standard escaping.
+macro_rules! impl_json_value_writer_for_string {
+    ($($t:ty),* $(,)?) => {
+        $(
+            impl<'s> JsonValueWriter for $t {
+                const IS_ARRAY: bool = false;
+                fn write(&self, dest: &mut String) {
+                    write_string(dest, self.as_ref())
+                }
+
+                fn write_with_options(&self, dest: &mut String, options: &JsonWriterOptions) {
+                    write_string_with_mode(dest, self.as_ref(), options.escape_mode)
+                }
+            }
+
+            impl<'s> JsonValueWriter for Option<$t> {
+                const IS_ARRAY: bool = false;
+                fn write(&self, dest: &mut String) {
+                    match self {
+                        Some(v) => write_string(dest, v.as_ref()),
+                        None => dest.push_str("null"),
+                    }
+                }
+
+                fn write_with_options(&self, dest: &mut String, options: &JsonWriterOptions) {
+                    match self {
+                        Some(v) => write_string_with_mode(dest, v.as_ref(), options.escape_mode),
+                        None => dest.push_str("null"),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_json_value_writer_for_string!(
+    String,
+    &'s str,
+    &'s String,
+    Box<str>,
+    Rc<str>,
+    Arc<str>,
+    Cow<'s, str>,
+);
+
+// Smart pointers are transparent on the wire: a `Box<T>` writes exactly what a `T` writes,
+// `IS_ARRAY` included.
+macro_rules! impl_json_value_writer_for_pointer {
+    ($($p:ident),* $(,)?) => {
+        $(
+            impl<T: JsonValueWriter> JsonValueWriter for $p<T> {
+                const IS_ARRAY: bool = T::IS_ARRAY;
+                fn write(&self, dest: &mut String) {
+                    T::write(self, dest)
+                }
+
+                fn write_with_options(&self, dest: &mut String, options: &JsonWriterOptions) {
+                    T::write_with_options(self, dest, options)
+                }
+            }
+        )*
+    };
+}
+
+impl_json_value_writer_for_pointer!(Box, Rc, Arc);
+
+// The digits exactly as they were read. Only `big_integers_as_strings` applies - re-rendering
+// the digits through a float format is the precision loss `JsonNumber` exists to avoid.
+impl<'s> JsonValueWriter for JsonNumber<'s> {
     const IS_ARRAY: bool = false;
     fn write(&self, dest: &mut String) {
-        match self {
-            Some(v) => write_string(dest, v),
-            None => dest.push_str("null"),
+        dest.push_str(self.as_str());
+    }
+
+    fn write_with_options(&self, dest: &mut String, options: &JsonWriterOptions) {
+        let is_big = options.number_format.big_integers_as_strings
+            && self.is_integer()
+            && self
+                .as_i128()
+                .map_or(true, |v| v.unsigned_abs() > super::MAX_SAFE_INTEGER as u128);
+
+        if is_big {
+            dest.push('"');
+            dest.push_str(self.as_str());
+            dest.push('"');
+        } else {
+            dest.push_str(self.as_str());
         }
     }
 }
 
-impl<'s> JsonValueWriter for &'s str {
-    const IS_ARRAY: bool = false;
+// Leniency is a reading concern only: the value is written in its canonical form.
+impl<T: JsonValueWriter> JsonValueWriter for Lenient<T> {
+    const IS_ARRAY: bool = T::IS_ARRAY;
     fn write(&self, dest: &mut String) {
-        write_string(dest, self)
+        self.0.write(dest)
     }
-}
 
-impl<'s> JsonValueWriter for Option<&'s str> {
-    const IS_ARRAY: bool = false;
-    fn write(&self, dest: &mut String) {
-        match self {
-            Some(v) => write_string(dest, v),
-            None => dest.push_str("null"),
-        }
+    fn write_with_options(&self, dest: &mut String, options: &JsonWriterOptions) {
+        self.0.write_with_options(dest, options)
     }
 }
 
-impl<'s> JsonValueWriter for &'s String {
+impl<'s> JsonValueWriter for StrOrString<'s> {
     const IS_ARRAY: bool = false;
     fn write(&self, dest: &mut String) {
-        write_string(dest, self)
+        write_string(dest, self.as_str())
+    }
+
+    fn write_with_options(&self, dest: &mut String, options: &JsonWriterOptions) {
+        write_string_with_mode(dest, self.as_str(), options.escape_mode)
     }
 }
 
-impl<'s> JsonValueWriter for Option<&'s String> {
+impl<'s> JsonValueWriter for &'s StrOrString<'s> {
     const IS_ARRAY: bool = false;
     fn write(&self, dest: &mut String) {
-        match self {
-            Some(v) => write_string(dest, v),
-            None => dest.push_str("null"),
-        }
+        write_string(dest, self.as_str())
+    }
+
+    fn write_with_options(&self, dest: &mut String, options: &JsonWriterOptions) {
+        write_string_with_mode(dest, self.as_str(), options.escape_mode)
     }
 }
 
@@ -236,9 +348,13 @@ impl<'s> RawJsonObject<'s> {
     }
 }
 
+// The bytes are often a payload that came off the wire, so invalid UTF-8 is replaced rather
+// than allowed to panic.
 impl<'s> Into<RawJsonObject<'s>> for Vec<u8> {
     fn into(self) -> RawJsonObject<'s> {
-        RawJsonObject::AsString(String::from_utf8(self).unwrap())
+        let value = String::from_utf8(self)
+            .unwrap_or_else(|err| String::from_utf8_lossy(err.as_bytes()).into_owned());
+        RawJsonObject::AsString(value)
     }
 }
 
@@ -276,69 +392,193 @@ impl JsonValueWriter for EmptyJsonArray {
 // Writes the comma-separated body of an array of `T`. Each element that is itself an array
 // (`T::IS_ARRAY`) is wrapped in its own `[` `]` - without this a `Vec<Vec<T>>` (or a `Vec` of any
 // array-valued element) would collapse into a single flat array, e.g. `[[1,2],[3]]` -> `[1,2,3]`.
-fn write_array_body<T: JsonValueWriter>(dest: &mut String, items: &[T]) {
-    for (no, itm) in items.iter().enumerate() {
+//
+// `options` is `None` on the plain `write` path, so that path stays byte-for-byte what it was.
+fn write_array_body<'a, T: JsonValueWriter + 'a>(
+    dest: &mut String,
+    items: impl IntoIterator<Item = &'a T>,
+    options: Option<&JsonWriterOptions>,
+) {
+    for (no, itm) in items.into_iter().enumerate() {
         if no > 0 {
             dest.push(',');
         }
-        if T::IS_ARRAY {
-            dest.push('[');
-        }
-        itm.write(dest);
-        if T::IS_ARRAY {
-            dest.push(']');
-        }
+        write_wrapped(itm, dest, options);
     }
 }
 
 impl<T: JsonValueWriter> JsonValueWriter for Vec<T> {
     const IS_ARRAY: bool = true;
     fn write(&self, dest: &mut String) {
-        write_array_body(dest, self.as_slice());
+        write_array_body(dest, self.as_slice(), None);
+    }
+
+    fn write_with_options(&self, dest: &mut String, options: &JsonWriterOptions) {
+        write_array_body(dest, self.as_slice(), Some(options));
     }
 }
 
 impl<'s, T: JsonValueWriter> JsonValueWriter for &'s [T] {
     const IS_ARRAY: bool = true;
     fn write(&self, dest: &mut String) {
-        write_array_body(dest, *self);
+        write_array_body(dest, *self, None);
+    }
+
+    fn write_with_options(&self, dest: &mut String, options: &JsonWriterOptions) {
+        write_array_body(dest, *self, Some(options));
     }
 }
 
 impl<'s, T: JsonValueWriter> JsonValueWriter for &'s Vec<T> {
     const IS_ARRAY: bool = true;
     fn write(&self, dest: &mut String) {
-        write_array_body(dest, self.as_slice());
+        write_array_body(dest, self.as_slice(), None);
     }
+
+    fn write_with_options(&self, dest: &mut String, options: &JsonWriterOptions) {
+        write_array_body(dest, self.as_slice(), Some(options));
+    }
+}
+
+// The other sequences write the same body as `Vec<T>`, so they read back as any of them.
+macro_rules! impl_json_value_writer_for_sequence {
+    ($($t:ident),* $(,)?) => {
+        $(
+            impl<T: JsonValueWriter> JsonValueWriter for $t<T> {
+                const IS_ARRAY: bool = true;
+                fn write(&self, dest: &mut String) {
+                    write_array_body(dest, self, None);
+                }
+
+                fn write_with_options(&self, dest: &mut String, options: &JsonWriterOptions) {
+                    write_array_body(dest, self, Some(options));
+                }
+            }
+        )*
+    };
+}
+
+impl_json_value_writer_for_sequence!(VecDeque, BTreeSet);
+
+impl<T: JsonValueWriter, S> JsonValueWriter for HashSet<T, S> {
+    const IS_ARRAY: bool = true;
+    fn write(&self, dest: &mut String) {
+        write_array_body(dest, self, None);
+    }
+
+    fn write_with_options(&self, dest: &mut String, options: &JsonWriterOptions) {
+        write_array_body(dest, self, Some(options));
+    }
+}
+
+impl<T: JsonValueWriter, const N: usize> JsonValueWriter for [T; N] {
+    const IS_ARRAY: bool = true;
+    fn write(&self, dest: &mut String) {
+        write_array_body(dest, self, None);
+    }
+
+    fn write_with_options(&self, dest: &mut String, options: &JsonWriterOptions) {
+        write_array_body(dest, self, Some(options));
+    }
+}
+
+// A tuple is written as an array of its elements: `("a", 1)` is `["a",1]`.
+macro_rules! impl_json_value_writer_for_tuple {
+    ($($no:tt $t:ident),+) => {
+        impl<$($t: JsonValueWriter),+> JsonValueWriter for ($($t,)+) {
+            const IS_ARRAY: bool = true;
+            fn write(&self, dest: &mut String) {
+                $(
+                    if $no > 0 {
+                        dest.push(',');
+                    }
+                    write_wrapped(&self.$no, dest, None);
+                )+
+            }
+
+            fn write_with_options(&self, dest: &mut String, options: &JsonWriterOptions) {
+                $(
+                    if $no > 0 {
+                        dest.push(',');
+                    }
+                    write_wrapped(&self.$no, dest, Some(options));
+                )+
+            }
+        }
+    };
 }
 
-// A `HashMap<String, V>` serialises as a JSON object - each entry becomes a `"key":value` pair.
-// `IS_ARRAY` is false because, like `JsonObjectWriter`, `write` emits its own surrounding `{` `}`,
-// so `JsonObjectWriter::write` must not wrap it again. A value that is itself an array
-// (`V::IS_ARRAY`) is wrapped in `[` `]`, mirroring `JsonObjectWriter::write`, so a
+impl_json_value_writer_for_tuple!(0 T0);
+impl_json_value_writer_for_tuple!(0 T0, 1 T1);
+impl_json_value_writer_for_tuple!(0 T0, 1 T1, 2 T2);
+impl_json_value_writer_for_tuple!(0 T0, 1 T1, 2 T2, 3 T3);
+impl_json_value_writer_for_tuple!(0 T0, 1 T1, 2 T2, 3 T3, 4 T4);
+impl_json_value_writer_for_tuple!(0 T0, 1 T1, 2 T2, 3 T3, 4 T4, 5 T5);
+impl_json_value_writer_for_tuple!(0 T0, 1 T1, 2 T2, 3 T3, 4 T4, 5 T5, 6 T6);
+impl_json_value_writer_for_tuple!(0 T0, 1 T1, 2 T2, 3 T3, 4 T4, 5 T5, 6 T6, 7 T7);
+
+// A map serialises as a JSON object - each entry becomes a `"key":value` pair, the key rendered
+// through `Display`, so `HashMap<u32, V>` writes `{"1":...}` and the reader parses it back with
+// `FromStr`. `IS_ARRAY` is false because, like `JsonObjectWriter`, `write` emits its own
+// surrounding `{` `}`, so `JsonObjectWriter::write` must not wrap it again. A value that is itself
+// an array (`V::IS_ARRAY`) is wrapped in `[` `]`, mirroring `JsonObjectWriter::write`, so a
 // `HashMap<String, Vec<T>>` keeps each value as its own nested array rather than a flat run.
 // This impl is what lets derived writers (e.g. `MyHttpObjectStructure`) accept `HashMap` fields.
-// Entry order follows `HashMap` iteration order and is therefore not deterministic.
-impl<V: JsonValueWriter> JsonValueWriter for HashMap<String, V> {
+// Entry order follows `HashMap` iteration order and is therefore not deterministic; a `BTreeMap`
+// writes its keys in order.
+impl<K: Display, V: JsonValueWriter, S> JsonValueWriter for HashMap<K, V, S> {
     const IS_ARRAY: bool = false;
     fn write(&self, dest: &mut String) {
-        dest.push('{');
-        for (no, (key, value)) in self.iter().enumerate() {
-            if no > 0 {
-                dest.push(',');
-            }
-            write_string(dest, key);
-            dest.push(':');
-            if V::IS_ARRAY {
-                dest.push('[');
-            }
-            value.write(dest);
-            if V::IS_ARRAY {
-                dest.push(']');
-            }
+        write_map(dest, self, None);
+    }
+
+    fn write_with_options(&self, dest: &mut String, options: &JsonWriterOptions) {
+        write_map(dest, self, Some(options));
+    }
+}
+
+impl<K: Display, V: JsonValueWriter> JsonValueWriter for BTreeMap<K, V> {
+    const IS_ARRAY: bool = false;
+    fn write(&self, dest: &mut String) {
+        write_map(dest, self, None);
+    }
+
+    fn write_with_options(&self, dest: &mut String, options: &JsonWriterOptions) {
+        write_map(dest, self, Some(options));
+    }
+}
+
+fn write_map<'a, K: Display + 'a, V: JsonValueWriter + 'a>(
+    dest: &mut String,
+    map: impl IntoIterator<Item = (&'a K, &'a V)>,
+    options: Option<&JsonWriterOptions>,
+) {
+    // One buffer for every key, so a `String` key costs a copy and not an allocation.
+    let mut key_buffer = String::new();
+    let mut first = true;
+
+    dest.push('{');
+    for (key, value) in map {
+        // A key whose `Display` fails has no name to write the entry under, so the entry is left
+        // out rather than written under an empty or partial key.
+        key_buffer.clear();
+        if write!(key_buffer, "{}", key).is_err() {
+            continue;
         }
-        dest.push('}');
+
+        if !first {
+            dest.push(',');
+        }
+        first = false;
+
+        match options {
+            Some(options) => write_string_with_mode(dest, &key_buffer, options.escape_mode),
+            None => write_string(dest, &key_buffer),
+        }
+        dest.push(':');
+        write_wrapped(value, dest, options);
     }
+    dest.push('}');
 }
 
 fn write_string(out: &mut String, value: &str) {
@@ -347,6 +587,12 @@ fn write_string(out: &mut String, value: &str) {
     out.push('"');
 }
 
+fn write_string_with_mode(out: &mut String, value: &str, mode: JsonEscapeMode) {
+    out.push('"');
+    crate::json_string_value::write_escaped_json_string_value_with_mode(value, mode, out);
+    out.push('"');
+}
+
 #[cfg(test)]
 mod test {
     use crate::json_writer::{JsonArrayWriter, JsonObjectWriter};
@@ -626,6 +872,31 @@ mod test {
         assert_eq!(result, r#"{"m":{}}"#);
     }
 
+    #[test]
+    fn test_map_entry_whose_key_can_not_be_displayed_is_left_out() {
+        use std::collections::BTreeMap;
+
+        // Writes part of its name, then fails - as a `Display` that gives up half way does.
+        #[derive(PartialEq, Eq, PartialOrd, Ord)]
+        struct Key(u32);
+
+        impl std::fmt::Display for Key {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                write!(f, "k{}", self.0)?;
+
+                if self.0 % 2 == 0 {
+                    return Err(std::fmt::Error);
+                }
+
+                Ok(())
+            }
+        }
+
+        let map: BTreeMap<Key, i32> = (0..4).map(|no| (Key(no), no as i32)).collect();
+        let result = JsonObjectWriter::new().write("m", map).build();
+        assert_eq!(result, r#"{"m":{"k1":1,"k3":3}}"#);
+    }
+
     #[test]
     fn test_option_date_time() {
         use rust_extensions::date_time::DateTimeAsMicroseconds;
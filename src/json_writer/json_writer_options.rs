@@ -0,0 +1,71 @@
+use crate::json_string_value::JsonEscapeMode;
+
+use super::JsonNumberFormat;
+
+/// Everything a writer applies to the values it renders: how numbers are formatted and which
+/// characters strings (and object keys) escape. Set it with `with_options` on any writer - or
+/// `with_number_format` / `with_escape_mode` for one half - and it carries into nested objects
+/// and arrays.
+///
+/// The default writes exactly what the writers write without options.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct JsonWriterOptions {
+    pub number_format: JsonNumberFormat,
+    pub escape_mode: JsonEscapeMode,
+}
+
+impl JsonWriterOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_number_format(mut self, number_format: JsonNumberFormat) -> Self {
+        self.number_format = number_format;
+        self
+    }
+
+    pub fn with_escape_mode(mut self, escape_mode: JsonEscapeMode) -> Self {
+        self.escape_mode = escape_mode;
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+    use crate::json_writer::{JsonArrayStreamWriter, JsonObjectWriter};
+
+    #[test]
+    fn test_escape_mode_reaches_keys_values_and_containers() {
+        let mut map = HashMap::new();
+        map.insert("ключ".to_string(), "<b>");
+
+        let result = JsonObjectWriter::new()
+            .with_escape_mode(JsonEscapeMode::ASCII_HTML_SAFE)
+            .write("a&b", "naïve")
+            .write("list", vec!["'x'".to_string()])
+            .write("map", map)
+            .write_json_array("arr", |a| a.write(Some("\u{2028}")))
+            .build();
+
+        assert_eq!(
+            result,
+            r#"{"a\u0026b":"na\u00efve","list":["\u0027x\u0027"],"map":{"\u043a\u043b\u044e\u0447":"\u003cb\u003e"},"arr":["\u2028"]}"#
+        );
+    }
+
+    #[test]
+    fn test_stream_writer_honours_options() {
+        let mut writer = JsonArrayStreamWriter::new(Vec::new())
+            .unwrap()
+            .with_options(JsonWriterOptions::new().with_escape_mode(JsonEscapeMode::ASCII_ONLY));
+
+        writer.write("ß").unwrap();
+        writer.write_json_object(|o| o.write("ü", 1)).unwrap();
+
+        let result = writer.finish().unwrap();
+        assert_eq!(result, br#"["\u00df",{"\u00fc":1}]"#);
+    }
+}
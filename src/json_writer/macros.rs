@@ -10,6 +10,10 @@
 /// The macro returns the writer (not a `String`), so call `.build()` to get the
 /// final JSON.
 ///
+/// Prefix the literal with a [`JsonWriterOptions`](crate::json_writer::JsonWriterOptions)
+/// expression and `;` to write it - nested literals included - with those options, e.g.
+/// `my_json!(JsonWriterOptions::new().with_escape_mode(JsonEscapeMode::HTML_SAFE); { ... })`.
+///
 /// # Examples
 /// ```
 /// use my_json::my_json;
@@ -38,16 +42,24 @@
 /// ```
 #[macro_export]
 macro_rules! my_json {
-    ({ $($tt:tt)* }) => {{
+    ({ $($tt:tt)* }) => {
+        $crate::my_json!($crate::json_writer::JsonWriterOptions::default(); { $($tt)* })
+    };
+    ([ $($tt:tt)* ]) => {
+        $crate::my_json!($crate::json_writer::JsonWriterOptions::default(); [ $($tt)* ])
+    };
+    ($options:expr; { $($tt:tt)* }) => {{
+        let __o: $crate::json_writer::JsonWriterOptions = $options;
         #[allow(unused_mut)]
-        let mut __w = $crate::json_writer::JsonObjectWriter::new();
-        $crate::__my_json_obj!(__w; $($tt)*);
+        let mut __w = $crate::json_writer::JsonObjectWriter::new().with_options(__o);
+        $crate::__my_json_obj!(__w, __o; $($tt)*);
         __w
     }};
-    ([ $($tt:tt)* ]) => {{
+    ($options:expr; [ $($tt:tt)* ]) => {{
+        let __o: $crate::json_writer::JsonWriterOptions = $options;
         #[allow(unused_mut)]
-        let mut __w = $crate::json_writer::JsonArrayWriter::new();
-        $crate::__my_json_arr!(__w; $($tt)*);
+        let mut __w = $crate::json_writer::JsonArrayWriter::new().with_options(__o);
+        $crate::__my_json_arr!(__w, __o; $($tt)*);
         __w
     }};
 }
@@ -57,34 +69,34 @@ macro_rules! my_json {
 #[macro_export]
 macro_rules! __my_json_obj {
     // end
-    ($w:ident;) => {};
-    ($w:ident; ,) => {};
+    ($w:ident, $o:ident;) => {};
+    ($w:ident, $o:ident; ,) => {};
 
     // key => { nested object } [, rest]
-    ($w:ident; $key:expr => { $($inner:tt)* } , $($rest:tt)*) => {
-        $w = $w.write($key, $crate::my_json!({ $($inner)* }));
-        $crate::__my_json_obj!($w; $($rest)*);
+    ($w:ident, $o:ident; $key:expr => { $($inner:tt)* } , $($rest:tt)*) => {
+        $w = $w.write($key, $crate::my_json!($o; { $($inner)* }));
+        $crate::__my_json_obj!($w, $o; $($rest)*);
     };
-    ($w:ident; $key:expr => { $($inner:tt)* }) => {
-        $w = $w.write($key, $crate::my_json!({ $($inner)* }));
+    ($w:ident, $o:ident; $key:expr => { $($inner:tt)* }) => {
+        $w = $w.write($key, $crate::my_json!($o; { $($inner)* }));
     };
 
     // key => [ nested array ] [, rest]
-    ($w:ident; $key:expr => [ $($inner:tt)* ] , $($rest:tt)*) => {
-        $w = $w.write($key, $crate::my_json!([ $($inner)* ]));
-        $crate::__my_json_obj!($w; $($rest)*);
+    ($w:ident, $o:ident; $key:expr => [ $($inner:tt)* ] , $($rest:tt)*) => {
+        $w = $w.write($key, $crate::my_json!($o; [ $($inner)* ]));
+        $crate::__my_json_obj!($w, $o; $($rest)*);
     };
-    ($w:ident; $key:expr => [ $($inner:tt)* ]) => {
-        $w = $w.write($key, $crate::my_json!([ $($inner)* ]));
+    ($w:ident, $o:ident; $key:expr => [ $($inner:tt)* ]) => {
+        $w = $w.write($key, $crate::my_json!($o; [ $($inner)* ]));
     };
 
     // key => expr , rest
-    ($w:ident; $key:expr => $value:expr , $($rest:tt)*) => {
+    ($w:ident, $o:ident; $key:expr => $value:expr , $($rest:tt)*) => {
         $w = $w.write($key, $value);
-        $crate::__my_json_obj!($w; $($rest)*);
+        $crate::__my_json_obj!($w, $o; $($rest)*);
     };
     // key => expr (last)
-    ($w:ident; $key:expr => $value:expr) => {
+    ($w:ident, $o:ident; $key:expr => $value:expr) => {
         $w = $w.write($key, $value);
     };
 }
@@ -94,34 +106,34 @@ macro_rules! __my_json_obj {
 #[macro_export]
 macro_rules! __my_json_arr {
     // end
-    ($w:ident;) => {};
-    ($w:ident; ,) => {};
+    ($w:ident, $o:ident;) => {};
+    ($w:ident, $o:ident; ,) => {};
 
     // { nested object } [, rest]
-    ($w:ident; { $($inner:tt)* } , $($rest:tt)*) => {
-        $w = $w.write($crate::my_json!({ $($inner)* }));
-        $crate::__my_json_arr!($w; $($rest)*);
+    ($w:ident, $o:ident; { $($inner:tt)* } , $($rest:tt)*) => {
+        $w = $w.write($crate::my_json!($o; { $($inner)* }));
+        $crate::__my_json_arr!($w, $o; $($rest)*);
     };
-    ($w:ident; { $($inner:tt)* }) => {
-        $w = $w.write($crate::my_json!({ $($inner)* }));
+    ($w:ident, $o:ident; { $($inner:tt)* }) => {
+        $w = $w.write($crate::my_json!($o; { $($inner)* }));
     };
 
     // [ nested array ] [, rest]
-    ($w:ident; [ $($inner:tt)* ] , $($rest:tt)*) => {
-        $w = $w.write($crate::my_json!([ $($inner)* ]));
-        $crate::__my_json_arr!($w; $($rest)*);
+    ($w:ident, $o:ident; [ $($inner:tt)* ] , $($rest:tt)*) => {
+        $w = $w.write($crate::my_json!($o; [ $($inner)* ]));
+        $crate::__my_json_arr!($w, $o; $($rest)*);
     };
-    ($w:ident; [ $($inner:tt)* ]) => {
-        $w = $w.write($crate::my_json!([ $($inner)* ]));
+    ($w:ident, $o:ident; [ $($inner:tt)* ]) => {
+        $w = $w.write($crate::my_json!($o; [ $($inner)* ]));
     };
 
     // expr , rest
-    ($w:ident; $value:expr , $($rest:tt)*) => {
+    ($w:ident, $o:ident; $value:expr , $($rest:tt)*) => {
         $w = $w.write($value);
-        $crate::__my_json_arr!($w; $($rest)*);
+        $crate::__my_json_arr!($w, $o; $($rest)*);
     };
     // expr (last)
-    ($w:ident; $value:expr) => {
+    ($w:ident, $o:ident; $value:expr) => {
         $w = $w.write($value);
     };
 }
@@ -260,4 +272,25 @@ mod tests {
         .build();
         assert_eq!(s, r#"{"phone":null,"email":"a@b.com"}"#);
     }
+
+    #[test]
+    fn options_reach_nested_literals() {
+        use crate::json_writer::{JsonEscapeMode, JsonFloatFormat, JsonNumberFormat, JsonWriterOptions};
+
+        let options = JsonWriterOptions::new()
+            .with_escape_mode(JsonEscapeMode::ASCII_HTML_SAFE)
+            .with_number_format(JsonNumberFormat::new().with_float_format(JsonFloatFormat::Fixed(1)));
+
+        let s = crate::my_json!(options; {
+            "html" => "</script>",
+            "città" => ["é", 2.25],
+            "inner" => { "x" => 1.0 },
+        })
+        .build();
+
+        assert_eq!(
+            s,
+            r#"{"html":"\u003c/script\u003e","citt\u00e0":["\u00e9",2.2],"inner":{"x":1.0}}"#
+        );
+    }
 }
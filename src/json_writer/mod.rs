@@ -1,9 +1,36 @@
+mod json_array_stream_writer;
+#[cfg(feature = "tokio")]
+mod json_array_stream_writer_async;
 mod json_array_writer;
 mod json_builder;
+mod json_l_writer;
+mod json_number_format;
+mod json_object_stream_writer;
+#[cfg(feature = "tokio")]
+mod json_object_stream_writer_async;
 mod json_object_writer;
+mod json_seq_writer;
+mod json_value_ref_writer;
 mod json_value_writer;
+mod json_writer_options;
 mod macros;
+pub use json_array_stream_writer::JsonArrayStreamWriter;
+#[cfg(feature = "tokio")]
+pub use json_array_stream_writer_async::JsonArrayStreamWriterAsync;
 pub use json_array_writer::JsonArrayWriter;
 pub use json_builder::JsonValueWriter;
+pub use json_l_writer::{numbered_file_path, JsonLRotateAt, JsonLWriter};
+pub use json_number_format::*;
+pub use json_object_stream_writer::JsonObjectStreamWriter;
+#[cfg(feature = "tokio")]
+pub use json_object_stream_writer_async::JsonObjectStreamWriterAsync;
 pub use json_object_writer::JsonObjectWriter;
+pub use json_seq_writer::JsonSeqWriter;
+pub use json_value_ref_writer::{CheckedJsonValue, CheckedJsonValueMode};
 pub use json_value_writer::*;
+pub use json_writer_options::JsonWriterOptions;
+
+pub use crate::json_string_value::JsonEscapeMode;
+
+#[cfg(feature = "derive")]
+pub use my_json_derive::JsonValueWriter;
@@ -1,5 +1,6 @@
 pub mod consts;
 pub mod j_path;
+pub mod json_date_time_format;
 pub mod json_reader;
 pub mod json_string_value;
 pub mod json_utils;
@@ -12,6 +12,8 @@ use rust_extensions::array_of_bytes_iterator::{ArrayOfBytesIteratorAsync, NextVa
 struct MockAsyncIterator {
     slice: Vec<u8>,
     pos: usize,
+    fail_at: Option<usize>,
+    fail_slices: bool,
 }
 
 impl MockAsyncIterator {
@@ -19,6 +21,24 @@ impl MockAsyncIterator {
         Self {
             slice: src.to_vec(),
             pos: 0,
+            fail_at: None,
+            fail_slices: false,
+        }
+    }
+
+    /// Reads fail with an I/O error from `pos` on, the way a dropped connection does mid-payload.
+    fn failing_at(src: &[u8], pos: usize) -> Self {
+        Self {
+            fail_at: Some(pos),
+            ..Self::new(src)
+        }
+    }
+
+    /// Every slice asked for fails, the way a source that has already let the bytes go does.
+    fn failing_slices(src: &[u8]) -> Self {
+        Self {
+            fail_slices: true,
+            ..Self::new(src)
         }
     }
 }
@@ -37,6 +57,13 @@ impl ArrayOfBytesIteratorAsync for MockAsyncIterator {
     }
 
     async fn get_next(&mut self) -> std::io::Result<Option<NextValue>> {
+        if self.fail_at == Some(self.pos) {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::ConnectionReset,
+                "connection reset",
+            ));
+        }
+
         if self.pos < self.slice.len() {
             let result = NextValue {
                 pos: self.pos,
@@ -54,6 +81,13 @@ impl ArrayOfBytesIteratorAsync for MockAsyncIterator {
     }
 
     async fn get_slice_to_current_pos(&self, from_pos: usize) -> std::io::Result<Vec<u8>> {
+        if self.fail_slices {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "slice released",
+            ));
+        }
+
         Ok(self.slice[from_pos..self.pos].to_vec())
     }
 
@@ -96,8 +130,12 @@ fn block_on<F: std::future::Future>(mut fut: F) -> F::Output {
 
 /// Elements as strings, or the first error - the shape both paths are compared on.
 fn read_async(json: &[u8]) -> Vec<Result<String, String>> {
+    read_async_from(MockAsyncIterator::new(json))
+}
+
+fn read_async_from(src: MockAsyncIterator) -> Vec<Result<String, String>> {
     block_on(async {
-        let mut iterator = JsonArrayIteratorAsync::new(MockAsyncIterator::new(json)).await;
+        let mut iterator = JsonArrayIteratorAsync::new(src).await;
 
         let mut result = Vec::new();
 
@@ -223,3 +261,625 @@ fn non_utf8_key_is_an_error_on_both_paths_not_a_panic() {
         "sync and async disagree on a non-UTF-8 key"
     );
 }
+
+/// `read_as` turns the per-element `Vec<u8>` into an owned `T`, so the element buffer can go
+/// as soon as it is read; a bad element fails on its own.
+#[test]
+fn read_as_yields_owned_typed_elements() {
+    use std::collections::HashMap;
+
+    let json = br#"[{"qty":[1,2]}, {"qty":[3]}, {"qty":"x"}, {"qty":[]}]"#;
+
+    let result = block_on(async {
+        let mut iterator = JsonArrayIteratorAsync::new(MockAsyncIterator::new(json)).await;
+
+        let mut result = Vec::new();
+
+        while let Some(item) = iterator.read_as::<HashMap<String, Vec<u64>>>().await {
+            result.push(item.map(|mut map| map.remove("qty").unwrap()));
+        }
+
+        result
+    });
+
+    assert_eq!(result.len(), 4);
+    assert_eq!(result[0].as_ref().unwrap(), &vec![1, 2]);
+    assert_eq!(result[1].as_ref().unwrap(), &vec![3]);
+    assert_eq!(result[2].as_ref().unwrap_err().path().unwrap(), "$.qty");
+    assert!(result[3].as_ref().unwrap().is_empty());
+
+    let lines = b"{\"id\":1}\n{\"id\":2}\n";
+
+    let result = block_on(async {
+        let mut iterator =
+            my_json::json_reader::JsonLIteratorAsync::new(MockAsyncIterator::new(lines)).await;
+
+        let mut result = Vec::new();
+
+        while let Some(item) = iterator.read_as::<HashMap<String, u8>>().await {
+            result.push(item.unwrap()["id"]);
+        }
+
+        result
+    });
+
+    assert_eq!(result, vec![1, 2]);
+}
+
+/// Construction used to skip leading white space eagerly and unwrap the result, so an empty or
+/// blank payload panicked before the first `get_next`. Now it is an error like any other.
+#[test]
+fn empty_payload_is_an_error_not_a_panic() {
+    for json in ["", "   "] {
+        let result = read_async(json.as_bytes());
+        assert!(
+            matches!(result.as_slice(), [Err(_)]),
+            "expected one error for {:?}, got {:?}",
+            json,
+            result
+        );
+    }
+}
+
+/// An I/O failure from the source is a `JsonParseError` for the element being read, never a
+/// panic - at the opening bracket, inside a value and inside a string alike.
+#[test]
+fn io_error_from_the_source_is_an_error_not_a_panic() {
+    let json = br#"[{"a":"xyz"},[1,2]]"#;
+
+    for fail_at in 0..json.len() {
+        let result = read_async_from(MockAsyncIterator::failing_at(json, fail_at));
+        assert!(
+            result.iter().any(|item| item.is_err()),
+            "expected an error when the source fails at {}, got {:?}",
+            fail_at,
+            result
+        );
+    }
+
+    let lines = b"{\"id\":1}\n{\"id\":2}\n";
+
+    let result = block_on(async {
+        let mut iterator =
+            my_json::json_reader::JsonLIteratorAsync::new(MockAsyncIterator::failing_at(lines, 12))
+                .await;
+
+        let mut result = Vec::new();
+
+        while let Some(item) = iterator.get_next().await {
+            let is_err = item.is_err();
+            result.push(item.map_err(|err| err.to_string()));
+            if is_err {
+                break;
+            }
+        }
+
+        result
+    });
+
+    assert!(result[0].is_ok());
+    assert!(result[1].as_ref().unwrap_err().contains("connection reset"));
+}
+
+/// JSON-L over an async source: any value is a record, each with its line, and in skip-bad mode
+/// a broken line is reported and the next line read as usual.
+#[test]
+fn json_l_records_carry_lines_and_bad_ones_can_be_skipped() {
+    use my_json::json_reader::JsonLIteratorAsync;
+
+    let json = b"{\"a\":1}\n{\"a\": \"open\n[3] 4\n\nnope\n\"s\"";
+
+    let read = |skip_bad_records: bool| {
+        block_on(async {
+            let mut iterator = JsonLIteratorAsync::new(MockAsyncIterator::new(json)).await;
+
+            if skip_bad_records {
+                iterator = iterator.with_skip_bad_records();
+            }
+
+            let mut result = Vec::new();
+
+            while let Some(record) = iterator.get_next_record().await {
+                result.push(match record {
+                    Ok(record) => Ok((
+                        record.line,
+                        record.offset,
+                        String::from_utf8(record.data).unwrap(),
+                    )),
+                    Err(err) => Err((err.line, err.offset)),
+                });
+            }
+
+            result
+        })
+    };
+
+    assert_eq!(
+        read(true),
+        vec![
+            Ok((1, 0, "{\"a\":1}".to_string())),
+            Err((2, 8)),
+            Ok((3, 20, "[3]".to_string())),
+            Ok((3, 24, "4".to_string())),
+            Err((5, 27)),
+            Ok((6, 32, "\"s\"".to_string())),
+        ]
+    );
+
+    assert_eq!(
+        read(false),
+        vec![Ok((1, 0, "{\"a\":1}".to_string())), Err((2, 8))]
+    );
+}
+
+/// A restarted job picks the array up from a checkpoint and reads exactly the elements it had not
+/// read yet, whether the source starts at the beginning or was opened at the checkpoint.
+#[test]
+fn array_read_resumes_from_its_checkpoint() {
+    let json = br#"[{"a":1}, [2], "3" ,4]"#;
+    let all = read_async(json);
+
+    for taken_after in 0..=all.len() {
+        let (checkpoint, rest) = block_on(async {
+            let mut iterator = JsonArrayIteratorAsync::new(MockAsyncIterator::new(json)).await;
+
+            for _ in 0..taken_after {
+                iterator.get_next().await.unwrap().unwrap();
+            }
+
+            let checkpoint = iterator.checkpoint();
+
+            let mut resumed =
+                JsonArrayIteratorAsync::resume(MockAsyncIterator::new(json), checkpoint)
+                    .await
+                    .unwrap();
+
+            let mut rest = Vec::new();
+            while let Some(item) = resumed.get_next().await {
+                rest.push(Ok(String::from_utf8(item.unwrap()).unwrap()));
+            }
+
+            (checkpoint, rest)
+        });
+
+        assert_eq!(
+            rest,
+            all[taken_after..].to_vec(),
+            "checkpoint {}",
+            checkpoint
+        );
+    }
+
+    let result = block_on(async {
+        let mut source = MockAsyncIterator::new(json);
+        source.pos = 8;
+
+        let mut resumed = JsonArrayIteratorAsync::resume(source, 8).await.unwrap();
+        resumed.get_next().await.unwrap().unwrap()
+    });
+    assert_eq!(result, b"[2]");
+
+    for bad in [3, 5, 9, 100] {
+        let result = block_on(JsonArrayIteratorAsync::resume(
+            MockAsyncIterator::new(json),
+            bad,
+        ));
+        assert!(result.is_err(), "{}", bad);
+    }
+}
+
+/// An offset inside an element is rejected even where the byte at it is a `,` or a `]` - the
+/// elements before it are read past to find one that ends exactly there.
+#[test]
+fn array_checkpoint_inside_an_element_is_rejected() {
+    for (json, bad) in [
+        (&b"[[1,2],3]"[..], 3),
+        (&b"[[1,2],3]"[..], 5),
+        (&br#"[{"a":1,"b":2},3]"#[..], 7),
+        (&br#"["x,y",3]"#[..], 3),
+    ] {
+        let result = block_on(JsonArrayIteratorAsync::resume(
+            MockAsyncIterator::new(json),
+            bad,
+        ));
+
+        assert!(result.is_err(), "{:?} at {}", json, bad);
+    }
+
+    let rest = block_on(async {
+        let mut resumed = JsonArrayIteratorAsync::resume(MockAsyncIterator::new(b"[[1,2],3]"), 6)
+            .await
+            .unwrap();
+
+        let mut rest = Vec::new();
+        while let Some(item) = resumed.get_next().await {
+            rest.push(item.unwrap());
+        }
+        rest
+    });
+
+    assert_eq!(rest, vec![b"3".to_vec()]);
+}
+
+#[test]
+fn json_l_read_resumes_from_its_checkpoint() {
+    use my_json::json_reader::JsonLIteratorAsync;
+
+    let json = b"{\"a\":1}\n[2] 3\nbad\n\"5\"";
+
+    for skip_bad_records in [false, true] {
+        let read_all = |checkpoint| {
+            block_on(async {
+                let source = MockAsyncIterator::new(json);
+
+                let mut iterator = match checkpoint {
+                    Some(checkpoint) => JsonLIteratorAsync::resume(source, checkpoint)
+                        .await
+                        .unwrap(),
+                    None => JsonLIteratorAsync::new(source).await,
+                };
+
+                if skip_bad_records {
+                    iterator = iterator.with_skip_bad_records();
+                }
+
+                let mut result = Vec::new();
+                let mut checkpoints = vec![iterator.checkpoint()];
+
+                while let Some(record) = iterator.get_next_record().await {
+                    result.push(
+                        record
+                            .map(|record| (record.line, record.data))
+                            .map_err(|err| err.line),
+                    );
+                    checkpoints.push(iterator.checkpoint());
+                }
+
+                (result, checkpoints)
+            })
+        };
+
+        let (all, checkpoints) = read_all(None);
+
+        for (taken_after, checkpoint) in checkpoints.into_iter().enumerate().take(3) {
+            let (rest, _) = read_all(Some(checkpoint));
+            assert_eq!(rest, all[taken_after..].to_vec(), "{:?}", checkpoint);
+        }
+    }
+}
+
+/// A checkpoint in the middle of a record is rejected on resume, as the sync iterator rejects it,
+/// instead of reading the rest of the record as records of its own.
+#[test]
+fn json_l_checkpoint_off_a_record_boundary_is_rejected() {
+    use my_json::json_reader::{JsonLCheckpoint, JsonLIteratorAsync};
+
+    let json = b"{\"a\":12}\n{\"b\":2}";
+
+    for offset in [1, 5, 6, 7, 100] {
+        let checkpoint = JsonLCheckpoint { offset, line: 1 };
+        let result = block_on(JsonLIteratorAsync::resume(
+            MockAsyncIterator::new(json),
+            checkpoint,
+        ));
+
+        assert!(result.is_err(), "{}", offset);
+    }
+
+    let record = block_on(async {
+        let checkpoint = JsonLCheckpoint { offset: 8, line: 1 };
+        let mut resumed = JsonLIteratorAsync::resume(MockAsyncIterator::new(json), checkpoint)
+            .await
+            .unwrap();
+        resumed.get_next_record().await.unwrap().unwrap()
+    });
+
+    assert_eq!((record.line, record.data), (2, b"{\"b\":2}".to_vec()));
+}
+
+/// json-seq and concatenated JSON over an async source read the same values as the sync
+/// iterators, and a source that fails mid-sequence ends it with an error.
+#[test]
+fn json_seq_and_concatenated_values_match_the_sync_readers() {
+    use my_json::json_reader::{
+        JsonConcatIterator, JsonConcatIteratorAsync, JsonSeqIterator, JsonSeqIteratorAsync,
+    };
+    use rust_extensions::array_of_bytes_iterator::SliceIterator;
+
+    let seq = b"\x1E{\"a\":\n 1}\n\x1E\x1E[1,\x1E2\n\x1E\"s\"\n";
+
+    let mut sync_result = Vec::new();
+    let mut iterator = JsonSeqIterator::new(SliceIterator::new(seq));
+    while let Some(item) = iterator.get_next() {
+        sync_result.push(
+            item.map(|item| item.to_vec())
+                .map_err(|err| err.to_string()),
+        );
+    }
+
+    let async_result = block_on(async {
+        let mut iterator = JsonSeqIteratorAsync::new(MockAsyncIterator::new(seq)).await;
+        let mut result = Vec::new();
+        while let Some(item) = iterator.get_next().await {
+            result.push(item.map_err(|err| err.to_string()));
+        }
+        result
+    });
+
+    assert_eq!(async_result, sync_result);
+    assert_eq!(async_result.len(), 4);
+    assert!(async_result[1].is_err());
+    assert_eq!(async_result[3], Ok(b"\"s\"".to_vec()));
+
+    let concat = br#"{"a":1}[2]"s"3 {}"#;
+
+    let mut sync_result = Vec::new();
+    let mut iterator = JsonConcatIterator::new(SliceIterator::new(concat));
+    while let Some(item) = iterator.get_next() {
+        sync_result.push(item.unwrap().to_vec());
+    }
+
+    let async_result = block_on(async {
+        let mut iterator = JsonConcatIteratorAsync::new(MockAsyncIterator::new(concat)).await;
+        let mut result = Vec::new();
+        while let Some(item) = iterator.get_next().await {
+            result.push(item.unwrap());
+        }
+        result
+    });
+
+    assert_eq!(async_result, sync_result);
+    assert_eq!(async_result.len(), 5);
+
+    let result = block_on(async {
+        let mut iterator = JsonSeqIteratorAsync::new(MockAsyncIterator::failing_at(seq, 16)).await;
+        let mut result = Vec::new();
+        while let Some(item) = iterator.get_next().await {
+            result.push(item.is_ok());
+        }
+        result
+    });
+
+    // the broken `[1,` record, then the failed read, which ends the sequence
+    assert_eq!(result, vec![true, false, false]);
+}
+
+/// The array at a path reads the same elements and captures over an async source as over a
+/// slice, and a missing array is the same error on both.
+#[test]
+fn array_at_path_matches_the_sync_reader() {
+    use my_json::json_reader::{JsonArrayAtPathIterator, JsonArrayAtPathIteratorAsync};
+    use rust_extensions::array_of_bytes_iterator::SliceIterator;
+
+    let json = br#"{"meta":{"total":2,"page":1},"included":[{"data":[9]}],
+        "data":[{"id":1},{"id":2}],"next":"abc"}"#;
+
+    for (path, captures) in [
+        ("data", vec!["meta.total", "next"]),
+        ("meta.page", vec![]),
+        ("", vec![]),
+    ] {
+        let mut sync_result = Vec::new();
+        let mut iterator = JsonArrayAtPathIterator::new(SliceIterator::new(json), path);
+        for capture in &captures {
+            iterator = iterator.with_capture(capture);
+        }
+        while let Some(item) = iterator.get_next() {
+            sync_result.push(
+                item.map(|item| item.to_vec())
+                    .map_err(|err| err.to_string()),
+            );
+        }
+        let sync_captured: Vec<_> = captures
+            .iter()
+            .map(|capture| iterator.get_captured(capture).map(|value| value.to_vec()))
+            .collect();
+
+        let (async_result, async_captured) = block_on(async {
+            let mut iterator =
+                JsonArrayAtPathIteratorAsync::new(MockAsyncIterator::new(json), path).await;
+            for capture in &captures {
+                iterator = iterator.with_capture(capture);
+            }
+            let mut result = Vec::new();
+            while let Some(item) = iterator.get_next().await {
+                result.push(item.map_err(|err| err.to_string()));
+            }
+            let captured: Vec<_> = captures
+                .iter()
+                .map(|capture| iterator.get_captured(capture).map(|value| value.to_vec()))
+                .collect();
+            (result, captured)
+        });
+
+        assert_eq!(async_result, sync_result, "{}", path);
+        assert_eq!(async_captured, sync_captured, "{}", path);
+    }
+
+    let result = block_on(async {
+        let mut iterator = JsonArrayAtPathIteratorAsync::new(MockAsyncIterator::new(json), "data")
+            .await
+            .with_capture("meta.total")
+            .with_capture("next");
+        let mut ids = Vec::new();
+        while let Some(item) = iterator.get_next().await {
+            ids.push(item.unwrap());
+        }
+        (
+            ids,
+            iterator.get_captured("meta.total").map(|v| v.to_vec()),
+            iterator.get_captured("next").map(|v| v.to_vec()),
+        )
+    });
+
+    assert_eq!(
+        result,
+        (
+            vec![br#"{"id":1}"#.to_vec(), br#"{"id":2}"#.to_vec()],
+            Some(b"2".to_vec()),
+            Some(b"\"abc\"".to_vec())
+        )
+    );
+}
+
+/// The async object reader hands out the same fields as the sync one, de-escaped keys and raw
+/// values, and leaves out the ones its key filter rejects.
+#[test]
+fn first_line_reader_matches_the_sync_reader() {
+    use my_json::json_reader::{JsonFirstLineReaderAsync, JsonFirstLineReaderInner};
+    use rust_extensions::array_of_bytes_iterator::SliceIterator;
+
+    let json =
+        br#"{"id": 1, "na\"me": "x", "tags": ["a", {"b": 2}], "big": {"c": [1, 2, 3]}, "": null}"#;
+
+    let reader = JsonFirstLineReaderInner::new(SliceIterator::new(json));
+    let mut sync_result = Vec::new();
+    while let Some(item) = reader.get_next() {
+        let item = item.unwrap();
+        sync_result.push((
+            item.name.as_str(&reader).unwrap().as_str().to_string(),
+            item.value.as_bytes(&reader).to_vec(),
+        ));
+    }
+
+    let async_result = block_on(async {
+        let mut reader = JsonFirstLineReaderAsync::new(MockAsyncIterator::new(json)).await;
+        let mut result = Vec::new();
+        while let Some(item) = reader.get_next().await {
+            let item = item.unwrap();
+            result.push((item.name, item.value));
+        }
+        result
+    });
+
+    assert_eq!(async_result, sync_result);
+    assert_eq!(async_result[1].0, "na\"me");
+
+    let (names, id) = block_on(async {
+        let mut reader = JsonFirstLineReaderAsync::new(MockAsyncIterator::new(json))
+            .await
+            .with_key_filter(|name| name == "id" || name.is_empty());
+        let mut names = Vec::new();
+        let mut id = None;
+        while let Some(item) = reader.get_next().await {
+            let item = item.unwrap();
+            if item.name == "id" {
+                id = Some(item.read_value_as::<i64>().unwrap());
+            }
+            names.push(item.name);
+        }
+        (names, id)
+    });
+
+    assert_eq!(names, vec!["id".to_string(), "".to_string()]);
+    assert_eq!(id, Some(1));
+
+    let result = block_on(async {
+        let mut reader =
+            JsonFirstLineReaderAsync::new(MockAsyncIterator::new(br#"{"a": [1, "#)).await;
+        let mut result = Vec::new();
+        while let Some(item) = reader.get_next().await {
+            result.push(item.map(|item| item.name).map_err(|err| err.to_string()));
+        }
+        result
+    });
+
+    assert_eq!(result.len(), 1);
+    assert!(result[0].is_err());
+
+    let empty = block_on(async {
+        let mut reader = JsonFirstLineReaderAsync::new(MockAsyncIterator::new(b" { } ")).await;
+        reader.get_next().await.is_none()
+    });
+    assert!(empty);
+}
+
+/// The lending `get_next_ref` reads the same elements and records as `get_next`, errors
+/// included, and a slice the source can not hand out is an error rather than a panic.
+#[test]
+fn lending_readers_match_the_allocating_ones() {
+    use my_json::json_reader::JsonLIteratorAsync;
+
+    let array: &[u8] = br#"[{"a":"x,]"}, [1, [2]], "s", -1.5, true, null, {}]"#;
+    let broken: &[u8] = br#"[1, {"a": }]"#;
+
+    let sources: [fn() -> MockAsyncIterator; 3] = [
+        || MockAsyncIterator::new(br#"[{"a":"x,]"}, [1, [2]], "s", -1.5, true, null, {}]"#),
+        || MockAsyncIterator::new(br#"[1, {"a": }]"#),
+        || {
+            MockAsyncIterator::failing_at(
+                br#"[{"a":"x,]"}, [1, [2]], "s", -1.5, true, null, {}]"#,
+                20,
+            )
+        },
+    ];
+
+    for src in sources {
+        let lent = block_on(async {
+            let mut iterator = JsonArrayIteratorAsync::new(src()).await;
+            let mut result = Vec::new();
+            while let Some(item) = iterator.get_next_ref().await {
+                match item {
+                    Ok(bytes) => result.push(Ok(String::from_utf8_lossy(bytes).to_string())),
+                    Err(err) => {
+                        result.push(Err(err.to_string()));
+                        break;
+                    }
+                }
+            }
+            result
+        });
+
+        assert_eq!(lent, read_async_from(src()));
+    }
+
+    assert_eq!(read_async(array).len(), 7);
+    assert!(read_async(broken)[1].is_err());
+
+    let lines = b"{\"id\":1} 2\n[3,\n4]\n{bad\n\"five\"\n";
+
+    for skip_bad_records in [false, true] {
+        let (owned, lent) = block_on(async {
+            let mut owned = Vec::new();
+            let mut iterator = JsonLIteratorAsync::new(MockAsyncIterator::new(lines)).await;
+            if skip_bad_records {
+                iterator = iterator.with_skip_bad_records();
+            }
+            while let Some(record) = iterator.get_next_record().await {
+                owned.push(
+                    record
+                        .map(|record| (record.line, record.offset, record.data))
+                        .map_err(|err| (err.line, err.offset)),
+                );
+            }
+
+            let mut lent = Vec::new();
+            let mut iterator = JsonLIteratorAsync::new(MockAsyncIterator::new(lines)).await;
+            if skip_bad_records {
+                iterator = iterator.with_skip_bad_records();
+            }
+            while let Some(record) = iterator.get_next_record_ref().await {
+                lent.push(
+                    record
+                        .map(|record| (record.line, record.offset, record.data.to_vec()))
+                        .map_err(|err| (err.line, err.offset)),
+                );
+            }
+
+            (owned, lent)
+        });
+
+        assert_eq!(lent, owned, "skip_bad_records: {}", skip_bad_records);
+    }
+
+    let result = block_on(async {
+        let mut iterator =
+            JsonArrayIteratorAsync::new(MockAsyncIterator::failing_slices(b"[1]")).await;
+        iterator.get_next().await
+    });
+    assert!(result
+        .unwrap()
+        .unwrap_err()
+        .to_string()
+        .contains("slice released"));
+}
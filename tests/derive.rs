@@ -0,0 +1,500 @@
+//! `#[derive(JsonValueReader, JsonValueWriter)]` must hold the same contract as the hand-written
+//! impls: whatever the derived writer puts on the wire, the derived reader takes back unchanged.
+
+#![cfg(feature = "derive")]
+
+use my_json::json_reader::{JsonParseError, JsonValueReader, Lenient};
+use my_json::json_writer::{JsonNumberFormat, JsonValueWriter, JsonWriterOptions};
+use rust_extensions::StrOrString;
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::Arc;
+
+#[derive(Debug, PartialEq, Default, JsonValueReader, JsonValueWriter)]
+struct Address {
+    city: String,
+    zip: Option<String>,
+}
+
+#[derive(Debug, PartialEq, JsonValueReader, JsonValueWriter)]
+#[json(rename_all = "camelCase")]
+struct Customer {
+    customer_id: u64,
+    #[json(rename = "name")]
+    full_name: String,
+    tags: Vec<String>,
+    #[json(skip)]
+    cached_score: i32,
+    #[json(default = "default_limit")]
+    credit_limit: f64,
+    #[json(flatten)]
+    address: Address,
+    #[json(skip_serializing_if = "Option::is_none")]
+    referrer: Option<Address>,
+    #[json(default, skip_serializing_if = "Vec::is_empty")]
+    notes: Vec<String>,
+}
+
+fn default_limit() -> f64 {
+    100.0
+}
+
+#[derive(Debug, PartialEq, JsonValueReader, JsonValueWriter)]
+struct Envelope<T> {
+    r#type: String,
+    payload: T,
+}
+
+#[derive(Debug, PartialEq, JsonValueReader, JsonValueWriter)]
+struct Position {
+    symbol: Arc<str>,
+    levels: BTreeMap<u32, [f64; 2]>,
+    last: (String, u32),
+    parent: Option<Box<Position>>,
+    venues: BTreeSet<String>,
+}
+
+#[derive(Debug, PartialEq, JsonValueReader, JsonValueWriter)]
+struct LooseQuote {
+    #[json(lenient)]
+    qty: u32,
+    #[json(lenient)]
+    active: bool,
+    #[json(lenient)]
+    limit: Option<f64>,
+    price: Lenient<f64>,
+    #[json(lenient)]
+    note: Option<String>,
+}
+
+#[derive(JsonValueReader)]
+struct Borrowed<'s> {
+    name: StrOrString<'s>,
+}
+
+fn write<T: JsonValueWriter>(value: &T) -> String {
+    let mut result = String::new();
+    value.write(&mut result);
+    result
+}
+
+fn read<'s, T: JsonValueReader<'s>>(json: &'s str) -> Result<T, JsonParseError> {
+    let value = my_json::j_path::get_value(json.as_bytes(), "v")?.unwrap();
+    T::from_json_value(&value)
+}
+
+fn customer() -> Customer {
+    Customer {
+        customer_id: 7,
+        full_name: "Jane \"JD\" Doe".to_string(),
+        tags: vec!["vip".to_string()],
+        cached_score: 0,
+        credit_limit: 2.5,
+        address: Address {
+            city: "Oslo".to_string(),
+            zip: None,
+        },
+        referrer: Some(Address {
+            city: "Bergen".to_string(),
+            zip: Some("5003".to_string()),
+        }),
+        notes: vec![],
+    }
+}
+
+#[test]
+fn derived_writer_applies_every_attribute() {
+    let mut value = customer();
+    value.cached_score = 42;
+    value.referrer = None;
+
+    assert_eq!(
+        write(&value),
+        r#"{"customerId":7,"name":"Jane \"JD\" Doe","tags":["vip"],"creditLimit":2.5,"city":"Oslo","zip":null}"#
+    );
+}
+
+#[test]
+fn derived_reader_reads_back_what_the_writer_wrote() {
+    let value = customer();
+    let json = format!(r#"{{"v":{}}}"#, write(&value));
+
+    let result: Customer = read(&json).unwrap();
+    assert_eq!(result, value);
+
+    let envelope = Envelope {
+        r#type: "customer".to_string(),
+        payload: vec![customer()],
+    };
+    let json = format!(r#"{{"v":{}}}"#, write(&envelope));
+    assert!(json.contains(r#""type":"customer""#));
+
+    let result: Envelope<Vec<Customer>> = read(&json).unwrap();
+    assert_eq!(result, envelope);
+}
+
+#[test]
+fn derived_reader_falls_back_for_absent_keys() {
+    let result: Customer =
+        read(r#"{"v":{"customerId":3,"name":"N","tags":[],"city":"Rome","cachedScore":9,"extra":{"ignored":true}}}"#)
+            .unwrap();
+
+    assert_eq!(result.credit_limit, 100.0);
+    assert_eq!(result.cached_score, 0);
+    assert_eq!(result.address.zip, None);
+    assert_eq!(result.referrer, None);
+    assert!(result.notes.is_empty());
+
+    let err = read::<Customer>(r#"{"v":{"name":"N","tags":[],"city":"Rome"}}"#).unwrap_err();
+    assert!(err.to_string().contains("customerId"));
+
+    let err = read::<Customer>(r#"{"v":[1,2]}"#).unwrap_err();
+    assert!(err.to_string().contains("Customer"));
+}
+
+#[test]
+fn derived_reader_borrows_from_the_document() {
+    let result: Borrowed = read(r#"{"v":{"name":"plain"}}"#).unwrap();
+    assert_eq!(result.name.as_str(), "plain");
+}
+
+#[test]
+fn derived_writer_honours_writer_options() {
+    let value = Address {
+        city: "Zürich".to_string(),
+        zip: Some("8001".to_string()),
+    };
+
+    let options = JsonWriterOptions::new()
+        .with_number_format(JsonNumberFormat::new())
+        .with_escape_mode(my_json::json_writer::JsonEscapeMode::ASCII_ONLY);
+
+    let mut result = String::new();
+    value.write_with_options(&mut result, &options);
+
+    assert_eq!(result, r#"{"city":"Z\u00fcrich","zip":"8001"}"#);
+}
+
+#[derive(Debug, PartialEq, JsonValueReader, JsonValueWriter)]
+#[json(rename_all = "snake_case")]
+enum Side {
+    Buy,
+    Sell,
+    #[json(rename = "short")]
+    SellShort,
+}
+
+#[derive(Debug, PartialEq, JsonValueReader, JsonValueWriter)]
+enum External {
+    Ping,
+    Order(Address),
+    Pair(u32, Vec<u32>),
+    Cancel {
+        order_id: u64,
+        reason: Option<String>,
+    },
+}
+
+#[derive(Debug, PartialEq, JsonValueReader, JsonValueWriter)]
+#[json(tag = "type", rename_all = "snake_case")]
+enum Internal {
+    Heartbeat,
+    Order(Address),
+    #[json(rename_all = "camelCase")]
+    Cancel {
+        order_id: u64,
+        side: Side,
+    },
+}
+
+#[derive(Debug, PartialEq, JsonValueReader, JsonValueWriter)]
+#[json(tag = "type", content = "payload", rename_all = "lowercase")]
+enum Adjacent {
+    Heartbeat,
+    Order(Address),
+    Fill(u64, f64),
+    // Not an object, so it needs `content` to carry the tag.
+    Qty(u32),
+    Cancel { order_id: u64 },
+}
+
+#[derive(Debug, PartialEq, JsonValueReader, JsonValueWriter)]
+#[json(untagged)]
+enum Untagged {
+    Nothing,
+    Number(i64),
+    Pair(String, bool),
+    Order(Address),
+    Text(String),
+}
+
+fn round_trip<T>(value: &T, expected: &str)
+where
+    T: JsonValueWriter + for<'s> JsonValueReader<'s> + PartialEq + std::fmt::Debug,
+{
+    let json = write(value);
+    assert_eq!(json, expected);
+
+    let json = format!(r#"{{"v":{}}}"#, json);
+    let result: T = read(&json).unwrap();
+    assert_eq!(&result, value);
+}
+
+#[test]
+fn unit_enums_are_strings() {
+    round_trip(&Side::Buy, r#""buy""#);
+    round_trip(&Side::SellShort, r#""short""#);
+    round_trip(
+        &Envelope {
+            r#type: "sides".to_string(),
+            payload: vec![Side::Sell, Side::Buy],
+        },
+        r#"{"type":"sides","payload":["sell","buy"]}"#,
+    );
+
+    let err = read::<Side>(r#"{"v":"hold"}"#).unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("Unknown variant 'hold' of enum Side"));
+    assert!(err.to_string().contains("buy, sell, short"));
+}
+
+#[test]
+fn externally_tagged_enums() {
+    round_trip(&External::Ping, r#""Ping""#);
+    round_trip(
+        &External::Order(Address {
+            city: "Oslo".to_string(),
+            zip: None,
+        }),
+        r#"{"Order":{"city":"Oslo","zip":null}}"#,
+    );
+    round_trip(&External::Pair(1, vec![2, 3]), r#"{"Pair":[1,[2,3]]}"#);
+    round_trip(
+        &External::Cancel {
+            order_id: 5,
+            reason: Some("late".to_string()),
+        },
+        r#"{"Cancel":{"order_id":5,"reason":"late"}}"#,
+    );
+
+    let err = read::<External>(r#"{"v":{"Refund":{}}}"#).unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("Unknown variant 'Refund' of enum External"));
+
+    let err = read::<External>(r#"{"v":{"Ping":null,"Order":{}}}"#).unwrap_err();
+    assert!(err.to_string().contains("exactly one key"));
+
+    let err = read::<External>(r#"{"v":{"Pair":[1]}}"#).unwrap_err();
+    assert!(err.to_string().contains("array of 2 elements"));
+}
+
+#[test]
+fn internally_tagged_enums() {
+    round_trip(&Internal::Heartbeat, r#"{"type":"heartbeat"}"#);
+    round_trip(
+        &Internal::Order(Address {
+            city: "Oslo".to_string(),
+            zip: Some("0150".to_string()),
+        }),
+        r#"{"type":"order","city":"Oslo","zip":"0150"}"#,
+    );
+    round_trip(
+        &Internal::Cancel {
+            order_id: 9,
+            side: Side::SellShort,
+        },
+        r#"{"type":"cancel","orderId":9,"side":"short"}"#,
+    );
+
+    // the tag does not have to come first
+    let result: Internal = read(r#"{"v":{"orderId":1,"side":"buy","type":"cancel"}}"#).unwrap();
+    assert_eq!(
+        result,
+        Internal::Cancel {
+            order_id: 1,
+            side: Side::Buy
+        }
+    );
+
+    let err = read::<Internal>(r#"{"v":{"type":"refund"}}"#).unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("Unknown variant 'refund' of enum Internal"));
+
+    let err = read::<Internal>(r#"{"v":{"orderId":1}}"#).unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("Tag field 'type' of enum Internal is missing"));
+}
+
+#[test]
+fn adjacently_tagged_enums() {
+    round_trip(&Adjacent::Heartbeat, r#"{"type":"heartbeat"}"#);
+    round_trip(
+        &Adjacent::Order(Address {
+            city: "Oslo".to_string(),
+            zip: None,
+        }),
+        r#"{"type":"order","payload":{"city":"Oslo","zip":null}}"#,
+    );
+    round_trip(
+        &Adjacent::Fill(3, 1.5),
+        r#"{"type":"fill","payload":[3,1.5]}"#,
+    );
+    round_trip(&Adjacent::Qty(5), r#"{"type":"qty","payload":5}"#);
+    round_trip(
+        &Adjacent::Cancel { order_id: 4 },
+        r#"{"type":"cancel","payload":{"order_id":4}}"#,
+    );
+
+    let err = read::<Adjacent>(r#"{"v":{"type":"fill"}}"#).unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("Content field 'payload' of variant 'fill'"));
+}
+
+#[test]
+fn untagged_enums() {
+    round_trip(&Untagged::Nothing, "null");
+    round_trip(&Untagged::Number(-3), "-3");
+    round_trip(&Untagged::Pair("a".to_string(), true), r#"["a",true]"#);
+    round_trip(
+        &Untagged::Order(Address {
+            city: "Oslo".to_string(),
+            zip: None,
+        }),
+        r#"{"city":"Oslo","zip":null}"#,
+    );
+    round_trip(&Untagged::Text("x".to_string()), r#""x""#);
+
+    let err = read::<Untagged>(r#"{"v":[1,2,3]}"#).unwrap_err();
+    assert!(err.to_string().contains("untagged enum Untagged"));
+}
+
+#[test]
+fn derived_readers_report_where_the_error_is() {
+    let err = read::<Envelope<Vec<Customer>>>(
+        r#"{"v":{"type":"t","payload":[{"customerId":1,"name":"a","tags":[],"city":"c"},{"customerId":2,"name":"b","tags":["x",5],"city":"c"}]}}"#,
+    )
+    .unwrap_err();
+    assert_eq!(err.path().unwrap(), "$.payload[1].tags[1]");
+
+    let err = read::<Customer>(r#"{"v":{"name":"N","tags":[],"city":"Rome"}}"#).unwrap_err();
+    assert_eq!(err.path().unwrap(), "$.customerId");
+
+    let err = read::<External>(r#"{"v":{"Pair":[1,["x"]]}}"#).unwrap_err();
+    assert_eq!(err.path().unwrap(), "$.Pair[1][0]");
+
+    let err =
+        read::<Adjacent>(r#"{"v":{"type":"cancel","payload":{"order_id":"x"}}}"#).unwrap_err();
+    assert_eq!(err.path().unwrap(), "$.payload.order_id");
+}
+
+#[test]
+fn derived_fields_can_use_std_collections_and_pointers() {
+    let value = Position {
+        symbol: "EURUSD".into(),
+        levels: BTreeMap::from([(1, [1.5, 100.0]), (2, [1.25, 50.0])]),
+        last: ("fill".to_string(), 3),
+        parent: Some(Box::new(Position {
+            symbol: "EUR".into(),
+            levels: BTreeMap::new(),
+            last: (String::new(), 0),
+            parent: None,
+            venues: BTreeSet::new(),
+        })),
+        venues: BTreeSet::from(["lmax".to_string()]),
+    };
+
+    round_trip(
+        &value,
+        r#"{"symbol":"EURUSD","levels":{"1":[1.5,100],"2":[1.25,50]},"last":["fill",3],"parent":{"symbol":"EUR","levels":{},"last":["",0],"parent":null,"venues":[]},"venues":["lmax"]}"#,
+    );
+}
+
+#[derive(Debug, PartialEq, JsonValueReader, JsonValueWriter)]
+enum Forward {
+    To(Option<Address>),
+    Pair(Option<Address>, Option<Vec<u32>>),
+}
+
+#[derive(Debug, PartialEq, JsonValueReader, JsonValueWriter)]
+struct Route {
+    from: Option<Address>,
+    stops: Option<Vec<u32>>,
+}
+
+#[derive(JsonValueWriter)]
+struct Stamped {
+    id: u32,
+    #[json(flatten)]
+    address: Option<Address>,
+}
+
+#[test]
+fn derived_writers_write_an_option_of_any_type() {
+    let oslo = || Address {
+        city: "Oslo".to_string(),
+        zip: None,
+    };
+
+    round_trip(&Forward::To(None), r#"{"To":null}"#);
+    round_trip(
+        &Forward::Pair(Some(oslo()), Some(vec![1, 2])),
+        r#"{"Pair":[{"city":"Oslo","zip":null},[1,2]]}"#,
+    );
+    round_trip(&Forward::Pair(None, None), r#"{"Pair":[null,null]}"#);
+
+    round_trip(
+        &Route {
+            from: Some(oslo()),
+            stops: Some(vec![3]),
+        },
+        r#"{"from":{"city":"Oslo","zip":null},"stops":[3]}"#,
+    );
+    round_trip(
+        &Route {
+            from: None,
+            stops: None,
+        },
+        r#"{"from":null,"stops":null}"#,
+    );
+
+    let stamped = Stamped {
+        id: 1,
+        address: Some(oslo()),
+    };
+    assert_eq!(write(&stamped), r#"{"id":1,"city":"Oslo","zip":null}"#);
+
+    let stamped = Stamped {
+        id: 1,
+        address: None,
+    };
+    assert_eq!(write(&stamped), r#"{"id":1}"#);
+}
+
+#[test]
+fn lenient_fields_coerce_loose_values_and_write_canonical_ones() {
+    let value: LooseQuote =
+        read(r#"{"v":{"qty":"12","active":1,"limit":"","price":"1.5","note":""}}"#).unwrap();
+
+    assert_eq!(
+        value,
+        LooseQuote {
+            qty: 12,
+            active: true,
+            limit: None,
+            price: Lenient(1.5),
+            note: None,
+        }
+    );
+
+    round_trip(
+        &value,
+        r#"{"qty":12,"active":true,"limit":null,"price":1.5,"note":null}"#,
+    );
+
+    let err = read::<LooseQuote>(r#"{"v":{"qty":"x","active":1,"price":1}}"#).unwrap_err();
+    assert_eq!(err.path().unwrap(), "$.qty");
+}
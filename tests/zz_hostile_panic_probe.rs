@@ -1,45 +1,61 @@
 use my_json::json_reader::JsonArrayIterator;
 
 #[test]
-fn probe_unwrap_as_number_panics_on_double() {
+fn probe_unwrap_as_number_errs_on_double() {
     let json = br#"{"v":1.5}"#;
     let v = my_json::j_path::get_value(json, "v").unwrap().unwrap();
-    let r = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
-        let _ = v.unwrap_as_number();
-    }));
-    assert!(r.is_err(), "expected panic on Double via unwrap_as_number");
+    assert!(v.unwrap_as_number().is_err());
 }
 
 #[test]
-fn probe_unwrap_as_number_panics_on_null() {
+fn probe_unwrap_as_number_is_none_on_null() {
     let json = br#"{"v":null}"#;
     let v = my_json::j_path::get_value(json, "v").unwrap().unwrap();
-    let r = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
-        let _ = v.unwrap_as_number();
-    }));
-    assert!(r.is_err(), "expected panic on null via unwrap_as_number");
+    assert_eq!(v.unwrap_as_number().unwrap(), None);
 }
 
 #[test]
-fn probe_into_array_iterator_panics_on_empty() {
-    let r = std::panic::catch_unwind(|| {
-        let _it: JsonArrayIterator = b"".as_slice().into();
-    });
-    assert!(r.is_err(), "expected panic building JsonArrayIterator from empty bytes");
+fn probe_into_array_iterator_errs_on_empty() {
+    let it: JsonArrayIterator = b"".as_slice().into();
+    assert!(it.get_next().unwrap().is_err());
+    assert!(JsonArrayIterator::new(b"").is_err());
 }
 
 #[test]
-fn probe_into_array_iterator_panics_on_whitespace() {
-    let r = std::panic::catch_unwind(|| {
-        let _it: JsonArrayIterator = b"   ".as_slice().into();
-    });
-    assert!(r.is_err(), "expected panic building JsonArrayIterator from whitespace");
+fn probe_into_array_iterator_errs_on_whitespace() {
+    let it: JsonArrayIterator = b"   ".as_slice().into();
+    assert!(it.get_next().unwrap().is_err());
+    assert!(JsonArrayIterator::new(b"   ").is_err());
 }
 
 #[test]
-fn probe_j_update_panics_on_malformed() {
-    let r = std::panic::catch_unwind(|| {
-        let _ = my_json::j_path::j_update("{\"a\"", "x", "y");
-    });
-    assert!(r.is_err(), "expected panic in j_update on malformed json (next.unwrap())");
+fn probe_j_update_errs_on_malformed() {
+    assert!(my_json::j_path::j_update("{\"a\"", "x", "y").is_err());
+}
+
+#[test]
+fn probe_json_l_iterator_accepts_empty_payload() {
+    use my_json::json_reader::JsonLIterator;
+    use rust_extensions::array_of_bytes_iterator::SliceIterator;
+
+    let mut it = JsonLIterator::new(SliceIterator::new(b""));
+    assert!(it.get_next().is_none());
+
+    let mut it = JsonLIterator::new(SliceIterator::new(b" \n "));
+    assert!(it.get_next().is_none());
+}
+
+#[test]
+fn probe_raw_value_helpers_err_on_bad_tokens() {
+    use my_json::json_reader::bytes_of_array_reader::sync_reader;
+    use rust_extensions::array_of_bytes_iterator::SliceIterator;
+
+    let src = SliceIterator::new(b"x");
+    assert!(sync_reader::find_the_end_of_the_object_value(&src, b'x').is_err());
+
+    let src = SliceIterator::new(b"x");
+    assert!(sync_reader::find_the_end_of_json_object_or_array(&src).is_err());
+
+    let src = SliceIterator::new(b"");
+    assert!(sync_reader::find_the_end_of_array(&src).is_err());
 }